@@ -104,6 +104,13 @@ impl ConfigLoader {
                 }.into());
             }
 
+            if !config.security.allows_relay_url(&builder.relay_url) {
+                return Err(ConfigError::ValidationError {
+                    field: "builders.relay_url".to_string(),
+                    message: format!("Relay URL for builder {} is not in security.relay_url_allowlist: {}", builder.name, builder.relay_url),
+                }.into());
+            }
+
             if builder.payment_address.is_empty() {
                 return Err(ConfigError::ValidationError {
                     field: "builders.payment_address".to_string(),
@@ -259,6 +266,8 @@ impl ConfigLoader {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::schema::LoggingConfig;
+    use alloy::primitives::U256;
     use tempfile::NamedTempFile;
 
     #[test]
@@ -301,6 +310,42 @@ builders:
         assert_eq!(config.builders[0].name, "test_builder");
     }
 
+    #[test]
+    fn test_amounts_with_units_parsed_to_wei() {
+        let yaml_content = r#"
+network:
+  network: "mainnet"
+targets:
+  blocks_ahead: 2
+  resubmit_max: 2
+payment:
+  formula: "flat"
+  k1: 1.0
+  k2: "200000 gwei"
+  max_amount_wei: "0.0005 eth"
+  per_bundle_cap_wei: "2000000000000000"
+  daily_cap_wei: "0.5 ether"
+limits:
+  per_bundle_cap_wei: "0.002 eth"
+  daily_cap_wei: "0.5 eth"
+  emergency_stop_threshold_wei: "1000000 gwei"
+builders:
+  - name: "test_builder"
+    relay_url: "https://test.relay.com"
+    payment_address: "0x1234567890123456789012345678901234567890"
+"#;
+
+        let config = ConfigLoader::load_from_str(yaml_content).unwrap();
+        assert_eq!(config.payment.k2, U256::from(200_000_000_000_000u64));
+        assert_eq!(config.payment.max_amount_wei, U256::from(500_000_000_000_000u64));
+        assert_eq!(config.payment.daily_cap_wei, U256::from(500_000_000_000_000_000u64));
+
+        let limits = config.parse_limits().unwrap();
+        assert_eq!(limits.per_bundle_cap_wei, U256::from(2_000_000_000_000_000u64));
+        assert_eq!(limits.daily_cap_wei, U256::from(500_000_000_000_000_000u64));
+        assert_eq!(limits.emergency_stop_threshold_wei, U256::from(1_000_000_000_000_000u64));
+    }
+
     #[test]
     fn test_validation_errors() {
         // Test empty network
@@ -326,6 +371,47 @@ builders:
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_relay_url_outside_allowlist_fails_to_load() {
+        let yaml_content = r#"
+network:
+  network: "mainnet"
+builders:
+  - name: "internal"
+    relay_url: "http://127.0.0.1:8080/admin"
+    payment_address: "0x1234567890123456789012345678901234567890"
+    enabled: true
+security:
+  relay_url_allowlist: ["*.flashbots.net"]
+"#;
+        let err = ConfigLoader::load_from_str(yaml_content).unwrap_err();
+        assert!(format!("{:#}", err).contains("relay_url_allowlist"));
+
+        let allowed = yaml_content.replace("http://127.0.0.1:8080/admin", "https://relay.flashbots.net");
+        assert!(ConfigLoader::load_from_str(&allowed).is_ok());
+    }
+
+    #[test]
+    fn test_startup_summary_redacts_secrets() {
+        let mut config = ConfigLoader::default();
+        config.security.admin_api_key = Some("super-secret-admin-key".to_string());
+
+        let summary = config.summary().to_string();
+        assert!(summary.contains("payment_formula=basefee"));
+        assert!(summary.contains("builder_count=1"));
+        assert!(!summary.contains("super-secret-admin-key"));
+    }
+
+    #[test]
+    fn test_log_hash_sanitized_when_enabled() {
+        let hash = "0x8f2a4b6c8d0e1f2a3b4c5d6e7f8091a2b3c4d5e6f708192a3b4c5d6e7f8091a2";
+        let mut logging = LoggingConfig::default();
+        assert_eq!(logging.log_hash(hash), hash);
+
+        logging.sanitize_hashes = true;
+        assert_eq!(logging.log_hash(hash), "0x8f2a...91a2");
+    }
+
     #[test]
     fn test_create_example() {
         let temp_file = NamedTempFile::new().unwrap();
@@ -336,4 +422,31 @@ builders:
         assert!(content.contains("network:"));
         assert!(content.contains("builders:"));
     }
+
+    #[test]
+    fn test_redacted_yaml_round_trips() {
+        let mut config = ConfigLoader::default();
+        config.network.rpc_url = Some("https://eth-mainnet.example/v2/provider-secret".to_string());
+        config.security.admin_api_key = Some("admin-secret".to_string());
+        config.security.api_tokens.insert("searcher_a".to_string(), "token-secret".to_string());
+        config.builders[0].enabled = false;
+        let mut second_builder = config.builders[0].clone();
+        second_builder.name = "second".to_string();
+        second_builder.enabled = true;
+        config.builders.push(second_builder);
+
+        let yaml = config.to_redacted_yaml().unwrap();
+        for secret in ["provider-secret", "admin-secret", "token-secret"] {
+            assert!(!yaml.contains(secret));
+        }
+
+        let exported = ConfigLoader::load_from_str(&yaml).unwrap();
+        assert_eq!(exported.security.admin_api_key.as_deref(), Some("<redacted>"));
+        assert_eq!(exported.security.api_tokens["searcher_a"], "<redacted>");
+        assert_eq!(exported.network.rpc_url.as_deref(), Some("<redacted>"));
+        assert!(!exported.builders[0].enabled);
+        assert!(exported.builders[1].enabled);
+        assert_eq!(exported.payment.k2, config.payment.k2);
+        assert_eq!(exported.to_redacted_yaml().unwrap(), yaml);
+    }
 }
@@ -2,7 +2,8 @@
 
 use alloy::primitives::{Address, U256};
 use serde::{Deserialize, Serialize};
-use types::{BuilderRelay, PaymentConfig};
+use std::collections::HashMap;
+use types::{BuilderRelay, PaymentConfig, RelayHttpVersion, RelaySignatureScheme, RequestIdFormat};
 
 /// Main configuration structure
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -43,6 +44,17 @@ pub struct NetworkConfig {
     pub rpc_url: Option<String>,
     /// Chain ID
     pub chain_id: Option<u64>,
+    /// Reject forging when the RPC's latest block is older than this many seconds (optional)
+    pub max_block_staleness_seconds: Option<u64>,
+    /// At startup, warn when the local clock and the RPC's latest block timestamp differ by more
+    /// than this many seconds (optional)
+    pub max_clock_drift_seconds: Option<u64>,
+    /// Refuse to start, rather than warn, when the clock drift check fails
+    #[serde(default)]
+    pub fail_on_clock_drift: bool,
+    /// Timeout for background RPC requests such as batched receipt lookups, in seconds
+    #[serde(default = "default_rpc_timeout_seconds")]
+    pub rpc_timeout_seconds: u64,
 }
 
 /// Target block configuration
@@ -55,6 +67,53 @@ pub struct TargetConfig {
     /// Bundle expiry time in seconds
     #[serde(default = "default_bundle_expiry_seconds")]
     pub bundle_expiry_seconds: u64,
+    /// Minimum time between resubmissions of the same bundle in seconds
+    #[serde(default = "default_resubmit_interval_seconds")]
+    pub resubmit_interval_seconds: u64,
+    /// Furthest a bundle's `payment.expiry` may lie in the future, in seconds
+    #[serde(default = "default_max_payment_expiry_seconds")]
+    pub max_payment_expiry_seconds: u64,
+    /// Bundles still `sent` this many seconds after submission are marked `failed`
+    #[serde(default = "default_stuck_bundle_timeout_seconds")]
+    pub stuck_bundle_timeout_seconds: u64,
+    /// How the scheduler detects that a sent bundle landed
+    #[serde(default)]
+    pub inclusion_strategy: InclusionStrategy,
+    /// Most inclusion checks the scheduler runs at once
+    #[serde(default = "default_inclusion_check_concurrency")]
+    pub inclusion_check_concurrency: usize,
+    /// Receipts requested per JSON-RPC batch; 1 requests each receipt separately
+    #[serde(default = "default_receipt_batch_size")]
+    pub receipt_batch_size: usize,
+    /// What to do when no target block was requested and the next block can't be fetched
+    #[serde(default)]
+    pub unknown_target_block: UnknownTargetBlock,
+    /// Cap on relay retries summed across all builders for one submission (optional)
+    pub bundle_retry_budget: Option<u32>,
+}
+
+/// Handling of submissions whose target block can't be determined
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UnknownTargetBlock {
+    /// Fail the submission
+    #[default]
+    Reject,
+    /// Submit without a `blockNumber` and let the relay pick
+    Omit,
+}
+
+/// Source used to detect bundle inclusion
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InclusionStrategy {
+    /// Poll the RPC for a tx1 receipt
+    #[default]
+    Receipt,
+    /// Query each builder's `status_url` bundle stats
+    RelayStats,
+    /// Landed if either source reports inclusion
+    Both,
 }
 
 /// Spending limits configuration
@@ -72,6 +131,9 @@ pub struct LimitsConfig {
     /// Emergency stop threshold in wei
     #[serde(default = "default_emergency_threshold")]
     pub emergency_stop_threshold_wei: String,
+    /// Payment ceiling in wei applied to every bundle while safe mode is active
+    #[serde(default = "default_safe_mode_ceiling")]
+    pub safe_mode_ceiling_wei: String,
 }
 
 /// Builder configuration
@@ -94,9 +156,66 @@ pub struct BuilderConfig {
     /// Maximum retries
     #[serde(default = "default_max_retries")]
     pub max_retries: u32,
+    /// Upper bound on the delay between retries in milliseconds
+    #[serde(default = "default_max_backoff_ms")]
+    pub max_backoff_ms: u64,
     /// Health check interval in seconds
     #[serde(default = "default_health_check_interval")]
     pub health_check_interval_seconds: u64,
+    /// JSON pointer to the bundle hash in the relay's response (e.g. "/result/data/hash")
+    pub response_result_path: Option<String>,
+    /// Send the bundle's replacement UUID as `uuid` in eth_sendBundle (required by some relays)
+    #[serde(default)]
+    pub send_bundle_uuid: bool,
+    /// Send `network.chain_id` as `chainId` in eth_sendBundle (required by some relays)
+    #[serde(default)]
+    pub send_chain_id: bool,
+    /// Bundle API version sent as `version` in eth_sendBundle params (newer Flashbots APIs)
+    #[serde(default)]
+    pub bundle_version: Option<String>,
+    /// Headers the relay must return on submission (guards against hijacked relay URLs)
+    #[serde(default)]
+    pub expected_response_headers: HashMap<String, String>,
+    /// Fail submissions on header mismatch instead of only warning
+    #[serde(default)]
+    pub strict_response_headers: bool,
+    /// JSON-RPC id encoding: `numeric` (default) or `string` for relays that reject numbers
+    #[serde(default)]
+    pub request_id_format: RequestIdFormat,
+    /// Maximum idle HTTP connections kept open to the relay
+    #[serde(default = "default_pool_max_idle_per_host")]
+    pub pool_max_idle_per_host: usize,
+    /// Seconds an idle pooled connection is kept before closing (0 = never expire)
+    #[serde(default = "default_pool_idle_timeout_seconds")]
+    pub pool_idle_timeout_seconds: u64,
+    /// TCP keepalive interval in seconds (0 = disabled)
+    #[serde(default = "default_tcp_keepalive_seconds")]
+    pub tcp_keepalive_seconds: u64,
+    /// Largest relay response body read before the submission fails
+    #[serde(default = "default_max_relay_response_bytes")]
+    pub max_relay_response_bytes: usize,
+    /// `auto` (HTTP/2 via ALPN, HTTP/1.1 fallback), `http1`, or `http2` (prior knowledge)
+    #[serde(default)]
+    pub http_version: RelayHttpVersion,
+    /// Environment variable holding the key that signs relay requests (unsigned if unset)
+    pub auth_key_env: Option<String>,
+    /// `eip191` (personal-sign of the body hash, Flashbots) or `raw_keccak` (sign the body hash)
+    #[serde(default)]
+    pub signature_scheme: RelaySignatureScheme,
+    /// UTC hour windows during which this builder is submitted to (empty = always)
+    #[serde(default)]
+    pub active_hours_utc: Vec<HourWindow>,
+    /// Name of the `payment.signers` entry that funds this builder's tx2 (default signer if unset)
+    pub signer_key_ref: Option<String>,
+}
+
+/// Half-open UTC hour range `[start_hour, end_hour)`; wraps past midnight when start > end
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HourWindow {
+    /// First active hour (0-23)
+    pub start_hour: u32,
+    /// First inactive hour (0-24)
+    pub end_hour: u32,
 }
 
 /// HTTP server configuration
@@ -117,6 +236,27 @@ pub struct ServerConfig {
     /// Enable CORS
     #[serde(default = "default_true")]
     pub cors_enabled: bool,
+    /// Enable debugging endpoints such as /debug/decode
+    #[serde(default)]
+    pub debug_endpoints_enabled: bool,
+    /// Maximum number of bundles waiting for background submission
+    #[serde(default = "default_submission_queue_capacity")]
+    pub submission_queue_capacity: usize,
+    /// Number of background submission workers
+    #[serde(default = "default_submission_workers")]
+    pub submission_workers: usize,
+    /// Forge and log bundles but never send them to relays (shadow deployments)
+    #[serde(default)]
+    pub observe_only: bool,
+    /// Echo each submission's ordered raw transactions as `bundleTxs` in bundle responses
+    #[serde(default)]
+    pub include_bundle_txs: bool,
+    /// How long a bundle status lookup is served from memory, in milliseconds (0 = disabled)
+    #[serde(default = "default_status_cache_ttl_ms")]
+    pub status_cache_ttl_ms: u64,
+    /// Maximum number of bundle statuses kept in memory
+    #[serde(default = "default_status_cache_capacity")]
+    pub status_cache_capacity: usize,
 }
 
 /// Database configuration
@@ -134,6 +274,27 @@ pub struct DatabaseConfig {
     /// Enable WAL mode for SQLite
     #[serde(default = "default_true")]
     pub wal_mode: bool,
+    /// How long a connection waits on a locked database before failing, in milliseconds
+    #[serde(default = "default_busy_timeout_ms")]
+    pub busy_timeout_ms: u64,
+    /// SQLite `synchronous` pragma
+    #[serde(default)]
+    pub synchronous: SqliteSynchronous,
+    /// Enforce foreign key constraints
+    #[serde(default = "default_true")]
+    pub foreign_keys: bool,
+}
+
+/// SQLite `synchronous` pragma setting
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SqliteSynchronous {
+    Off,
+    /// Safe with WAL mode and avoids an fsync on every commit
+    #[default]
+    Normal,
+    Full,
+    Extra,
 }
 
 /// Logging configuration
@@ -153,6 +314,9 @@ pub struct LoggingConfig {
     /// Enable SQL query logging
     #[serde(default = "default_false")]
     pub sql_logging: bool,
+    /// Shorten transaction hashes in logs via `sanitize_for_logging`
+    #[serde(default = "default_false")]
+    pub sanitize_hashes: bool,
 }
 
 /// Metrics configuration
@@ -177,6 +341,9 @@ pub struct MetricsConfig {
 pub struct SecurityConfig {
     /// Admin API key
     pub admin_api_key: Option<String>,
+    /// Submitter name -> bearer token, used to attribute bundle spend per submitter
+    #[serde(default)]
+    pub api_tokens: HashMap<String, String>,
     /// Enable rate limiting
     #[serde(default = "default_true")]
     pub rate_limiting_enabled: bool,
@@ -189,6 +356,14 @@ pub struct SecurityConfig {
     /// Enable killswitch
     #[serde(default = "default_true")]
     pub killswitch_enabled: bool,
+    /// Automatically deactivate the killswitch this many seconds after activation (optional)
+    pub killswitch_auto_deactivate_seconds: Option<u64>,
+    /// Return internal error details (RPC and database errors) to API clients; they are always logged
+    #[serde(default)]
+    pub verbose_errors: bool,
+    /// Hosts builder relay URLs may point at: exact hosts or `*.domain` subdomain patterns (empty = any)
+    #[serde(default)]
+    pub relay_url_allowlist: Vec<String>,
 }
 
 // Default value functions
@@ -196,6 +371,30 @@ fn default_bundle_expiry_seconds() -> u64 {
     300 // 5 minutes
 }
 
+fn default_resubmit_interval_seconds() -> u64 {
+    12 // one slot
+}
+
+fn default_max_payment_expiry_seconds() -> u64 {
+    3600 // 1 hour
+}
+
+fn default_stuck_bundle_timeout_seconds() -> u64 {
+    600 // 10 minutes
+}
+
+fn default_rpc_timeout_seconds() -> u64 {
+    10
+}
+
+fn default_inclusion_check_concurrency() -> usize {
+    8
+}
+
+fn default_receipt_batch_size() -> usize {
+    50
+}
+
 fn default_true() -> bool {
     true
 }
@@ -205,7 +404,11 @@ fn default_false() -> bool {
 }
 
 fn default_emergency_threshold() -> String {
-    "100000000000000000".to_string() // 0.1 ETH
+    "1000000000000000".to_string() // 0.001 ETH, below the default per-bundle cap
+}
+
+fn default_safe_mode_ceiling() -> String {
+    "10000000000000".to_string() // 0.00001 ETH
 }
 
 fn default_timeout_seconds() -> u64 {
@@ -216,6 +419,26 @@ fn default_max_retries() -> u32 {
     3
 }
 
+fn default_max_backoff_ms() -> u64 {
+    2_000
+}
+
+fn default_pool_max_idle_per_host() -> usize {
+    32
+}
+
+fn default_pool_idle_timeout_seconds() -> u64 {
+    90
+}
+
+fn default_tcp_keepalive_seconds() -> u64 {
+    60
+}
+
+fn default_max_relay_response_bytes() -> usize {
+    1024 * 1024
+}
+
 fn default_health_check_interval() -> u64 {
     60
 }
@@ -236,6 +459,22 @@ fn default_max_body_size() -> usize {
     1024 * 1024 // 1MB
 }
 
+fn default_submission_queue_capacity() -> usize {
+    1000
+}
+
+fn default_submission_workers() -> usize {
+    4
+}
+
+fn default_status_cache_ttl_ms() -> u64 {
+    2_000
+}
+
+fn default_status_cache_capacity() -> usize {
+    1_024
+}
+
 fn default_database_url() -> String {
     "sqlite:data/atomic_bundler.db".to_string()
 }
@@ -248,6 +487,10 @@ fn default_connection_timeout() -> u64 {
     30
 }
 
+fn default_busy_timeout_ms() -> u64 {
+    5_000
+}
+
 fn default_log_level() -> String {
     "info".to_string()
 }
@@ -276,47 +519,137 @@ fn default_rate_limit_burst() -> u32 {
     20
 }
 
+impl LoggingConfig {
+    /// Format a transaction hash for logging, sanitized when `sanitize_hashes` is set
+    pub fn log_hash(&self, hash: &str) -> String {
+        if self.sanitize_hashes {
+            types::utils::sanitize_for_logging(hash)
+        } else {
+            hash.to_string()
+        }
+    }
+}
+
+impl HourWindow {
+    /// Whether `hour` (0-23) falls inside this window
+    pub fn contains(&self, hour: u32) -> bool {
+        if self.start_hour <= self.end_hour {
+            hour >= self.start_hour && hour < self.end_hour
+        } else {
+            hour >= self.start_hour || hour < self.end_hour
+        }
+    }
+}
+
+impl SecurityConfig {
+    /// Whether `relay_url`'s host matches `relay_url_allowlist` (always true when the list is empty)
+    pub fn allows_relay_url(&self, relay_url: &str) -> bool {
+        if self.relay_url_allowlist.is_empty() {
+            return true;
+        }
+        let Some(host) = url_host(relay_url) else {
+            return false;
+        };
+        self.relay_url_allowlist.iter().any(|pattern| {
+            let pattern = pattern.to_ascii_lowercase();
+            match pattern.strip_prefix("*.") {
+                Some(domain) => host.strip_suffix(domain).is_some_and(|sub| sub.ends_with('.')),
+                None => host == pattern,
+            }
+        })
+    }
+}
+
+/// Lowercased host of `url`, parsed the same way reqwest resolves it when sending
+fn url_host(url: &str) -> Option<String> {
+    let url = reqwest::Url::parse(url).ok()?;
+    let host = url.host_str()?;
+    // IPv6 hosts come back bracketed
+    let host = host.strip_prefix('[').and_then(|h| h.strip_suffix(']')).unwrap_or(host);
+    (!host.is_empty()).then(|| host.to_ascii_lowercase())
+}
+
+impl BuilderConfig {
+    /// Whether this builder should be submitted to at the given UTC hour
+    pub fn is_active_at_hour(&self, hour: u32) -> bool {
+        self.active_hours_utc.is_empty() || self.active_hours_utc.iter().any(|w| w.contains(hour))
+    }
+
+    /// Convert this builder config to a BuilderRelay instance
+    pub fn to_builder_relay(&self) -> Result<BuilderRelay, String> {
+        let payment_address = self.payment_address.parse::<Address>()
+            .map_err(|e| format!("Invalid payment address for builder {}: {}", self.name, e))?;
+
+        Ok(BuilderRelay {
+            name: self.name.clone(),
+            relay_url: self.relay_url.clone(),
+            status_url: self.status_url.clone(),
+            payment_address,
+            enabled: self.enabled,
+            timeout_seconds: self.timeout_seconds,
+            max_retries: self.max_retries,
+            max_backoff_ms: self.max_backoff_ms,
+            health_check_interval_seconds: self.health_check_interval_seconds,
+            response_result_path: self.response_result_path.clone(),
+            send_bundle_uuid: self.send_bundle_uuid,
+            expected_response_headers: self.expected_response_headers.clone(),
+            strict_response_headers: self.strict_response_headers,
+            request_id_format: self.request_id_format,
+            pool_max_idle_per_host: self.pool_max_idle_per_host,
+            pool_idle_timeout_seconds: self.pool_idle_timeout_seconds,
+            tcp_keepalive_seconds: self.tcp_keepalive_seconds,
+            max_relay_response_bytes: self.max_relay_response_bytes,
+            http_version: self.http_version,
+            auth_key_env: self.auth_key_env.clone(),
+            signature_scheme: self.signature_scheme,
+            chain_id: None,
+            bundle_version: self.bundle_version.clone(),
+        })
+    }
+}
+
 impl Config {
     /// Convert builder configs to BuilderRelay instances
     pub fn to_builder_relays(&self) -> Result<Vec<BuilderRelay>, String> {
         let mut relays = Vec::new();
         
         for builder in &self.builders {
-            let payment_address = builder.payment_address.parse::<Address>()
-                .map_err(|e| format!("Invalid payment address for builder {}: {}", builder.name, e))?;
-            
-            relays.push(BuilderRelay {
-                name: builder.name.clone(),
-                relay_url: builder.relay_url.clone(),
-                status_url: builder.status_url.clone(),
-                payment_address,
-                enabled: builder.enabled,
-                timeout_seconds: builder.timeout_seconds,
-                max_retries: builder.max_retries,
-                health_check_interval_seconds: builder.health_check_interval_seconds,
-            });
+            relays.push(self.builder_relay(builder)?);
         }
         
         Ok(relays)
     }
 
+    /// Convert a builder config to the BuilderRelay used for bundle submission,
+    /// including `network.chain_id` for builders with `send_chain_id`
+    pub fn builder_relay(&self, builder: &BuilderConfig) -> Result<BuilderRelay, String> {
+        let mut relay = builder.to_builder_relay()?;
+        if builder.send_chain_id {
+            relay.chain_id = self.network.chain_id;
+        }
+        Ok(relay)
+    }
+
     /// Convert limits config to U256 values
     pub fn parse_limits(&self) -> Result<ParsedLimits, String> {
-        let per_bundle_cap_wei = self.limits.per_bundle_cap_wei.parse::<U256>()
+        let per_bundle_cap_wei = types::utils::parse_wei_amount(&self.limits.per_bundle_cap_wei)
             .map_err(|e| format!("Invalid per_bundle_cap_wei: {}", e))?;
         
-        let daily_cap_wei = self.limits.daily_cap_wei.parse::<U256>()
+        let daily_cap_wei = types::utils::parse_wei_amount(&self.limits.daily_cap_wei)
             .map_err(|e| format!("Invalid daily_cap_wei: {}", e))?;
         
         let monthly_cap_wei = if let Some(ref monthly) = self.limits.monthly_cap_wei {
-            Some(monthly.parse::<U256>()
+            Some(types::utils::parse_wei_amount(monthly)
                 .map_err(|e| format!("Invalid monthly_cap_wei: {}", e))?)
         } else {
             None
         };
         
-        let emergency_stop_threshold_wei = self.limits.emergency_stop_threshold_wei.parse::<U256>()
+        let emergency_stop_threshold_wei = types::utils::parse_wei_amount(&self.limits.emergency_stop_threshold_wei)
             .map_err(|e| format!("Invalid emergency_stop_threshold_wei: {}", e))?;
+
+        let safe_mode_ceiling_wei = types::utils::parse_wei_amount(&self.limits.safe_mode_ceiling_wei)
+            .map_err(|e| format!("Invalid safe_mode_ceiling_wei: {}", e))?;
         
         Ok(ParsedLimits {
             per_bundle_cap_wei,
@@ -324,8 +657,114 @@ impl Config {
             monthly_cap_wei,
             emergency_stop_enabled: self.limits.emergency_stop_enabled,
             emergency_stop_threshold_wei,
+            safe_mode_ceiling_wei,
         })
     }
+
+    /// Serialize the effective configuration to YAML with secrets redacted.
+    ///
+    /// The admin API key, API token values and RPC URL (which often embeds a provider key) are
+    /// replaced with `<redacted>`; everything else round-trips through the loader unchanged.
+    pub fn to_redacted_yaml(&self) -> Result<String, serde_yaml::Error> {
+        const REDACTED: &str = "<redacted>";
+        let mut config = self.clone();
+        if config.security.admin_api_key.is_some() {
+            config.security.admin_api_key = Some(REDACTED.to_string());
+        }
+        for token in config.security.api_tokens.values_mut() {
+            *token = REDACTED.to_string();
+        }
+        if config.network.rpc_url.is_some() {
+            config.network.rpc_url = Some(REDACTED.to_string());
+        }
+        serde_yaml::to_string(&config)
+    }
+
+    /// Build a summary of the operational parameters with secrets redacted
+    pub fn summary(&self) -> ConfigSummary {
+        let enabled_builders: Vec<String> = self.builders.iter()
+            .filter(|b| b.enabled)
+            .map(|b| b.name.clone())
+            .collect();
+
+        let database_backend = self.database.url
+            .split(':')
+            .next()
+            .unwrap_or("unknown")
+            .to_string();
+
+        ConfigSummary {
+            network: self.network.network.clone(),
+            chain_id: self.network.chain_id,
+            payment_formula: self.payment.formula.as_str().to_string(),
+            k1: self.payment.k1,
+            k2_wei: self.payment.k2.to_string(),
+            max_amount_wei: self.payment.max_amount_wei.to_string(),
+            per_bundle_cap_wei: self.limits.per_bundle_cap_wei.clone(),
+            daily_cap_wei: self.limits.daily_cap_wei.clone(),
+            monthly_cap_wei: self.limits.monthly_cap_wei.clone(),
+            blocks_ahead: self.targets.blocks_ahead,
+            bundle_expiry_seconds: self.targets.bundle_expiry_seconds,
+            builder_count: enabled_builders.len(),
+            enabled_builders,
+            database_backend,
+            rpc_url_configured: self.network.rpc_url.is_some(),
+            admin_api_key: if self.security.admin_api_key.is_some() {
+                "<redacted>".to_string()
+            } else {
+                "<unset>".to_string()
+            },
+        }
+    }
+}
+
+/// Redacted summary of the effective configuration, logged once at startup
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigSummary {
+    pub network: String,
+    pub chain_id: Option<u64>,
+    pub payment_formula: String,
+    pub k1: f64,
+    pub k2_wei: String,
+    pub max_amount_wei: String,
+    pub per_bundle_cap_wei: String,
+    pub daily_cap_wei: String,
+    pub monthly_cap_wei: Option<String>,
+    pub blocks_ahead: u32,
+    pub bundle_expiry_seconds: u64,
+    pub builder_count: usize,
+    pub enabled_builders: Vec<String>,
+    pub database_backend: String,
+    pub rpc_url_configured: bool,
+    pub admin_api_key: String,
+}
+
+impl std::fmt::Display for ConfigSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "network={} chain_id={} payment_formula={} k1={} k2_wei={} max_amount_wei={} \
+             per_bundle_cap_wei={} daily_cap_wei={} monthly_cap_wei={} blocks_ahead={} \
+             bundle_expiry_seconds={} builder_count={} enabled_builders=[{}] database_backend={} \
+             rpc_url_configured={} admin_api_key={}",
+            self.network,
+            self.chain_id.map(|id| id.to_string()).unwrap_or_else(|| "none".to_string()),
+            self.payment_formula,
+            self.k1,
+            self.k2_wei,
+            self.max_amount_wei,
+            self.per_bundle_cap_wei,
+            self.daily_cap_wei,
+            self.monthly_cap_wei.as_deref().unwrap_or("none"),
+            self.blocks_ahead,
+            self.bundle_expiry_seconds,
+            self.builder_count,
+            self.enabled_builders.join(","),
+            self.database_backend,
+            self.rpc_url_configured,
+            self.admin_api_key,
+        )
+    }
 }
 
 /// Parsed limits with U256 values
@@ -336,6 +775,7 @@ pub struct ParsedLimits {
     pub monthly_cap_wei: Option<U256>,
     pub emergency_stop_enabled: bool,
     pub emergency_stop_threshold_wei: U256,
+    pub safe_mode_ceiling_wei: U256,
 }
 
 impl Default for Config {
@@ -345,11 +785,23 @@ impl Default for Config {
                 network: "mainnet".to_string(),
                 rpc_url: None,
                 chain_id: Some(1),
+                max_block_staleness_seconds: None,
+                max_clock_drift_seconds: None,
+                fail_on_clock_drift: false,
+                rpc_timeout_seconds: default_rpc_timeout_seconds(),
             },
             targets: TargetConfig {
                 blocks_ahead: 3,
                 resubmit_max: 3,
                 bundle_expiry_seconds: default_bundle_expiry_seconds(),
+                resubmit_interval_seconds: default_resubmit_interval_seconds(),
+                max_payment_expiry_seconds: default_max_payment_expiry_seconds(),
+                stuck_bundle_timeout_seconds: default_stuck_bundle_timeout_seconds(),
+                inclusion_strategy: InclusionStrategy::default(),
+                inclusion_check_concurrency: default_inclusion_check_concurrency(),
+                receipt_batch_size: default_receipt_batch_size(),
+                unknown_target_block: UnknownTargetBlock::default(),
+                bundle_retry_budget: None,
             },
             payment: PaymentConfig::default(),
             limits: LimitsConfig {
@@ -358,6 +810,7 @@ impl Default for Config {
                 monthly_cap_wei: None,
                 emergency_stop_enabled: default_true(),
                 emergency_stop_threshold_wei: default_emergency_threshold(),
+                safe_mode_ceiling_wei: default_safe_mode_ceiling(),
             },
             builders: vec![
                 BuilderConfig {
@@ -368,7 +821,24 @@ impl Default for Config {
                     enabled: true,
                     timeout_seconds: default_timeout_seconds(),
                     max_retries: default_max_retries(),
+                    max_backoff_ms: default_max_backoff_ms(),
                     health_check_interval_seconds: default_health_check_interval(),
+                    response_result_path: None,
+                    send_bundle_uuid: false,
+                    send_chain_id: false,
+                    bundle_version: None,
+                    expected_response_headers: HashMap::new(),
+                    strict_response_headers: false,
+                    request_id_format: RequestIdFormat::default(),
+                    pool_max_idle_per_host: default_pool_max_idle_per_host(),
+                    pool_idle_timeout_seconds: default_pool_idle_timeout_seconds(),
+                    tcp_keepalive_seconds: default_tcp_keepalive_seconds(),
+                    max_relay_response_bytes: default_max_relay_response_bytes(),
+                    http_version: RelayHttpVersion::default(),
+                    auth_key_env: None,
+                    signature_scheme: RelaySignatureScheme::default(),
+                    active_hours_utc: Vec::new(),
+                    signer_key_ref: None,
                 },
             ],
             server: ServerConfig::default(),
@@ -388,6 +858,13 @@ impl Default for ServerConfig {
             request_timeout_seconds: default_request_timeout(),
             max_body_size: default_max_body_size(),
             cors_enabled: default_true(),
+            debug_endpoints_enabled: false,
+            submission_queue_capacity: default_submission_queue_capacity(),
+            submission_workers: default_submission_workers(),
+            observe_only: false,
+            include_bundle_txs: false,
+            status_cache_ttl_ms: default_status_cache_ttl_ms(),
+            status_cache_capacity: default_status_cache_capacity(),
         }
     }
 }
@@ -399,6 +876,9 @@ impl Default for DatabaseConfig {
             max_connections: default_max_connections(),
             connection_timeout_seconds: default_connection_timeout(),
             wal_mode: default_true(),
+            busy_timeout_ms: default_busy_timeout_ms(),
+            synchronous: SqliteSynchronous::default(),
+            foreign_keys: default_true(),
         }
     }
 }
@@ -411,6 +891,7 @@ impl Default for LoggingConfig {
             file_path: None,
             request_logging: default_true(),
             sql_logging: default_false(),
+            sanitize_hashes: default_false(),
         }
     }
 }
@@ -430,10 +911,14 @@ impl Default for SecurityConfig {
     fn default() -> Self {
         Self {
             admin_api_key: None,
+            api_tokens: HashMap::new(),
             rate_limiting_enabled: default_true(),
             rate_limit_per_minute: default_rate_limit(),
             rate_limit_burst: default_rate_limit_burst(),
             killswitch_enabled: default_true(),
+            killswitch_auto_deactivate_seconds: None,
+            verbose_errors: false,
+            relay_url_allowlist: Vec::new(),
         }
     }
 }
@@ -1,27 +1,45 @@
 //! HTTP API request handlers
 
 use crate::app::AppState;
+use crate::fees::{fee_history_max_fee, recent_median_priority_fee, scale_priority_fee, tx2_gas_limit};
+use crate::metrics::CapReason;
+use crate::worker::SubmissionJob;
+use config::BuilderConfig;
 use axum::{
-    extract::{Path, State},
-    http::StatusCode,
-    response::Json,
+    extract::{Path, Query, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Json},
 };
+use serde::Deserialize;
 use serde_json::{json, Value};
 use std::sync::Arc;
+use std::time::Instant;
 use types::BundleRequest;
 use alloy::primitives::keccak256;
 use uuid::Uuid;
-use payment::{PaymentCalculator, PaymentTransactionForger};
-use alloy::primitives::{Address, U256};
+use payment::{PaymentCalculator, PaymentPolicyEnforcer, PaymentTransactionForger};
+use alloy::consensus::Transaction as _;
+use chrono::{DateTime, NaiveDate, Timelike, Utc};
+use alloy::primitives::{Address, Bytes, TxKind, U256};
 use alloy::providers::{Provider, ProviderBuilder};
 use std::str::FromStr;
-use types::{PaymentParams, PaymentFormula};
+use types::{PaymentConfig, PaymentError, PaymentParams, PaymentFormula, PaymentPolicy, PaymentSplit, TransactionError, Tx2Type};
 use relay_client;
 
+/// Query parameters for bundle submission
+#[derive(Debug, Default, Deserialize)]
+pub struct SubmitBundleQuery {
+    /// Return 202 immediately and forge/submit in a background task
+    #[serde(default, rename = "async")]
+    pub async_mode: bool,
+}
+
 /// Submit a new bundle for processing
 pub async fn submit_bundle(
     State(state): State<Arc<AppState>>,
-    Json(request): Json<BundleRequest>,
+    Query(query): Query<SubmitBundleQuery>,
+    headers: HeaderMap,
+    Json(mut request): Json<BundleRequest>,
 ) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
     // Check killswitch
     if state.is_killswitch_active().await {
@@ -33,422 +51,3651 @@ pub async fn submit_bundle(
         ));
     }
 
-    let bundle_id = Uuid::new_v4();
-
-    // Get all enabled builders
-    let enabled_builders: Vec<_> = state.config.builders.iter().filter(|b| b.enabled).collect();
-    if enabled_builders.is_empty() {
+    if let Err(e) = validate_tx1(&request.tx1) {
         return Err((
             StatusCode::BAD_REQUEST,
-            Json(json!({ "error": "No enabled builders configured" })),
+            Json(json!({ "error": format!("tx1 rejected: {}", e) })),
         ));
     }
 
-    // tx1 as provided
-    let tx1_hex = format!("{}", request.tx1);
+    let formula = resolve_payment_formula(&request.payment.formula, &state.config.payment)
+        .map_err(|e| (StatusCode::BAD_REQUEST, Json(json!({ "error": e }))))?;
+    request.payment.formula = formula.as_str().to_string();
 
-    // Get signer key from env (this is still needed for signing)
-    let signer_key = std::env::var("PAYMENT_SIGNER_PRIVATE_KEY")
-        .map_err(|_| (
+    let now = state.clock.now();
+    if request.payment.expiry <= now {
+        return Err((
             StatusCode::BAD_REQUEST,
-            Json(json!({ "error": "PAYMENT_SIGNER_PRIVATE_KEY missing" }))
-        ))?;
+            Json(json!({
+                "error": format!(
+                    "payment.expiry {} is not in the future; the bundle would expire before submission",
+                    request.payment.expiry.to_rfc3339()
+                )
+            })),
+        ));
+    }
 
-    let chain_id = state.config.network.chain_id.unwrap_or(1);
+    let max_expiry_seconds = state.config.targets.max_payment_expiry_seconds;
+    if expiry_exceeds_cap(request.payment.expiry, now, max_expiry_seconds) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({
+                "error": format!(
+                    "payment.expiry is more than {} seconds in the future",
+                    max_expiry_seconds
+                )
+            })),
+        ));
+    }
 
-    // Create RPC provider to get current network conditions
-    let rpc_url = std::env::var("ETH_RPC_URL")
-        .unwrap_or_else(|_| "http://localhost:8545".to_string());
-    let provider = ProviderBuilder::new()
-        .on_http(rpc_url.parse().map_err(|_| (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(json!({ "error": "Invalid RPC URL" }))
-        ))?);
+    let bundle_id = Uuid::new_v4();
+    let tx1_hash = raw_tx_hash(&format!("{}", request.tx1));
+    let submitter = submitter_from_headers(&headers, &state.config.security.api_tokens);
 
-    // Get current base fee and suggested max fee from latest block
-    let latest_block = provider.get_block_by_number(alloy::rpc::types::BlockNumberOrTag::Latest, false)
-        .await
-        .map_err(|e| (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(json!({ "error": format!("Failed to get latest block: {}", e) }))
-        ))?
-        .ok_or_else(|| (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(json!({ "error": "Latest block not found" }))
-        ))?;
+    let recorded = state
+        .database
+        .insert_bundle(&bundle_id.to_string(), &tx1_hash, &submitter, state.clock.now(), request.payment.expiry)
+        .await;
 
-    let base_fee_per_gas = U256::from(
-        latest_block.header.base_fee_per_gas
-            .unwrap_or(20_000_000_000u64) // 20 gwei fallback
-    );
+    if query.async_mode {
+        // Status is only observable through the database, so it must be recorded
+        recorded.map_err(|e| internal_error(&state, StatusCode::INTERNAL_SERVER_ERROR, "Failed to queue bundle", e))?;
 
-    // Estimate gas for tx1 using simulator helper (decode + eth_estimateGas)
-    let estimated_gas_used: u64 = match simulator::estimate_gas_from_raw(&rpc_url, &tx1_hex).await {
-        // Add 21_000 to the estimated gas used to account for the tx2
-        Ok(g) => g + 21_000u64,
-        Err(e) => {
-            tracing::warn!(error = %e, "tx1 gas estimation failed; defaulting to 21000");
-            21_000u64
+        if state.submission_queue.try_enqueue(SubmissionJob { bundle_id, request }).is_err() {
+            tracing::warn!(bundle_id = %bundle_id, "Submission queue full; rejecting bundle");
+            if let Err(e) = state.set_bundle_state(&bundle_id.to_string(), "failed").await {
+                tracing::warn!(bundle_id = %bundle_id, error = %e, "Failed to record bundle state");
+            }
+            return Err((
+                StatusCode::TOO_MANY_REQUESTS,
+                Json(json!({ "error": "Submission queue is full, retry later" }))
+            ));
         }
+
+        return Ok((StatusCode::ACCEPTED, Json(json!({
+            "bundleId": bundle_id,
+            "state": "queued"
+        }))));
+    }
+
+    if let Err(e) = recorded {
+        tracing::warn!(bundle_id = %bundle_id, error = %e, "Failed to record bundle; status lookups will miss it");
+    }
+
+    process_and_record_bundle(&state, bundle_id, request).await
+}
+
+/// `POST /bundles`: [`submit_bundle`], answering a processed bundle with `201 Created`.
+///
+/// Both `201` and `202 Accepted` carry a `Location` header pointing at the bundle's status endpoint.
+pub async fn create_bundle(
+    state: State<Arc<AppState>>,
+    query: Query<SubmitBundleQuery>,
+    headers: HeaderMap,
+    request: Json<BundleRequest>,
+) -> impl IntoResponse {
+    let (status, Json(body)) = match submit_bundle(state, query, headers, request).await {
+        Ok(response) => response,
+        Err(error) => return error.into_response(),
     };
+    let status = if status == StatusCode::OK { StatusCode::CREATED } else { status };
+    match body["bundleId"].as_str() {
+        Some(bundle_id) => {
+            let location = format!("/bundles/{}", bundle_id);
+            (status, [(header::LOCATION, location)], Json(body)).into_response()
+        }
+        None => (status, Json(body)).into_response(),
+    }
+}
 
-    tracing::info!(estimated_gas_used = estimated_gas_used, "Estimated gas used for tx1");
+/// Forge and submit a bundle, then record its resulting state (`sent` or `failed`).
+///
+/// In observe-only mode nothing is sent, so the bundle stays `queued`.
+pub(crate) async fn process_and_record_bundle(
+    state: &AppState,
+    bundle_id: Uuid,
+    request: BundleRequest,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    let outcome = process_bundle(state, bundle_id, request.clone()).await;
+    if let Some(parked) = park_until_funded(state, bundle_id, request, &outcome).await {
+        return parked;
+    }
 
-    // Calculate payment using PaymentCalculator to get priority fee
-    let calculator = PaymentCalculator::new();
-    let payment_params = PaymentParams {
-        gas_used: estimated_gas_used,
-        base_fee_per_gas,
-        max_priority_fee_per_gas: U256::from(0u64), // 0 gwei default, will be calculated
-        formula: request.payment.formula.clone(),
-        k1: state.config.payment.k1,
-        k2: state.config.payment.k2,
-        max_amount: U256::from_str(&state.config.payment.max_amount_wei.to_string())
-            .unwrap_or(U256::from(500_000_000_000_000_000u64)), // 0.5 ETH fallback
+    let bundle_state = match &outcome {
+        Ok((_, Json(body))) if body["observeOnly"] == true => "queued",
+        Ok((_, Json(body))) if any_submitted(body) => "sent",
+        Ok(_) => "failed",
+        Err((_, Json(body))) => {
+            tracing::warn!(bundle_id = %bundle_id, error = %body["error"], "Bundle processing failed");
+            "failed"
+        }
     };
 
-    let payment_result = calculator.calculate_payment(&payment_params)
-        .map_err(|e| (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(json!({ "error": format!("Payment calculation failed: {}", e) }))
-        ))?;
+    // Recorded before the state so status readers never see `sent` without the flag
+    if let Ok((_, Json(body))) = &outcome {
+        if body["partiallySubmitted"] == true {
+            if let Err(e) = state.database.set_partially_submitted(&bundle_id.to_string(), true).await {
+                tracing::warn!(bundle_id = %bundle_id, error = %e, "Failed to record partial submission");
+            }
+        }
+    }
 
-    let flat_amount_wei = payment_result.amount_wei;
+    if let Err(e) = state.set_bundle_state(&bundle_id.to_string(), bundle_state).await {
+        tracing::warn!(bundle_id = %bundle_id, error = %e, "Failed to record bundle state");
+    }
 
-    let max_priority_fee_per_gas: u128 = 0;
-    let max_fee_per_gas: u128 = (((base_fee_per_gas * U256::from(3)) / U256::from(2))
-        + U256::from(max_priority_fee_per_gas))
-        .try_into()
-        .unwrap_or(2_000_000_000u128);
+    if bundle_state == "sent" {
+        let paid = outcome
+            .as_ref()
+            .ok()
+            .and_then(|(_, Json(body))| body["paymentAmountWei"].as_str())
+            .and_then(|amount| amount.parse::<U256>().ok());
+        if let Some(amount_wei) = paid {
+            if let Err(e) = state.database.record_bundle_payment(&bundle_id.to_string(), amount_wei).await {
+                tracing::warn!(bundle_id = %bundle_id, error = %e, "Failed to record bundle payment");
+            }
+        }
+    }
 
-    let gas_limit: u64 = 21_000; // Standard ETH transfer
+    outcome
+}
 
-    // Get nonce for payment signer
-    let signer_addr = alloy::signers::local::PrivateKeySigner::from_str(&signer_key)
-        .map_err(|_| (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(json!({ "error": "Invalid signer key format" }))
-        ))?
-        .address();
+/// Requeue a bundle rejected for insufficient signer balance while `payment.balance_grace_seconds`
+/// since its submission have not yet passed; it stays `queued` for the workers to retry.
+async fn park_until_funded(
+    state: &AppState,
+    bundle_id: Uuid,
+    request: BundleRequest,
+    outcome: &Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)>,
+) -> Option<Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)>> {
+    let grace_seconds = state.config.payment.balance_grace_seconds?;
+    let Err((_, Json(body))) = outcome else {
+        return None;
+    };
+    if body["error"] != INSUFFICIENT_BALANCE_ERROR {
+        return None;
+    }
 
-    let base_nonce: u64 = provider.get_transaction_count(signer_addr)
-        .await
-        .map_err(|e| (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(json!({ "error": format!("Failed to get nonce: {}", e) }))
-        ))?
-        .try_into()
-        .unwrap_or(0);
+    let created_at = state.database.get_bundle(&bundle_id.to_string()).await.ok()??.created_at;
+    if state.clock.now() >= created_at + chrono::Duration::seconds(grace_seconds as i64) {
+        tracing::warn!(bundle_id = %bundle_id, grace_seconds = grace_seconds, "Signer balance still insufficient after grace period");
+        return None;
+    }
 
-    // Ensure payment signer has enough balance for value + max gas cost
-    let signer_balance = provider.get_balance(signer_addr)
-        .await
-        .map_err(|e| (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(json!({ "error": format!("Failed to get balance: {}", e) }))
-        ))?;
+    tracing::info!(bundle_id = %bundle_id, "Signer balance insufficient; queueing bundle until it is funded");
+    state.submission_queue.requeue_after(
+        SubmissionJob { bundle_id, request },
+        std::time::Duration::from_millis(state.config.payment.balance_retry_interval_ms),
+    );
+    Some(Ok((StatusCode::ACCEPTED, Json(json!({
+        "bundleId": bundle_id,
+        "state": "queued",
+        "warnings": ["Signer balance insufficient; the bundle will be retried until it is funded"]
+    })))))
+}
 
-    let required_wei = U256::from(gas_limit)
-        .checked_mul(U256::from(max_fee_per_gas))
-        .unwrap_or(U256::MAX)
-        .saturating_add(flat_amount_wei);
+/// Attribute a submission to the `security.api_tokens` entry matching its bearer token.
+///
+/// Unknown tokens are attributed to a short hash of the token so raw tokens are never stored;
+/// requests without a bearer token are `anonymous`.
+fn submitter_from_headers(headers: &HeaderMap, api_tokens: &std::collections::HashMap<String, String>) -> String {
+    let Some(token) = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(str::trim)
+        .filter(|token| !token.is_empty())
+    else {
+        return "anonymous".to_string();
+    };
 
-    if signer_balance < required_wei {
-        tracing::warn!(
-            signer = %format!("0x{:x}", signer_addr),
-            balance_wei = %signer_balance,
-            required_wei = %required_wei,
-            gas_limit = gas_limit,
-            max_fee_per_gas = max_fee_per_gas,
-            payment_wei = %flat_amount_wei,
-            "Insufficient balance for tx2 (value + max gas). Consider lowering payment or max fee"
-        );
+    api_tokens
+        .iter()
+        .find(|(_, configured)| configured.as_str() == token)
+        .map(|(name, _)| name.clone())
+        .unwrap_or_else(|| format!("token:{}", &alloy::hex::encode(keccak256(token.as_bytes()))[..8]))
+}
+
+/// Who performed an admin request: `admin` for the admin API key, otherwise the submitter identity
+fn admin_actor_from_headers(headers: &HeaderMap, security: &config::SecurityConfig) -> String {
+    let bearer = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(str::trim);
+    match (bearer, security.admin_api_key.as_deref()) {
+        (Some(token), Some(admin_key)) if token == admin_key => "admin".to_string(),
+        _ => submitter_from_headers(headers, &security.api_tokens),
+    }
+}
+
+/// Record an admin action in the audit log; a failed write is logged but never fails the action
+async fn audit_admin_action(state: &AppState, headers: &HeaderMap, action: &str, details: Value) {
+    let actor = admin_actor_from_headers(headers, &state.config.security);
+    tracing::info!(action, actor = %actor, %details, "Admin action");
+    if let Err(e) = state.database.record_admin_action(action, &actor, &details, state.clock.now()).await {
+        tracing::error!(error = %e, action, "Failed to record admin action");
+    }
+}
+
+/// Error response for an internal (RPC or database) failure.
+///
+/// `detail` is always logged; clients only see it when `security.verbose_errors` is set, since it
+/// can carry internal RPC URLs and node errors.
+fn internal_error(
+    state: &AppState,
+    status: StatusCode,
+    message: &str,
+    detail: impl std::fmt::Display,
+) -> (StatusCode, Json<Value>) {
+    tracing::error!(error = %detail, "{}", message);
+    let error = if state.config.security.verbose_errors {
+        format!("{}: {}", message, detail)
+    } else {
+        message.to_string()
+    };
+    (status, Json(json!({ "error": error })))
+}
+
+/// Whether at least one relay accepted the bundle
+fn any_submitted(body: &Value) -> bool {
+    body["submissions"]
+        .as_array()
+        .map(|subs| subs.iter().any(|s| s["status"] == "submitted"))
+        .unwrap_or(false)
+}
+
+/// Forge tx2 (unless supplied) and submit the bundle to every enabled builder
+async fn process_bundle(
+    state: &AppState,
+    bundle_id: Uuid,
+    request: BundleRequest,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    // Get all enabled builders
+    let enabled_builders = active_builders(state);
+    if enabled_builders.is_empty() {
         return Err((
             StatusCode::BAD_REQUEST,
-            Json(json!({
-                "error": "Insufficient balance for tx2 (value + max gas)",
-                "balanceWei": format!("{}", signer_balance),
-                "requiredWei": format!("{}", required_wei)
-            }))
+            Json(json!({ "error": "No enabled builders configured" })),
         ));
     }
 
-    let forger = PaymentTransactionForger::new();
+    // tx1 as provided
+    let tx1_hex = format!("{}", request.tx1);
+
+    // Compute tx1 hash for diagnostics (keccak256 of raw signed RLP)
+    let tx1_hash = raw_tx_hash(&tx1_hex);
+
     // Optional single target block accepted at API level
     let requested_target_block = request.target_block;
-    
-    // Compute tx1 hash for diagnostics (keccak256 of raw signed RLP)
-    let tx1_hash = {
-        let raw = tx1_hex.trim_start_matches("0x");
-        match alloy::hex::decode(raw) {
-            Ok(bytes) => format!("0x{}", alloy::hex::encode(keccak256(&bytes))),
-            Err(_) => "0x".to_string(),
+
+    // Fast-fail when the requested block has already been built
+    if let Some(target_block) = requested_target_block {
+        if let Some(chain_head) = passed_target_block_head(&rpc_url(state), target_block).await {
+            tracing::warn!(
+                bundle_id = %bundle_id,
+                target_block = target_block,
+                chain_head = chain_head,
+                "Skipping submission: target block is not ahead of the chain head"
+            );
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json(json!({
+                    "error": format!(
+                        "Target block {} has already been built (chain head {})",
+                        target_block, chain_head
+                    )
+                })),
+            ));
+        }
+    }
+
+    // Without a requested target, aim for the next `targets.blocks_ahead` blocks
+    let target_blocks: Vec<Option<u64>> = match requested_target_block {
+        Some(target_block) => vec![Some(target_block)],
+        None => match next_block(&rpc_url(state)).await {
+            Ok(next) => {
+                let blocks_ahead = u64::from(state.config.targets.blocks_ahead.max(1));
+                (next..next + blocks_ahead).map(Some).collect()
+            }
+            Err(e) => match state.config.targets.unknown_target_block {
+                config::UnknownTargetBlock::Reject => {
+                    tracing::warn!(bundle_id = %bundle_id, "Rejecting bundle: target block could not be determined");
+                    return Err(internal_error(
+                        state,
+                        StatusCode::SERVICE_UNAVAILABLE,
+                        "Could not determine a target block (no targetBlock given and RPC failed)",
+                        e,
+                    ));
+                }
+                config::UnknownTargetBlock::Omit => {
+                    tracing::warn!(bundle_id = %bundle_id, error = %e, "Target block could not be determined; submitting without blockNumber");
+                    vec![None]
+                }
+            },
+        },
+    };
+
+    // Requests that pay nothing need no bundle: tx1 is forwarded on its own
+    let private_tx = state.config.payment.private_tx_when_unpaid && is_unpaid(&request);
+
+    // Use the client-supplied tx2 when present, otherwise forge one per builder
+    let (flat_amount_wei, bundles) = match request.tx2 {
+        _ if private_tx => (U256::ZERO, enabled_builders.iter().map(|b| (*b, vec![tx1_hex.clone()])).collect()),
+        Some(ref raw_tx2) => {
+            use_supplied_tx2(state, &format!("{}", raw_tx2), &enabled_builders, &tx1_hex, &tx1_hash).await?
+        }
+        None => {
+            forge_builder_bundles(state, &request, &enabled_builders, &tx1_hex, &tx1_hash).await?
         }
     };
 
-    // Create a bundle for each enabled builder
-    let mut bundles = Vec::new();
-    
-    for builder in enabled_builders.iter() {
-        // Parse builder payment address
-        let builder_addr = Address::from_str(builder.payment_address.as_str())
-            .map_err(|_| (
+    // Reject bundles that contain the same transaction more than once
+    for (builder_config, txs) in bundles.iter() {
+        if let Some(duplicate_hash) = find_duplicate_tx(txs) {
+            tracing::warn!(
+                bundle_id = %bundle_id,
+                builder = %builder_config.name,
+                tx_hash = %state.config.logging.log_hash(&duplicate_hash),
+                "Rejecting bundle with duplicate transactions"
+            );
+            return Err((
                 StatusCode::BAD_REQUEST,
-                Json(json!({ "error": format!("Invalid builder payment address for {}", builder.name) }))
-            ))?;
+                Json(json!({ "error": format!("Duplicate transaction in bundle: {}", duplicate_hash) }))
+            ));
+        }
+    }
 
-        let (tx2_hex, tx2_hash) = forger
-            .forge_flat_transfer_hex(
-                builder_addr,
-                flat_amount_wei,
-                chain_id,
-                base_nonce,
-                max_fee_per_gas,
-                max_priority_fee_per_gas,
-                gas_limit,
-                &signer_key,
-            )
-            .await
-            .map_err(|e| (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({ "error": format!("failed to forge tx2 for {}: {}", builder.name, e) }))
-            ))?;
+    // Reserved up front so concurrent bundles can't all pass the caps; given back unless a
+    // builder accepts the bundle
+    let reserved_on = reserve_spending(state, bundle_id, flat_amount_wei).await?;
+    let outcome = submit_bundles(state, bundle_id, &request, &target_blocks, bundles, flat_amount_wei, private_tx).await;
+    if !matches!(&outcome, Ok((_, Json(body))) if any_submitted(body)) {
+        release_spending(state, bundle_id, reserved_on, flat_amount_wei).await;
+    }
+    outcome
+}
 
-        // Log the tx2 hash for this builder
-        tracing::info!(
-            builder = %builder.name,
-            tx2_hash = %tx2_hash,
-            tx2_to = %builder_addr,
-            tx2_value_wei = %flat_amount_wei,
-            tx1_hash = %tx1_hash,
-            "Forged tx2 payment transaction for builder"
-        );
+/// Submit each builder's bundle (or, for `private_tx`, its lone transaction) to its relay.
+///
+/// In observe-only mode the bundles are only logged.
+async fn submit_bundles(
+    state: &AppState,
+    bundle_id: Uuid,
+    request: &BundleRequest,
+    target_blocks: &[Option<u64>],
+    mut bundles: Vec<(&BuilderConfig, Vec<String>)>,
+    flat_amount_wei: U256,
+    private_tx: bool,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    let tx1_hex = format!("{}", request.tx1);
+    let warnings: Vec<String> = low_payment_warning(state, &tx1_hex, flat_amount_wei)
+        .await
+        .into_iter()
+        .collect();
 
-        let txs = vec![tx1_hex.clone(), tx2_hex.clone()];
-        bundles.push((builder.name.clone(), txs));
+    // Shadow deployments stop here: log what would be sent without contacting any relay
+    if state.config.server.observe_only {
+        let observed: Vec<Value> = bundles
+            .iter()
+            .map(|(builder_config, txs)| {
+                tracing::info!(
+                    bundle_id = %bundle_id,
+                    builder = %builder_config.name,
+                    relay_url = %builder_config.relay_url,
+                    targets = ?target_blocks,
+                    txs = ?txs,
+                    payment_wei = %flat_amount_wei,
+                    "Observe-only mode: skipping relay submission"
+                );
+                json!({
+                    "builder": builder_config.name,
+                    "status": "observed",
+                    "txs": txs
+                })
+            })
+            .collect();
+
+        return Ok((StatusCode::OK, Json(json!({
+            "bundleId": bundle_id,
+            "observeOnly": true,
+            "privateTransaction": private_tx,
+            "submissions": observed,
+            "warnings": warnings
+        }))));
+    }
+
+    if private_tx {
+        let max_block_number = target_blocks.iter().flatten().max().copied();
+        return Ok(send_private_transactions(state, bundle_id, &bundles, max_block_number, warnings).await);
     }
 
     // Submit bundles to relays individually (each builder gets their specific bundle)
     let mut submission_results = Vec::new();
-    for (i, (builder_name, txs)) in bundles.iter().enumerate() {
-        let builder_config = &enabled_builders[i];
-        
-        // Create BuilderRelay from BuilderConfig
-        let payment_address = Address::from_str(builder_config.payment_address.as_str())
-            .map_err(|_| (
+    let forged_tx2 = request.tx2.is_none();
+    // One retry pool shared by every relay keeps a bundle's total relay requests bounded
+    let retry_budget = state.config.targets.bundle_retry_budget.map(relay_client::RetryBudget::new);
+    for (builder_config, txs) in bundles.iter_mut().map(|(builder, txs)| (*builder, txs)) {
+        let builder_name = &builder_config.name;
+
+        let mut relay_client = state.relay_client(builder_config)
+            .map_err(|e| (
                 StatusCode::BAD_REQUEST,
-                Json(json!({ "error": format!("Invalid payment address for builder {}", builder_config.name) }))
+                Json(json!({ "error": e }))
             ))?;
-            
-        let builder_relay = types::BuilderRelay {
-            name: builder_config.name.clone(),
-            relay_url: builder_config.relay_url.clone(),
-            status_url: builder_config.status_url.clone(),
-            payment_address,
-            enabled: builder_config.enabled,
-            timeout_seconds: builder_config.timeout_seconds,
-            max_retries: builder_config.max_retries,
-            health_check_interval_seconds: builder_config.health_check_interval_seconds,
-        };
-        
-        let relay_client = relay_client::RelayClient::new(builder_relay);
+        if let Some(budget) = &retry_budget {
+            relay_client = relay_client.with_retry_budget(budget.clone());
+        }
         
-        // If API provided a target block, include it; otherwise omit blockNumber
-        let chosen_target_opt = requested_target_block;
-        tracing::info!(relay = %builder_name, target = ?chosen_target_opt, "Preparing to submit bundle");
+        tracing::info!(relay = %builder_name, targets = ?target_blocks, "Preparing to submit bundle");
+
+        // A concurrent submission may have used our forged tx2's nonce; re-forge it at the
+        // on-chain nonce so the relay client can retry
+        let reforge = move |mut txs: Vec<String>| async move {
+            if !forged_tx2 {
+                return None;
+            }
+            match reforge_tx2_with_fresh_nonce(state, builder_config, &txs[1]).await {
+                Ok((tx2_hex, nonce)) => {
+                    tracing::warn!(
+                        bundle_id = %bundle_id,
+                        builder = %builder_name,
+                        nonce = nonce,
+                        "Relay reported nonce too low; resubmitting with re-forged tx2"
+                    );
+                    txs[1] = tx2_hex;
+                    Some(txs)
+                }
+                Err(e) => {
+                    tracing::warn!(bundle_id = %bundle_id, builder = %builder_name, error = %e, "Failed to re-forge tx2 after nonce too low");
+                    None
+                }
+            }
+        };
+
+        // The same bundle goes out once per target block; relays that require a replacement
+        // UUID get one derived from the bundle id for each block
+        let started_at = Instant::now();
+        let mut accepted = Vec::new();
+        let mut last_error = None;
+        let results = relay_client
+            .submit_bundle_for_targets(txs, target_blocks.iter().copied(), Some(bundle_id), reforge)
+            .await;
+        for (target_block, submit_result) in results {
+            match submit_result {
+                Ok(submission) => accepted.push((target_block, submission)),
+                Err(e) => {
+                    tracing::warn!(
+                        bundle_id = %bundle_id,
+                        builder = %builder_name,
+                        target = ?target_block,
+                        error = %e,
+                        "Bundle submission for target block failed"
+                    );
+                    last_error = Some(e);
+                }
+            }
+        }
+        let latency_ms = started_at.elapsed().as_millis() as u64;
+        let used_target_blocks: Vec<u64> = accepted.iter().filter_map(|(block, _)| *block).collect();
+        let last_accepted_target = accepted.last().and_then(|(block, _)| *block);
 
-        match relay_client.submit_bundle(txs.clone(), chosen_target_opt).await {
-            Ok(response) => {
+        match (accepted.into_iter().next(), last_error) {
+            (Some((_, submission)), _) => {
+                let response = submission.bundle_hash;
                 tracing::info!(
                     bundle_id = %bundle_id,
                     builder = %builder_name,
                     relay_response = %response,
+                    target_blocks = ?used_target_blocks,
+                    latency_ms = latency_ms,
                     "Bundle submitted successfully"
                 );
+                state
+                    .resubmissions
+                    .record(bundle_id, builder_config, txs.clone(), last_accepted_target, state.clock.now())
+                    .await;
+                if let Err(e) = state
+                    .database
+                    .record_relay_submission(&bundle_id.to_string(), builder_name, &response, &used_target_blocks, state.clock.now())
+                    .await
+                {
+                    tracing::warn!(bundle_id = %bundle_id, builder = %builder_name, error = %e, "Failed to record relay submission");
+                }
                 submission_results.push(json!({
                     "builder": builder_name,
                     "status": "submitted",
-                    "response": response
+                    "response": response,
+                    "relayResult": submission.raw_result,
+                    "targetBlocks": used_target_blocks,
+                    "latencyMs": latency_ms
                 }));
             }
-            Err(e) => {
+            (None, Some(e)) => {
                 tracing::error!(
                     bundle_id = %bundle_id,
                     builder = %builder_name,
                     error = %e,
+                    latency_ms = latency_ms,
                     "Bundle submission failed"
                 );
                 submission_results.push(json!({
                     "builder": builder_name,
                     "status": "failed",
-                    "error": e.to_string()
+                    "error": e.to_string(),
+                    "latencyMs": latency_ms
                 }));
             }
+            (None, None) => unreachable!("there is always at least one target block"),
+        }
+    }
+
+    // Let clients verify the exact ordered transactions each builder received
+    if state.config.server.include_bundle_txs {
+        for (result, (_, txs)) in submission_results.iter_mut().zip(bundles.iter()) {
+            result["bundleTxs"] = json!(txs);
         }
     }
 
+    let submitted_count = submission_results.iter().filter(|s| s["status"] == "submitted").count();
+    if submitted_count > 0 {
+        state.metrics.observe_payment(&request_formula(request), flat_amount_wei);
+    }
+    let partially_submitted = submitted_count > 0 && submitted_count < submission_results.len();
+    if partially_submitted {
+        tracing::warn!(
+            bundle_id = %bundle_id,
+            submitted = submitted_count,
+            total = submission_results.len(),
+            "Bundle accepted by only some relays"
+        );
+    }
+
     tracing::info!(
         bundle_id = %bundle_id,
-        builders = ?enabled_builders.iter().map(|b| &b.name).collect::<Vec<_>>(),
+        builders = ?bundles.iter().map(|(b, _)| &b.name).collect::<Vec<_>>(),
         payment_wei = %flat_amount_wei,
         tx1_len = tx1_hex.len(),
         bundles_count = bundles.len(),
         "Created and submitted bundles for all enabled builders"
     );
 
-    Ok((StatusCode::OK, Json(json!({ 
+    let mut response = json!({ 
         "bundleId": bundle_id,
-        "submissions": submission_results
-    }))))
+        "paymentAmountWei": flat_amount_wei.to_string(),
+        "partiallySubmitted": partially_submitted,
+        "submissions": submission_results,
+        "warnings": warnings
+    });
+    // Report how a forged payment was computed; a supplied tx2 carries its own amount
+    if forged_tx2 {
+        response["formulaUsed"] = json!(request_formula(request).as_str());
+        response["k1Used"] = json!(state.config.payment.k1);
+        response["k2Used"] = json!(state.config.payment.k2.to_string());
+    }
+
+    Ok((submission_status(submitted_count), Json(response)))
 }
 
-/// Get bundle status by ID
-pub async fn get_bundle_status(
-    State(_state): State<Arc<AppState>>,
-    Path(bundle_id): Path<String>,
-) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
-    // TODO: Implement bundle status lookup
-    tracing::info!("Bundle status request for ID: {}", bundle_id);
-    
-    // Validate bundle ID format
-    if Uuid::parse_str(&bundle_id).is_err() {
-        return Err((
-            StatusCode::BAD_REQUEST,
-            Json(json!({
-                "error": "Invalid bundle ID format"
-            })),
-        ));
+/// Whether a request carries no payment: no tx2 and a zero `maxAmountWei`
+fn is_unpaid(request: &BundleRequest) -> bool {
+    request.tx2.is_none()
+        && types::utils::parse_wei_amount(&request.payment.max_amount_wei).is_ok_and(|amount| amount.is_zero())
+}
+
+/// Forward each builder's single transaction via `eth_sendPrivateTransaction`
+async fn send_private_transactions(
+    state: &AppState,
+    bundle_id: Uuid,
+    bundles: &[(&BuilderConfig, Vec<String>)],
+    max_block_number: Option<u64>,
+    warnings: Vec<String>,
+) -> (StatusCode, Json<Value>) {
+    let mut submission_results = Vec::new();
+    for (builder_config, txs) in bundles {
+        let builder_name = &builder_config.name;
+        let started_at = Instant::now();
+        let result = match state.relay_client(builder_config) {
+            Ok(relay_client) => relay_client
+                .send_private_transaction(&txs[0], max_block_number)
+                .await
+                .map_err(|e| e.to_string()),
+            Err(e) => Err(e),
+        };
+        let latency_ms = started_at.elapsed().as_millis() as u64;
+
+        match result {
+            Ok(tx_hash) => {
+                tracing::info!(bundle_id = %bundle_id, builder = %builder_name, tx_hash = %tx_hash, latency_ms = latency_ms, "Private transaction sent");
+                if let Err(e) = state
+                    .database
+                    .record_relay_submission(&bundle_id.to_string(), builder_name, &tx_hash, &[], state.clock.now())
+                    .await
+                {
+                    tracing::warn!(bundle_id = %bundle_id, builder = %builder_name, error = %e, "Failed to record relay submission");
+                }
+                submission_results.push(json!({
+                    "builder": builder_name,
+                    "status": "submitted",
+                    "response": tx_hash,
+                    "latencyMs": latency_ms
+                }));
+            }
+            Err(e) => {
+                tracing::error!(bundle_id = %bundle_id, builder = %builder_name, error = %e, latency_ms = latency_ms, "Private transaction failed");
+                submission_results.push(json!({
+                    "builder": builder_name,
+                    "status": "failed",
+                    "error": e,
+                    "latencyMs": latency_ms
+                }));
+            }
+        }
     }
 
-    // Placeholder response
-    Ok((
-        StatusCode::OK,
-        Json(json!({
-            "bundleId": bundle_id,
-            "state": "queued",
-            "createdAt": "2024-01-01T12:00:00Z",
-            "updatedAt": "2024-01-01T12:00:00Z"
-        })),
-    ))
+    let submitted_count = submission_results.iter().filter(|s| s["status"] == "submitted").count();
+    (submission_status(submitted_count), Json(json!({
+        "bundleId": bundle_id,
+        "privateTransaction": true,
+        "paymentAmountWei": "0",
+        "partiallySubmitted": submitted_count > 0 && submitted_count < submission_results.len(),
+        "submissions": submission_results,
+        "warnings": warnings
+    })))
 }
 
-/// Health check endpoint
-pub async fn health_check(
-    State(state): State<Arc<AppState>>,
-) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
-    // Check database connectivity
-    let db_healthy = state.database.health_check().await.is_ok();
-    
-    let status = if db_healthy {
+/// `200 OK` when at least one builder accepted the submission, otherwise `502 Bad Gateway`
+fn submission_status(submitted_count: usize) -> StatusCode {
+    if submitted_count > 0 {
         StatusCode::OK
     } else {
-        StatusCode::SERVICE_UNAVAILABLE
-    };
+        StatusCode::BAD_GATEWAY
+    }
+}
 
-    Ok((
-        status,
-        Json(json!({
-            "status": if db_healthy { "healthy" } else { "unhealthy" },
-            "version": env!("CARGO_PKG_VERSION"),
-            "timestamp": chrono::Utc::now().to_rfc3339(),
-            "components": {
-                "database": if db_healthy { "healthy" } else { "unhealthy" },
-                "killswitch": if state.is_killswitch_active().await { "active" } else { "inactive" }
+/// Enabled builders whose active hour windows include the current UTC hour
+fn active_builders(state: &AppState) -> Vec<&BuilderConfig> {
+    let hour = state.clock.now().hour();
+    state
+        .config
+        .builders
+        .iter()
+        .filter(|b| b.enabled)
+        .filter(|b| {
+            let active = b.is_active_at_hour(hour);
+            if !active {
+                tracing::debug!(builder = %b.name, hour_utc = hour, "Skipping builder outside its active hours");
             }
-        })),
-    ))
+            active
+        })
+        .collect()
 }
 
-/// System status endpoint with more detailed information
-pub async fn system_status(
-    State(state): State<Arc<AppState>>,
-) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
-    let db_healthy = state.database.health_check().await.is_ok();
-    let killswitch_active = state.is_killswitch_active().await;
-    
-    // TODO: Add more status checks (relays, etc.)
-    
-    Ok((
-        StatusCode::OK,
-        Json(json!({
-            "service": "atomic-bundler",
-            "version": env!("CARGO_PKG_VERSION"),
-            "status": if db_healthy && !killswitch_active { "operational" } else { "degraded" },
-            "timestamp": chrono::Utc::now().to_rfc3339(),
-            "components": {
-                "database": {
-                    "status": if db_healthy { "healthy" } else { "unhealthy" }
-                },
-                "killswitch": {
-                    "active": killswitch_active
-                },
-                "configuration": {
-                    "network": state.config.network.network,
-                    "enabled_builders": state.config.builders.iter()
-                        .filter(|b| b.enabled)
-                        .map(|b| &b.name)
-                        .collect::<Vec<_>>()
-                }
+/// Forge a payment transaction for every enabled builder and pair it with tx1.
+///
+/// Returns the payment amount and the per-builder bundles.
+async fn forge_builder_bundles<'a>(
+    state: &AppState,
+    request: &BundleRequest,
+    enabled_builders: &[&'a BuilderConfig],
+    tx1_hex: &str,
+    tx1_hash: &str,
+) -> Result<(U256, Vec<(&'a BuilderConfig, Vec<String>)>), (StatusCode, Json<Value>)> {
+    let chain_id = state.config.network.chain_id.unwrap_or(1);
+
+    // Create RPC provider to get current network conditions
+    let rpc_url = rpc_url(state);
+    let provider = ProviderBuilder::new()
+        .on_http(rpc_url.parse().map_err(|_| (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({ "error": "Invalid RPC URL" }))
+        ))?);
+
+    // Get current base fee and suggested max fee from latest block
+    let latest_block = provider.get_block_by_number(alloy::rpc::types::BlockNumberOrTag::Latest, false)
+        .await
+        .map_err(|e| internal_error(state, StatusCode::INTERNAL_SERVER_ERROR, "Failed to get latest block", e))?
+        .ok_or_else(|| (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({ "error": "Latest block not found" }))
+        ))?;
+
+    // Fees derived from a lagging RPC's old block may be badly off
+    if let Some(max_staleness_seconds) = state.config.network.max_block_staleness_seconds {
+        let age_seconds = state.clock.now().timestamp() - latest_block.header.timestamp as i64;
+        if age_seconds > max_staleness_seconds as i64 {
+            tracing::warn!(
+                block = latest_block.header.number,
+                age_seconds = age_seconds,
+                max_staleness_seconds = max_staleness_seconds,
+                "Rejecting bundle: RPC latest block is stale"
+            );
+            return Err((
+                StatusCode::SERVICE_UNAVAILABLE,
+                Json(json!({
+                    "error": format!(
+                        "RPC is stale: latest block {} is {}s old (max {}s)",
+                        latest_block.header.number, age_seconds, max_staleness_seconds
+                    )
+                })),
+            ));
+        }
+    }
+
+    let base_fee_per_gas = U256::from(
+        latest_block.header.base_fee_per_gas
+            .unwrap_or(20_000_000_000u64) // 20 gwei fallback
+    );
+
+    // Optionally tip tx2 with a share of the network's suggested priority fee
+    let priority_fee_multiplier = state.config.payment.tx2_priority_fee_multiplier;
+    let max_priority_fee_per_gas: u128 = if priority_fee_multiplier > 0.0 {
+        match state.priority_fee_cache.get(&rpc_url, latest_block.header.number).await {
+            Ok(suggested) => scale_priority_fee(suggested, priority_fee_multiplier),
+            Err(e) => {
+                tracing::warn!(error = %e, "Failed to fetch suggested priority fee; using zero tip for tx2");
+                0
             }
-        })),
-    ))
+        }
+    } else {
+        0
+    };
+
+    // Gas actually used in simulation is more accurate than eth_estimateGas's upper bound
+    let simulated_gas_used = if state.config.payment.use_simulated_gas {
+        match simulator::simulate_gas_used_from_raw(&rpc_url, tx1_hex).await {
+            Ok(g) => Some(g),
+            Err(e) => {
+                tracing::warn!(error = %e, "tx1 simulation failed; falling back to gas estimation");
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    // Estimate gas for tx1 using simulator helper (decode + eth_estimateGas)
+    let estimated_gas_used: u64 = match simulated_gas_used {
+        // Add 21_000 to the simulated gas used to account for the tx2
+        Some(g) => g + 21_000u64,
+        None => match simulator::estimate_gas_from_raw(&rpc_url, tx1_hex).await {
+            // Add 21_000 to the estimated gas used to account for the tx2
+            Ok(g) => g + 21_000u64,
+            Err(e) => {
+                tracing::warn!(error = %e, "tx1 gas estimation failed; defaulting to 21000");
+                21_000u64
+            }
+        },
+    };
+
+    tracing::info!(estimated_gas_used = estimated_gas_used, "Estimated gas used for tx1");
+
+    // Calculate payment using PaymentCalculator to get priority fee
+    let calculator = PaymentCalculator::new();
+    let payment_params = forge_payment_params(
+        &state.config.payment,
+        request_formula(request),
+        estimated_gas_used,
+        base_fee_per_gas,
+        U256::from(max_priority_fee_per_gas),
+    )
+        .map_err(|e| internal_error(state, StatusCode::INTERNAL_SERVER_ERROR, "Invalid payment parameters", e))?;
+
+    let payment_result = calculator.calculate_payment(&payment_params)
+        .map_err(|e| internal_error(state, StatusCode::INTERNAL_SERVER_ERROR, "Payment calculation failed", e))?;
+
+    if payment_result.was_capped {
+        state.metrics.observe_capped_payment(CapReason::MaxAmount);
+    }
+
+    let flat_amount_wei = split_payment(
+        state.config.payment.split,
+        safe_payment_amount(state, payment_result.amount_wei).await,
+        enabled_builders.len(),
+    );
+
+    let base_fee_max_fee: u128 = (((base_fee_per_gas * U256::from(3)) / U256::from(2))
+        + U256::from(max_priority_fee_per_gas))
+        .try_into()
+        .unwrap_or(2_000_000_000u128);
+
+    // Optionally follow the fee-history suggestion instead, which tracks fee volatility better
+    let max_fee_multiplier = state.config.payment.tx2_max_fee_multiplier;
+    let max_fee_per_gas: u128 = if max_fee_multiplier > 0.0 {
+        match fee_history_max_fee(&rpc_url, max_fee_multiplier).await {
+            Ok(suggested) => suggested.max(max_priority_fee_per_gas),
+            Err(e) => {
+                tracing::warn!(error = %e, "Failed to fetch fee history; deriving tx2 max fee from base fee");
+                base_fee_max_fee
+            }
+        }
+    } else {
+        base_fee_max_fee
+    };
+
+    // In very low-fee periods the derived max fee may be too low for relays to accept tx2
+    let min_max_fee = (state.config.payment.tx2_min_max_fee_gwei * 1e9) as u128;
+    let max_fee_per_gas = max_fee_per_gas.max(min_max_fee);
+
+    // Resolve each builder's payment address and tx2 gas limit (higher for contract targets)
+    let mut builder_targets = Vec::with_capacity(enabled_builders.len());
+    for builder in enabled_builders.iter() {
+        let builder_addr = Address::from_str(builder.payment_address.as_str())
+            .map_err(|_| (
+                StatusCode::BAD_REQUEST,
+                Json(json!({ "error": format!("Invalid builder payment address for {}", builder.name) }))
+            ))?;
+        let gas_limit = tx2_gas_limit(&rpc_url, builder_addr, state.config.payment.tx2_contract_gas_limit).await;
+        builder_targets.push((*builder, builder_addr, gas_limit));
+    }
+
+    let forger = PaymentTransactionForger::new();
+
+    // Create a bundle for each enabled builder
+    let mut bundles = Vec::new();
+
+    // Each signer pays from its own nonce sequence and balance
+    for plan in plan_signers(&state.config, builder_targets)? {
+        let signer_addr = plan.address;
+
+        // The balance check covers the most expensive tx2 this signer may forge
+        let gas_limit: u64 = plan
+            .builders
+            .iter()
+            .map(|(_, _, gas_limit)| *gas_limit)
+            .max()
+            .unwrap_or(crate::fees::EOA_TRANSFER_GAS_LIMIT);
+
+        // The startup warmup spares the first submission a nonce fetch
+        let base_nonce: u64 = match state.nonce_manager.take(signer_addr).await {
+            Some(nonce) => nonce,
+            None => provider.get_transaction_count(signer_addr)
+                .await
+                .map_err(|e| internal_error(state, StatusCode::INTERNAL_SERVER_ERROR, "Failed to get nonce", e))?
+                .try_into()
+                .unwrap_or(0),
+        };
+
+        // Ensure payment signer has enough balance for value + max gas cost
+        let signer_balance = provider.get_balance(signer_addr)
+            .await
+            .map_err(|e| internal_error(state, StatusCode::INTERNAL_SERVER_ERROR, "Failed to get balance", e))?;
+
+        let required_wei = required_balance_wei(gas_limit, U256::from(max_fee_per_gas), flat_amount_wei)?;
+
+        if signer_balance < required_wei && state.config.payment.skip_balance_check {
+            tracing::warn!(
+                signer = %format!("0x{:x}", signer_addr),
+                balance_wei = %signer_balance,
+                required_wei = %required_wei,
+                "Insufficient balance for tx2 (value + max gas); proceeding because payment.skip_balance_check is set"
+            );
+        }
+
+        if !balance_check_passes(signer_balance, required_wei, state.config.payment.skip_balance_check) {
+            tracing::warn!(
+                signer = %format!("0x{:x}", signer_addr),
+                balance_wei = %signer_balance,
+                required_wei = %required_wei,
+                gas_limit = gas_limit,
+                max_fee_per_gas = max_fee_per_gas,
+                payment_wei = %flat_amount_wei,
+                "Insufficient balance for tx2 (value + max gas). Consider lowering payment or max fee"
+            );
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json(json!({
+                    "error": INSUFFICIENT_BALANCE_ERROR,
+                    "signer": format!("0x{:x}", signer_addr),
+                    "balanceWei": format!("{}", signer_balance),
+                    "requiredWei": format!("{}", required_wei)
+                }))
+            ));
+        }
+
+        for (builder, builder_addr, builder_gas_limit) in plan.builders {
+            let forged = match state.config.payment.tx2_type {
+                Tx2Type::Eip1559 => {
+                    forger
+                        .forge_flat_transfer_hex(
+                            builder_addr,
+                            flat_amount_wei,
+                            chain_id,
+                            base_nonce,
+                            max_fee_per_gas,
+                            max_priority_fee_per_gas,
+                            builder_gas_limit,
+                            &plan.key,
+                        )
+                        .await
+                }
+                // Without a separate tip, the legacy gas price covers the whole max fee
+                Tx2Type::Legacy => {
+                    forger
+                        .forge_legacy_transfer_hex(
+                            builder_addr,
+                            flat_amount_wei,
+                            chain_id,
+                            base_nonce,
+                            max_fee_per_gas,
+                            builder_gas_limit,
+                            &plan.key,
+                        )
+                        .await
+                }
+            };
+            let (tx2_hex, tx2_hash) = forged
+                .map_err(|e| internal_error(
+                    state,
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    &format!("failed to forge tx2 for {}", builder.name),
+                    e,
+                ))?;
+
+            // Log the tx2 hash for this builder
+            tracing::info!(
+                builder = %builder.name,
+                signer = %format!("0x{:x}", signer_addr),
+                tx2_hash = %state.config.logging.log_hash(&tx2_hash),
+                tx2_to = %builder_addr,
+                tx2_value_wei = %flat_amount_wei,
+                tx2_gas_limit = builder_gas_limit,
+                tx1_hash = %state.config.logging.log_hash(tx1_hash),
+                "Forged tx2 payment transaction for builder"
+            );
+
+            let txs = vec![tx1_hex.to_string(), tx2_hex.clone()];
+            bundles.push((builder, txs));
+        }
+    }
+
+    Ok((flat_amount_wei, bundles))
 }
 
-/// Reload configuration (admin endpoint)
-pub async fn reload_config(
-    State(_state): State<Arc<AppState>>,
-) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
-    // TODO: Implement config reloading
-    tracing::info!("Configuration reload requested");
-    
-    Ok((
-        StatusCode::OK,
-        Json(json!({
-            "message": "Configuration reload initiated",
-            "timestamp": chrono::Utc::now().to_rfc3339()
-        })),
-    ))
+/// Each builder's share of `amount_wei` under `split`
+fn split_payment(split: PaymentSplit, amount_wei: U256, builder_count: usize) -> U256 {
+    match split {
+        PaymentSplit::Full => amount_wei,
+        PaymentSplit::Proportional => amount_wei / U256::from(builder_count.max(1)),
+    }
 }
 
-/// Toggle killswitch (admin endpoint)
-pub async fn toggle_killswitch(
-    State(state): State<Arc<AppState>>,
-    Json(payload): Json<Value>,
-) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
-    let activate = payload
-        .get("activate")
-        .and_then(|v| v.as_bool())
-        .unwrap_or(true);
+/// Environment variable holding the default tx2 signer key
+pub(crate) const DEFAULT_SIGNER_KEY_ENV: &str = "PAYMENT_SIGNER_PRIVATE_KEY";
 
-    if activate {
-        state.activate_killswitch().await;
-    } else {
-        state.deactivate_killswitch().await;
+/// Error returned when a tx2 signer cannot cover the payment plus its max gas cost
+const INSUFFICIENT_BALANCE_ERROR: &str = "Insufficient balance for tx2 (value + max gas)";
+
+/// Error returned when tx2's max gas cost plus payment doesn't fit in 256 bits
+const GAS_COST_OVERFLOW_ERROR: &str = "GAS_COST_OVERFLOW";
+
+/// Balance a tx2 signer needs to cover `payment_wei` plus `gas_limit` at `max_fee_per_gas`.
+///
+/// Overflow is reported as [`GAS_COST_OVERFLOW_ERROR`] rather than failing the balance check.
+fn required_balance_wei(
+    gas_limit: u64,
+    max_fee_per_gas: U256,
+    payment_wei: U256,
+) -> Result<U256, (StatusCode, Json<Value>)> {
+    U256::from(gas_limit)
+        .checked_mul(max_fee_per_gas)
+        .and_then(|max_gas_cost| max_gas_cost.checked_add(payment_wei))
+        .ok_or_else(|| {
+            tracing::warn!(
+                gas_limit = gas_limit,
+                max_fee_per_gas = %max_fee_per_gas,
+                payment_wei = %payment_wei,
+                "tx2 max gas cost plus payment overflows U256"
+            );
+            (
+                StatusCode::BAD_REQUEST,
+                Json(json!({
+                    "error": GAS_COST_OVERFLOW_ERROR,
+                    "message": "tx2 max gas cost plus payment exceeds the largest representable amount",
+                    "gasLimit": gas_limit,
+                    "maxFeePerGas": max_fee_per_gas.to_string(),
+                    "paymentWei": payment_wei.to_string()
+                })),
+            )
+        })
+}
+
+/// A tx2 signer and the builders (with payment address and gas limit) it pays
+struct SignerPlan<'a> {
+    key: String,
+    address: Address,
+    builders: Vec<(&'a BuilderConfig, Address, u64)>,
+}
+
+/// Group builder payment targets by the signer configured to fund them.
+///
+/// Builders without a `signer_key_ref` use the default signer. Refs resolving to the same key
+/// share one plan so they draw from a single nonce.
+fn plan_signers<'a>(
+    config: &config::Config,
+    builder_targets: Vec<(&'a BuilderConfig, Address, u64)>,
+) -> Result<Vec<SignerPlan<'a>>, (StatusCode, Json<Value>)> {
+    let mut plans: Vec<SignerPlan<'a>> = Vec::new();
+    for target in builder_targets {
+        let key = signer_key(config, target.0).map_err(|e| (
+            StatusCode::BAD_REQUEST,
+            Json(json!({ "error": e }))
+        ))?;
+        let address = signer_address(&key).map_err(|e| (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({ "error": e }))
+        ))?;
+
+        match plans.iter_mut().find(|plan| plan.address == address) {
+            Some(plan) => plan.builders.push(target),
+            None => plans.push(SignerPlan { key, address, builders: vec![target] }),
+        }
     }
+    Ok(plans)
+}
 
-    Ok((
-        StatusCode::OK,
-        Json(json!({
-            "killswitch": if activate { "activated" } else { "deactivated" },
-            "timestamp": chrono::Utc::now().to_rfc3339()
-        })),
-    ))
+/// The tx2 signer key configured for `builder` (its `signer_key_ref`, else the default signer)
+fn signer_key(config: &config::Config, builder: &BuilderConfig) -> Result<String, String> {
+    let key_env = match &builder.signer_key_ref {
+        None => DEFAULT_SIGNER_KEY_ENV,
+        Some(signer_ref) => config
+            .payment
+            .signers
+            .get(signer_ref)
+            .ok_or_else(|| format!("Unknown signer_key_ref {} for builder {}", signer_ref, builder.name))?,
+    };
+    std::env::var(key_env).map_err(|_| format!("{} missing", key_env))
 }
 
-/// Admin metrics endpoint
-pub async fn admin_metrics(
-    State(_state): State<Arc<AppState>>,
-) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
-    // TODO: Implement metrics collection
-    Ok((
-        StatusCode::OK,
-        Json(json!({
-            "metrics": {
-                "bundles_submitted_total": 0,
-                "bundles_landed_total": 0,
-                "uptime_seconds": 0
-            },
-            "timestamp": chrono::Utc::now().to_rfc3339()
-        })),
+/// Address of the tx2 signer holding `key`
+fn signer_address(key: &str) -> Result<Address, String> {
+    alloy::signers::local::PrivateKeySigner::from_str(key)
+        .map(|signer| signer.address())
+        .map_err(|_| "Invalid signer key format".to_string())
+}
+
+/// Re-forge a forged tx2 for `builder` at its signer's current on-chain nonce.
+///
+/// Every other field is carried over from `tx2_hex`. Returns the new raw tx2 and its nonce.
+async fn reforge_tx2_with_fresh_nonce(
+    state: &AppState,
+    builder: &BuilderConfig,
+    tx2_hex: &str,
+) -> anyhow::Result<(String, u64)> {
+    let key = signer_key(&state.config, builder).map_err(anyhow::Error::msg)?;
+    let signer = alloy::signers::local::PrivateKeySigner::from_str(&key)?;
+    let tx2 = simulator::decode_raw_transaction(tx2_hex)?;
+    let TxKind::Call(to) = tx2.to() else {
+        anyhow::bail!("tx2 has no recipient");
+    };
+
+    let nonce = state.nonce_manager.refresh(&rpc_url(state), signer.address()).await?;
+    let forger = PaymentTransactionForger::new();
+    let (tx2_hex, _) = match tx2.gas_price() {
+        Some(gas_price) => {
+            forger
+                .forge_legacy_transfer_hex(to, tx2.value(), tx2.chain_id().unwrap_or(1), nonce, gas_price, tx2.gas_limit(), &key)
+                .await?
+        }
+        None => {
+            forger
+                .forge_flat_transfer_hex(
+                    to,
+                    tx2.value(),
+                    tx2.chain_id().unwrap_or(1),
+                    nonce,
+                    tx2.max_fee_per_gas(),
+                    tx2.max_priority_fee_per_gas().unwrap_or(0),
+                    tx2.gas_limit(),
+                    &key,
+                )
+                .await?
+        }
+    };
+    Ok((tx2_hex, nonce))
+}
+
+/// Warn when the payment is conspicuously low next to what recent blocks paid in tips.
+///
+/// The reference is the recent median priority fee times the bundle's gas (tx1's gas limit plus
+/// a transfer for tx2). Disabled unless `payment.low_payment_warning_blocks` is set; fee history
+/// failures are logged and skipped.
+async fn low_payment_warning(state: &AppState, tx1_hex: &str, payment_wei: U256) -> Option<String> {
+    let payment_config = &state.config.payment;
+    if payment_config.low_payment_warning_blocks == 0 {
+        return None;
+    }
+
+    let tx1_gas = simulator::decode_raw_transaction(tx1_hex).ok()?.gas_limit();
+    let median_tip = match recent_median_priority_fee(&rpc_url(state), payment_config.low_payment_warning_blocks).await {
+        Ok(tip) => tip,
+        Err(e) => {
+            tracing::warn!(error = %e, "Failed to fetch fee history; skipping low payment check");
+            return None;
+        }
+    };
+
+    let reference_wei = U256::from(tx1_gas + crate::fees::EOA_TRANSFER_GAS_LIMIT) * U256::from(median_tip);
+    let threshold_wei = U256::from((u128::try_from(reference_wei).unwrap_or(u128::MAX) as f64
+        * payment_config.low_payment_warning_ratio) as u128);
+    if payment_wei >= threshold_wei {
+        return None;
+    }
+
+    tracing::warn!(
+        payment_wei = %payment_wei,
+        reference_wei = %reference_wei,
+        "Payment is low relative to recent inclusion costs"
+    );
+    Some(format!(
+        "Payment of {} wei is below {}% of recent inclusion cost ({} wei at the median tip of the last {} blocks)",
+        payment_wei,
+        payment_config.low_payment_warning_ratio * 100.0,
+        reference_wei,
+        payment_config.low_payment_warning_blocks
     ))
 }
+
+/// Apply safe mode to a calculated payment.
+///
+/// A payment above the emergency stop threshold switches safe mode on; while safe mode is
+/// active every payment is clamped to the safe ceiling.
+async fn safe_payment_amount(state: &AppState, amount_wei: U256) -> U256 {
+    let Ok(limits) = state.config.parse_limits() else {
+        return amount_wei;
+    };
+
+    if limits.emergency_stop_enabled
+        && amount_wei > limits.emergency_stop_threshold_wei
+        && !state.is_safe_mode_active().await
+    {
+        state
+            .activate_safe_mode(&format!(
+                "payment {} wei exceeded emergency stop threshold {} wei",
+                amount_wei, limits.emergency_stop_threshold_wei
+            ))
+            .await;
+    }
+
+    if state.is_safe_mode_active().await && amount_wei > limits.safe_mode_ceiling_wei {
+        tracing::warn!(
+            payment_wei = %amount_wei,
+            ceiling_wei = %limits.safe_mode_ceiling_wei,
+            "Safe mode active; clamping payment to the safe ceiling"
+        );
+        state.metrics.observe_capped_payment(CapReason::SafeMode);
+        return limits.safe_mode_ceiling_wei;
+    }
+
+    amount_wei
+}
+
+/// The spending policy configured under `limits`
+fn spending_enforcer(state: &AppState) -> Result<PaymentPolicyEnforcer, String> {
+    let limits = state.config.parse_limits()?;
+    Ok(PaymentPolicyEnforcer::new(PaymentPolicy {
+        per_bundle_cap_wei: limits.per_bundle_cap_wei,
+        daily_cap_wei: limits.daily_cap_wei,
+        monthly_cap_wei: limits.monthly_cap_wei,
+        emergency_stop_enabled: limits.emergency_stop_enabled,
+        emergency_stop_threshold_wei: limits.emergency_stop_threshold_wei,
+    }))
+}
+
+/// Add a payment to today's spending, or reject it with a 429 if it would break the per-bundle,
+/// daily or monthly spending cap.
+///
+/// The cap check and the addition happen in one database transaction. Returns the day the
+/// payment was booked on, for [`release_spending`].
+async fn reserve_spending(
+    state: &AppState,
+    bundle_id: Uuid,
+    payment_wei: U256,
+) -> Result<NaiveDate, (StatusCode, Json<Value>)> {
+    let enforcer = spending_enforcer(state)
+        .map_err(|e| internal_error(state, StatusCode::INTERNAL_SERVER_ERROR, "Invalid spending limits", e))?;
+    let now = state.clock.now();
+    let reservation = state
+        .database
+        .reserve_daily_spending(now.date_naive(), payment_wei, now, |today, month_to_date| {
+            enforcer.cap_violation(payment_wei, today, month_to_date)
+        })
+        .await
+        .map_err(|e| internal_error(state, StatusCode::INTERNAL_SERVER_ERROR, "Failed to reserve daily spending", e))?;
+
+    match reservation {
+        Ok(today) => Ok(today.date),
+        Err(violation) => {
+            tracing::warn!(bundle_id = %bundle_id, reason = %violation, "Rejecting bundle: spending cap reached");
+            Err((StatusCode::TOO_MANY_REQUESTS, Json(json!({ "error": violation }))))
+        }
+    }
+}
+
+/// Give back a reservation made by [`reserve_spending`] for a bundle no builder accepted
+async fn release_spending(state: &AppState, bundle_id: Uuid, date: NaiveDate, payment_wei: U256) {
+    if let Err(e) = state.database.release_daily_spending(date, payment_wei, state.clock.now()).await {
+        tracing::warn!(bundle_id = %bundle_id, error = %e, "Failed to release reserved daily spending");
+    }
+}
+
+/// Payment parameters for a forged tx2; k1, k2 and the max amount come straight from config
+fn forge_payment_params(
+    payment_config: &PaymentConfig,
+    formula: PaymentFormula,
+    gas_used: u64,
+    base_fee_per_gas: U256,
+    max_priority_fee_per_gas: U256,
+) -> Result<PaymentParams, PaymentError> {
+    PaymentParams::builder()
+        .gas_used(gas_used)
+        .base_fee_per_gas(base_fee_per_gas)
+        .max_priority_fee_per_gas(max_priority_fee_per_gas)
+        .formula(formula)
+        .config(payment_config)
+        .build()
+}
+
+/// Pair tx1 with a client-supplied tx2 for every builder the tx2 pays.
+///
+/// Returns the payment amount carried by tx2 and the per-builder bundles.
+async fn use_supplied_tx2<'a>(
+    state: &AppState,
+    tx2_hex: &str,
+    enabled_builders: &[&'a BuilderConfig],
+    tx1_hex: &str,
+    tx1_hash: &str,
+) -> Result<(U256, Vec<(&'a BuilderConfig, Vec<String>)>), (StatusCode, Json<Value>)> {
+    let builder_addresses: Vec<Address> = enabled_builders
+        .iter()
+        .filter_map(|b| Address::from_str(b.payment_address.as_str()).ok())
+        .collect();
+
+    // Supplied payments are held to the same caps as forged ones
+    let mut cap_wei = state.config.payment.max_amount_wei;
+    if let Ok(limits) = state.config.parse_limits() {
+        cap_wei = cap_wei.min(limits.per_bundle_cap_wei);
+        if state.is_safe_mode_active().await {
+            cap_wei = cap_wei.min(limits.safe_mode_ceiling_wei);
+        }
+    }
+
+    let (recipient, amount_wei) = validate_supplied_tx2(tx2_hex, &builder_addresses, cap_wei)
+        .map_err(|e| (
+            StatusCode::BAD_REQUEST,
+            Json(json!({ "error": e }))
+        ))?;
+
+    let tx2_hash = raw_tx_hash(tx2_hex);
+    let mut bundles = Vec::new();
+    for builder in enabled_builders.iter() {
+        if Address::from_str(builder.payment_address.as_str()).ok() != Some(recipient) {
+            continue;
+        }
+
+        tracing::info!(
+            builder = %builder.name,
+            tx2_hash = %state.config.logging.log_hash(&tx2_hash),
+            tx2_to = %recipient,
+            tx2_value_wei = %amount_wei,
+            tx1_hash = %state.config.logging.log_hash(tx1_hash),
+            "Using client-supplied tx2 payment transaction for builder"
+        );
+
+        bundles.push((*builder, vec![tx1_hex.to_string(), tx2_hex.to_string()]));
+    }
+
+    Ok((amount_wei, bundles))
+}
+
+/// Validate that a supplied tx2 pays an enabled builder a non-zero amount within the cap.
+///
+/// Returns the payment recipient and amount.
+fn validate_supplied_tx2(
+    tx2_hex: &str,
+    builder_addresses: &[Address],
+    cap_wei: U256,
+) -> Result<(Address, U256), String> {
+    let envelope = simulator::decode_raw_transaction(tx2_hex)
+        .map_err(|e| format!("Invalid tx2: {}", e))?;
+
+    let recipient = match envelope.to() {
+        TxKind::Call(addr) => addr,
+        TxKind::Create => return Err("tx2 must be a payment, not a contract creation".to_string()),
+    };
+
+    if !builder_addresses.contains(&recipient) {
+        return Err(format!(
+            "tx2 pays {} which is not the payment address of an enabled builder",
+            recipient
+        ));
+    }
+
+    let amount_wei = envelope.value();
+    if amount_wei == U256::ZERO {
+        return Err("tx2 must transfer a non-zero payment".to_string());
+    }
+
+    if amount_wei > cap_wei {
+        return Err(format!("tx2 payment {} exceeds cap {}", amount_wei, cap_wei));
+    }
+
+    Ok((recipient, amount_wei))
+}
+
+/// RPC endpoint used for chain queries: `ETH_RPC_URL`, then `network.rpc_url`, then localhost
+pub(crate) fn rpc_url(state: &AppState) -> String {
+    std::env::var("ETH_RPC_URL")
+        .ok()
+        .or_else(|| state.config.network.rpc_url.clone())
+        .unwrap_or_else(|| "http://localhost:8545".to_string())
+}
+
+/// The block after the current chain head
+pub(crate) async fn next_block(rpc_url: &str) -> anyhow::Result<u64> {
+    let provider = ProviderBuilder::new().on_http(rpc_url.parse()?);
+    Ok(provider.get_block_number().await? + 1)
+}
+
+/// Return the chain head if `target_block` is no longer ahead of it.
+///
+/// If the head cannot be fetched the submission proceeds and the relay decides.
+async fn passed_target_block_head(rpc_url: &str, target_block: u64) -> Option<u64> {
+    let provider = ProviderBuilder::new().on_http(rpc_url.parse().ok()?);
+    let chain_head = match provider.get_block_number().await {
+        Ok(head) => head,
+        Err(e) => {
+            tracing::warn!(error = %e, "Failed to fetch chain head; not checking target block deadline");
+            return None;
+        }
+    };
+
+    if target_block <= chain_head {
+        return Some(chain_head);
+    }
+
+    tracing::debug!(
+        target_block = target_block,
+        chain_head = chain_head,
+        eta_seconds = (target_block - chain_head) * config::SECONDS_PER_BLOCK,
+        "Target block is ahead of the chain head"
+    );
+    None
+}
+
+/// Ensure tx1 is non-empty, decodes to a transaction envelope and carries a recoverable signature.
+///
+/// EIP-7702 transactions must also carry at least one authorization. tx1 is forwarded to relays as
+/// the raw bytes received, so its authorization list reaches them unchanged.
+fn validate_tx1(tx1: &Bytes) -> Result<(), TransactionError> {
+    if tx1.is_empty() {
+        return Err(TransactionError::InvalidFormat("empty transaction".to_string()));
+    }
+    let envelope = simulator::decode_raw_transaction(&tx1.to_string())
+        .map_err(|e| TransactionError::InvalidFormat(e.to_string()))?;
+    if envelope.authorization_list().is_some_and(|auth| auth.is_empty()) {
+        return Err(TransactionError::InvalidFormat(
+            "EIP-7702 transaction has an empty authorization list".to_string(),
+        ));
+    }
+    simulator::recover_sender(&envelope).map_err(|_| TransactionError::InvalidSignature)?;
+    Ok(())
+}
+
+/// Parse a request's payment formula, falling back to the configured formula for unknown
+/// values unless `payment.strict_formula` is set
+fn resolve_payment_formula(formula: &str, payment_config: &PaymentConfig) -> Result<PaymentFormula, String> {
+    match PaymentFormula::from_str(formula) {
+        Ok(formula) => Ok(formula),
+        Err(e) if payment_config.strict_formula => Err(e),
+        Err(e) => {
+            tracing::warn!(
+                error = %e,
+                fallback = payment_config.formula.as_str(),
+                "Unknown payment formula; using the configured default"
+            );
+            Ok(payment_config.formula.clone())
+        }
+    }
+}
+
+/// The payment formula of a request already normalized by [`resolve_payment_formula`]
+fn request_formula(request: &BundleRequest) -> PaymentFormula {
+    PaymentFormula::from_str(&request.payment.formula).unwrap_or_default()
+}
+
+/// Whether `expiry` lies further than `max_expiry_seconds` past `now`
+fn expiry_exceeds_cap(expiry: DateTime<Utc>, now: DateTime<Utc>, max_expiry_seconds: u64) -> bool {
+    expiry - now > chrono::Duration::seconds(max_expiry_seconds as i64)
+}
+
+/// Whether the signer balance gate allows forging tx2
+fn balance_check_passes(signer_balance: U256, required_wei: U256, skip_balance_check: bool) -> bool {
+    skip_balance_check || signer_balance >= required_wei
+}
+
+/// Return the hash of the first transaction that appears more than once in a bundle
+fn find_duplicate_tx(txs: &[String]) -> Option<String> {
+    let mut seen = std::collections::HashSet::new();
+    txs.iter()
+        .map(|tx| raw_tx_hash(tx))
+        .find(|hash| !seen.insert(hash.clone()))
+}
+
+/// Compute the transaction hash (keccak256 of the raw signed bytes) of a raw tx hex
+fn raw_tx_hash(raw_tx_hex: &str) -> String {
+    let raw = raw_tx_hex.trim_start_matches("0x");
+    match alloy::hex::decode(raw) {
+        Ok(bytes) => format!("0x{}", alloy::hex::encode(keccak256(&bytes))),
+        Err(_) => "0x".to_string(),
+    }
+}
+
+/// Get bundle status by ID
+pub async fn get_bundle_status(
+    State(state): State<Arc<AppState>>,
+    Path(bundle_id): Path<String>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    tracing::info!("Bundle status request for ID: {}", bundle_id);
+    
+    // Validate bundle ID format
+    if Uuid::parse_str(&bundle_id).is_err() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({
+                "error": "Invalid bundle ID format"
+            })),
+        ));
+    }
+
+    let record = state
+        .bundle_status_cache
+        .get_or_load(&bundle_id, state.clock.now(), || state.database.get_bundle(&bundle_id))
+        .await
+        .map_err(|e| internal_error(&state, StatusCode::INTERNAL_SERVER_ERROR, "Failed to look up bundle", e))?
+        .ok_or_else(|| (
+            StatusCode::NOT_FOUND,
+            Json(json!({ "error": "Bundle not found" }))
+        ))?;
+
+    Ok((
+        StatusCode::OK,
+        Json(json!({
+            "bundleId": record.id,
+            "state": record.state,
+            "partiallySubmitted": record.partially_submitted,
+            "tx1Hash": record.tx1_hash,
+            "createdAt": record.created_at.to_rfc3339(),
+            "updatedAt": record.updated_at.to_rfc3339(),
+            "ttlSeconds": record
+                .expires_at
+                .map(|expires_at| types::utils::time_to_expiry(expires_at, state.clock.now()).as_secs())
+        })),
+    ))
+}
+
+/// Health check endpoint
+pub async fn health_check(
+    State(state): State<Arc<AppState>>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    // Check database connectivity
+    let db_healthy = state.database.health_check().await.is_ok();
+    
+    let status = if db_healthy {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+
+    Ok((
+        status,
+        Json(json!({
+            "status": if db_healthy { "healthy" } else { "unhealthy" },
+            "version": env!("CARGO_PKG_VERSION"),
+            "timestamp": chrono::Utc::now().to_rfc3339(),
+            "components": {
+                "database": if db_healthy { "healthy" } else { "unhealthy" },
+                "killswitch": if state.is_killswitch_active().await { "active" } else { "inactive" }
+            }
+        })),
+    ))
+}
+
+/// Readiness endpoint: database and RPC must both be reachable
+pub async fn readiness_check(
+    State(state): State<Arc<AppState>>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    let db_healthy = state.database.health_check().await.is_ok();
+    let block_number = state.rpc_health.block_number(&rpc_url(&state), state.clock.now()).await;
+    let ready = db_healthy && block_number.is_some();
+
+    let status = if ready {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+
+    Ok((
+        status,
+        Json(json!({
+            "status": if ready { "ready" } else { "not_ready" },
+            "timestamp": chrono::Utc::now().to_rfc3339(),
+            "components": {
+                "database": if db_healthy { "healthy" } else { "unhealthy" },
+                "rpc": {
+                    "status": if block_number.is_some() { "healthy" } else { "unhealthy" },
+                    "blockNumber": block_number
+                }
+            }
+        })),
+    ))
+}
+
+/// System status endpoint with more detailed information
+pub async fn system_status(
+    State(state): State<Arc<AppState>>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    let db_healthy = state.database.health_check().await.is_ok();
+    let killswitch_active = state.is_killswitch_active().await;
+    let relays = state.relay_health.read().await.get_all_health().to_vec();
+    let relays_healthy = relays.iter().all(|relay| relay.status != types::RelayHealth::Unhealthy);
+    
+    Ok((
+        StatusCode::OK,
+        Json(json!({
+            "service": "atomic-bundler",
+            "version": env!("CARGO_PKG_VERSION"),
+            "status": if db_healthy && !killswitch_active && relays_healthy { "operational" } else { "degraded" },
+            "timestamp": chrono::Utc::now().to_rfc3339(),
+            "components": {
+                "database": {
+                    "status": if db_healthy { "healthy" } else { "unhealthy" }
+                },
+                "killswitch": {
+                    "active": killswitch_active
+                },
+                "relays": relays,
+                "configuration": {
+                    "network": state.config.network.network,
+                    "enabled_builders": state.config.builders.iter()
+                        .filter(|b| b.enabled)
+                        .map(|b| &b.name)
+                        .collect::<Vec<_>>()
+                }
+            }
+        })),
+    ))
+}
+
+/// Reload configuration (admin endpoint)
+pub async fn reload_config(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    // TODO: Implement config reloading
+    tracing::info!("Configuration reload requested");
+    audit_admin_action(&state, &headers, "config_reload", json!({})).await;
+    
+    Ok((
+        StatusCode::OK,
+        Json(json!({
+            "message": "Configuration reload initiated",
+            "timestamp": chrono::Utc::now().to_rfc3339()
+        })),
+    ))
+}
+
+/// Export the effective configuration as YAML with secrets redacted
+pub async fn export_config(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    match state.config.to_redacted_yaml() {
+        Ok(yaml) => (StatusCode::OK, [(header::CONTENT_TYPE, "application/yaml")], yaml).into_response(),
+        Err(e) => {
+            internal_error(&state, StatusCode::INTERNAL_SERVER_ERROR, "Failed to export configuration", e)
+                .into_response()
+        }
+    }
+}
+
+/// Toggle killswitch (admin endpoint)
+pub async fn toggle_killswitch(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(payload): Json<Value>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    let activate = payload
+        .get("activate")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(true);
+
+    if activate {
+        state.activate_killswitch().await;
+    } else {
+        state.deactivate_killswitch().await;
+    }
+    audit_admin_action(&state, &headers, "killswitch", json!({ "activate": activate })).await;
+
+    Ok((
+        StatusCode::OK,
+        Json(json!({
+            "killswitch": if activate { "activated" } else { "deactivated" },
+            "timestamp": chrono::Utc::now().to_rfc3339()
+        })),
+    ))
+}
+
+/// Prometheus metrics endpoint
+pub async fn prometheus_metrics(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    if !state.config.metrics.enabled {
+        return (StatusCode::NOT_FOUND, "metrics disabled\n".to_string()).into_response();
+    }
+
+    (
+        StatusCode::OK,
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        state.metrics.render(&state.config.metrics.namespace),
+    )
+        .into_response()
+}
+
+/// Toggle safe mode
+pub async fn toggle_safe_mode(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(payload): Json<Value>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    let activate = payload
+        .get("activate")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(true);
+
+    if activate {
+        state.activate_safe_mode("activated via admin API").await;
+    } else {
+        state.deactivate_safe_mode().await;
+    }
+    audit_admin_action(&state, &headers, "safe_mode", json!({ "activate": activate })).await;
+
+    Ok((
+        StatusCode::OK,
+        Json(json!({
+            "safeMode": if activate { "activated" } else { "deactivated" },
+            "timestamp": chrono::Utc::now().to_rfc3339()
+        })),
+    ))
+}
+
+/// Admin metrics endpoint
+pub async fn admin_metrics(
+    State(_state): State<Arc<AppState>>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    // TODO: Implement metrics collection
+    Ok((
+        StatusCode::OK,
+        Json(json!({
+            "metrics": {
+                "bundles_submitted_total": 0,
+                "bundles_landed_total": 0,
+                "uptime_seconds": 0
+            },
+            "timestamp": chrono::Utc::now().to_rfc3339()
+        })),
+    ))
+}
+
+/// Cancel every queued or sent bundle at its relays and mark it failed
+pub async fn cancel_all_bundles(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    let mut in_flight = Vec::new();
+    for bundle_state in ["queued", "sent"] {
+        let bundles = state.database.bundles_in_state(bundle_state).await.map_err(|e| {
+            internal_error(&state, StatusCode::INTERNAL_SERVER_ERROR, "Failed to load bundles", e)
+        })?;
+        in_flight.extend(bundles.into_iter().map(|(id, _)| id));
+    }
+
+    let mut cancelled = Vec::new();
+    for bundle_id in in_flight {
+        let relay_errors = cancel_at_relays(&state, &bundle_id).await;
+        state.set_bundle_state(&bundle_id, "failed").await.map_err(|e| {
+            internal_error(&state, StatusCode::INTERNAL_SERVER_ERROR, &format!("Failed to mark bundle {} failed", bundle_id), e)
+        })?;
+        cancelled.push(json!({ "bundleId": bundle_id, "relayErrors": relay_errors }));
+    }
+
+    tracing::warn!(count = cancelled.len(), "Cancelled all in-flight bundles");
+    audit_admin_action(&state, &headers, "cancel_all_bundles", json!({ "cancelled": cancelled.len() })).await;
+    Ok((StatusCode::OK, Json(json!({
+        "cancelled": cancelled.len(),
+        "bundles": cancelled
+    }))))
+}
+
+/// Cancel a bundle at every relay that accepted it, returning the relay errors
+async fn cancel_at_relays(state: &AppState, bundle_id: &str) -> Vec<String> {
+    let Ok(uuid) = Uuid::parse_str(bundle_id) else {
+        return vec![format!("bundle id {} is not a UUID", bundle_id)];
+    };
+    state.resubmissions.forget(uuid).await;
+
+    let submissions = match state.database.relay_submission_targets(bundle_id).await {
+        Ok(submissions) => submissions,
+        Err(e) => return vec![e.to_string()],
+    };
+
+    let mut errors = Vec::new();
+    for (relay_name, target_blocks) in submissions {
+        let Some(builder) = state.config.builders.iter().find(|b| b.name == relay_name) else {
+            continue;
+        };
+        let relay = match state.relay_client(builder) {
+            Ok(relay) => relay,
+            Err(e) => {
+                errors.push(format!("{}: {}", relay_name, e));
+                continue;
+            }
+        };
+
+        // Each target block was submitted under its own replacement UUID
+        let replacement_uuids: Vec<Uuid> = if target_blocks.is_empty() {
+            vec![relay_client::block_replacement_uuid(uuid, None)]
+        } else {
+            target_blocks.iter().map(|block| relay_client::block_replacement_uuid(uuid, Some(*block))).collect()
+        };
+        for replacement_uuid in replacement_uuids {
+            if let Err(e) = relay.cancel_bundle(replacement_uuid).await {
+                tracing::warn!(bundle_id = %bundle_id, builder = %relay_name, error = %e, "Bundle cancellation failed");
+                errors.push(format!("{}: {}", relay_name, e));
+            }
+        }
+    }
+    errors
+}
+
+/// Query parameters for the admin stats endpoint
+#[derive(Debug, Default, Deserialize)]
+pub struct StatsQuery {
+    /// Breakdown dimension; only `submitter` is supported
+    pub by: Option<String>,
+}
+
+/// Bundle spend totals, optionally broken down by submitter
+pub async fn admin_stats(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<StatsQuery>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    if let Some(by) = query.by.as_deref().filter(|by| *by != "submitter") {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({ "error": format!("Unsupported stats breakdown: {}", by) })),
+        ));
+    }
+
+    let spend = state.database.spend_by_submitter().await.map_err(|e| {
+        internal_error(&state, StatusCode::INTERNAL_SERVER_ERROR, "Failed to load stats", e)
+    })?;
+
+    let total_wei = spend.iter().fold(U256::ZERO, |sum, s| sum + s.total_amount_wei);
+    let total_bundles: u64 = spend.iter().map(|s| s.bundle_count).sum();
+    let mut body = json!({
+        "bundles": total_bundles,
+        "spendWei": total_wei.to_string(),
+        "timestamp": state.clock.now().to_rfc3339()
+    });
+    if query.by.is_some() {
+        body["submitters"] = spend
+            .iter()
+            .map(|s| json!({
+                "submitter": s.submitter,
+                "bundles": s.bundle_count,
+                "spendWei": s.total_amount_wei.to_string()
+            }))
+            .collect();
+    }
+
+    Ok((StatusCode::OK, Json(body)))
+}
+
+/// Addresses of the tx2 signers, so operators know which accounts to fund (never key material)
+pub async fn signer_address_info(
+    State(state): State<Arc<AppState>>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    let unavailable = |e: String| (StatusCode::SERVICE_UNAVAILABLE, Json(json!({ "error": e })));
+    let key = std::env::var(DEFAULT_SIGNER_KEY_ENV)
+        .map_err(|_| unavailable(format!("{} missing", DEFAULT_SIGNER_KEY_ENV)))?;
+    let address = signer_address(&key).map_err(unavailable)?;
+
+    // Named signers whose key is not set are left out
+    let signers: serde_json::Map<String, Value> = state
+        .config
+        .payment
+        .signers
+        .iter()
+        .filter_map(|(name, key_env)| {
+            let address = signer_address(&std::env::var(key_env).ok()?).ok()?;
+            Some((name.clone(), json!(format!("0x{:x}", address))))
+        })
+        .collect();
+
+    Ok((StatusCode::OK, Json(json!({
+        "address": format!("0x{:x}", address),
+        "signers": signers
+    }))))
+}
+
+/// Query parameters for the admin audit log
+#[derive(Debug, Default, Deserialize)]
+pub struct AuditQuery {
+    /// Maximum number of entries to return (default 100, at most 1000)
+    pub limit: Option<u32>,
+}
+
+/// Recent admin actions, newest first
+pub async fn admin_audit(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<AuditQuery>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    let limit = query.limit.unwrap_or(100).min(1000);
+    let entries = state.database.admin_audit(limit).await.map_err(|e| {
+        internal_error(&state, StatusCode::INTERNAL_SERVER_ERROR, "Failed to load admin audit", e)
+    })?;
+
+    Ok((StatusCode::OK, Json(json!({
+        "entries": entries
+            .iter()
+            .map(|entry| json!({
+                "id": entry.id,
+                "action": entry.action,
+                "actor": entry.actor,
+                "details": entry.details,
+                "timestamp": entry.created_at.to_rfc3339()
+            }))
+            .collect::<Vec<_>>()
+    }))))
+}
+
+/// Query parameters for a payment quote
+#[derive(Debug, Deserialize)]
+pub struct PaymentQuoteQuery {
+    /// Payment formula (defaults to `payment.formula`)
+    pub formula: Option<String>,
+    /// Gas used by the bundle
+    #[serde(rename = "gasUsed")]
+    pub gas_used: u64,
+    /// Base fee per gas in wei
+    #[serde(rename = "baseFeePerGas", default)]
+    pub base_fee_per_gas: u64,
+    /// Priority fee per gas in wei
+    #[serde(rename = "priorityFeePerGas", default)]
+    pub priority_fee_per_gas: u64,
+}
+
+/// Quote the payment for the given gas and fees using the configured k1, k2 and max amount
+pub async fn payment_quote(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<PaymentQuoteQuery>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    let bad_request = |e: String| (StatusCode::BAD_REQUEST, Json(json!({ "error": e })));
+    let formula = match &query.formula {
+        Some(formula) => resolve_payment_formula(formula, &state.config.payment).map_err(bad_request)?,
+        None => state.config.payment.formula.clone(),
+    };
+
+    let params = forge_payment_params(
+        &state.config.payment,
+        formula,
+        query.gas_used,
+        U256::from(query.base_fee_per_gas),
+        U256::from(query.priority_fee_per_gas),
+    )
+    .map_err(|e| bad_request(e.to_string()))?;
+    let breakdown = PaymentCalculator::new()
+        .calculate_payment_detailed(&params)
+        .map_err(|e| bad_request(e.to_string()))?;
+
+    Ok((
+        StatusCode::OK,
+        Json(json!({
+            "formula": breakdown.formula.as_str(),
+            "gasComponentWei": breakdown.gas_component.to_string(),
+            "baseComponentWei": breakdown.base_component.to_string(),
+            "effectiveGasPrice": breakdown.effective_gas_price.map(|p| p.to_string()),
+            "preCapAmountWei": breakdown.pre_cap_amount.to_string(),
+            "amountWei": breakdown.amount_wei.to_string(),
+            "wasCapped": breakdown.was_capped,
+        })),
+    ))
+}
+
+/// Request body for the raw transaction decode endpoint
+#[derive(Debug, Deserialize)]
+pub struct DecodeRequest {
+    /// Raw signed transaction hex
+    pub raw: String,
+}
+
+/// Decode a raw signed transaction (debugging aid)
+pub async fn decode_transaction(
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<DecodeRequest>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    if !state.config.server.debug_endpoints_enabled {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(json!({ "error": "Debug endpoints are disabled" })),
+        ));
+    }
+
+    let envelope = simulator::decode_raw_transaction(&request.raw).map_err(|e| (
+        StatusCode::BAD_REQUEST,
+        Json(json!({ "error": format!("Invalid transaction: {}", e) })),
+    ))?;
+
+    let from = simulator::recover_sender(&envelope).ok();
+    let to = match envelope.to() {
+        TxKind::Call(addr) => Some(addr),
+        TxKind::Create => None,
+    };
+
+    Ok((
+        StatusCode::OK,
+        Json(json!({
+            "hash": envelope.tx_hash().to_string(),
+            "type": u8::from(envelope.tx_type()),
+            "chainId": envelope.chain_id(),
+            "nonce": envelope.nonce(),
+            "from": from.map(|a| a.to_string()),
+            "to": to.map(|a| a.to_string()),
+            "value": envelope.value().to_string(),
+            "gasLimit": envelope.gas_limit(),
+            "gasPrice": envelope.gas_price().map(|p| p.to_string()),
+            "maxFeePerGas": envelope.max_fee_per_gas().to_string(),
+            "maxPriorityFeePerGas": envelope.max_priority_fee_per_gas().map(|p| p.to_string()),
+        })),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::MockClock;
+    use crate::database::Database;
+    use crate::test_utils::{block_json, mock_rpc_method, LogBuffer, RpcError};
+    use types::DailySpending;
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+    use config::Config;
+
+    // Well-known development key (anvil account 0)
+    const TEST_SIGNER_KEY: &str =
+        "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+
+    async fn forge_test_tx2(to: Address, amount_wei: U256) -> String {
+        let (tx2_hex, _) = PaymentTransactionForger::new()
+            .forge_flat_transfer_hex(
+                to,
+                amount_wei,
+                1,
+                0,
+                30_000_000_000,
+                0,
+                21_000,
+                TEST_SIGNER_KEY,
+            )
+            .await
+            .unwrap();
+        tx2_hex
+    }
+
+    #[tokio::test]
+    async fn test_supplied_tx2_paying_builder_is_used() {
+        let builder = Address::repeat_byte(0x11);
+        let amount = U256::from(100_000_000_000_000u64);
+        let tx2_hex = forge_test_tx2(builder, amount).await;
+
+        let (recipient, value) =
+            validate_supplied_tx2(&tx2_hex, &[builder], U256::from(500_000_000_000_000u64)).unwrap();
+
+        assert_eq!(recipient, builder);
+        assert_eq!(value, amount);
+    }
+
+    #[tokio::test]
+    async fn test_supplied_tx2_paying_wrong_address_is_rejected() {
+        let builder = Address::repeat_byte(0x11);
+        let other = Address::repeat_byte(0x22);
+        let tx2_hex = forge_test_tx2(other, U256::from(100_000_000_000_000u64)).await;
+
+        let result = validate_supplied_tx2(&tx2_hex, &[builder], U256::from(500_000_000_000_000u64));
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_underfunded_signer_proceeds_when_balance_check_skipped() {
+        let balance = U256::from(1_000u64);
+        let required = U256::from(1_000_000u64);
+
+        assert!(!balance_check_passes(balance, required, false));
+        assert!(balance_check_passes(balance, required, true));
+        assert!(balance_check_passes(required, required, false));
+    }
+
+    #[test]
+    fn test_required_balance_overflow_is_a_distinct_error() {
+        assert_eq!(
+            required_balance_wei(21_000, U256::from(1_000_000_000u64), U256::from(5u64)).unwrap(),
+            U256::from(21_000_000_000_005u64)
+        );
+
+        for (gas_limit, max_fee_per_gas, payment_wei) in [
+            (u64::MAX, U256::MAX, U256::ZERO),
+            (21_000, U256::from(1_000_000_000u64), U256::MAX),
+        ] {
+            let (status, Json(body)) = required_balance_wei(gas_limit, max_fee_per_gas, payment_wei).unwrap_err();
+            assert_eq!(status, StatusCode::BAD_REQUEST);
+            assert_eq!(body["error"], GAS_COST_OVERFLOW_ERROR);
+            assert_ne!(body["error"], INSUFFICIENT_BALANCE_ERROR);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_bundle_with_identical_transactions_is_rejected() {
+        let tx_hex = forge_test_tx2(Address::repeat_byte(0x11), U256::from(1u64)).await;
+
+        let duplicate = find_duplicate_tx(&[tx_hex.clone(), tx_hex.clone()]);
+        assert_eq!(duplicate, Some(raw_tx_hash(&tx_hex)));
+
+        let other_hex = forge_test_tx2(Address::repeat_byte(0x22), U256::from(1u64)).await;
+        assert_eq!(find_duplicate_tx(&[tx_hex, other_hex]), None);
+    }
+
+    async fn create_test_state(debug_endpoints_enabled: bool) -> Arc<AppState> {
+        let mut config = Config::default();
+        config.server.debug_endpoints_enabled = debug_endpoints_enabled;
+        let database = Database::new_in_memory().await.unwrap();
+        Arc::new(AppState::new(config, database))
+    }
+
+    #[tokio::test]
+    async fn test_decode_known_transaction() {
+        let to = Address::repeat_byte(0x11);
+        let tx_hex = forge_test_tx2(to, U256::from(123_456u64)).await;
+        let state = create_test_state(true).await;
+
+        let (status, Json(body)) = decode_transaction(State(state), Json(DecodeRequest { raw: tx_hex.clone() }))
+            .await
+            .unwrap();
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["hash"], raw_tx_hash(&tx_hex));
+        assert_eq!(body["type"], 2);
+        assert_eq!(body["chainId"], 1);
+        assert_eq!(body["nonce"], 0);
+        assert_eq!(body["from"], "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266");
+        assert_eq!(body["to"], to.to_string());
+        assert_eq!(body["value"], "123456");
+        assert_eq!(body["gasLimit"], 21_000);
+        assert_eq!(body["maxFeePerGas"], "30000000000");
+        assert_eq!(body["maxPriorityFeePerGas"], "0");
+    }
+
+    #[tokio::test]
+    async fn test_decode_disabled_by_default() {
+        let state = create_test_state(false).await;
+
+        let (status, _) = decode_transaction(State(state), Json(DecodeRequest { raw: "0x00".to_string() }))
+            .await
+            .unwrap_err();
+
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_submission_skipped_when_chain_head_beyond_target() {
+        let server = MockServer::start().await;
+        mock_rpc_method(&server, "eth_blockNumber", json!("0x64")).await; // head = 100
+
+        assert_eq!(passed_target_block_head(&server.uri(), 99).await, Some(100));
+        assert_eq!(passed_target_block_head(&server.uri(), 100).await, Some(100));
+        assert_eq!(passed_target_block_head(&server.uri(), 101).await, None);
+    }
+
+    /// State with a single builder relayed to a mock server and a bundle request that pays it
+    async fn async_submission_setup(queue_capacity: usize) -> (Arc<AppState>, MockServer, BundleRequest) {
+        let relay = MockServer::start().await;
+        mock_rpc_method(&relay, "eth_sendBundle", json!("0xbundlehash")).await;
+        // The relay mock doubles as the RPC for target block lookups
+        mock_rpc_method(&relay, "eth_blockNumber", json!("0x64")).await;
+
+        let builder = Address::repeat_byte(0x11);
+        let mut config = Config::default();
+        config.network.rpc_url = Some(relay.uri());
+        config.builders[0].relay_url = relay.uri();
+        config.builders[0].payment_address = builder.to_string();
+        config.server.submission_queue_capacity = queue_capacity;
+        // A single target block keeps relay request counts at one per builder
+        config.targets.blocks_ahead = 1;
+        let state = Arc::new(AppState::new(config, Database::new_in_memory().await.unwrap()));
+
+        let tx1 = forge_test_tx2(Address::repeat_byte(0x22), U256::from(1u64)).await;
+        let tx2 = forge_test_tx2(builder, U256::from(100_000_000_000_000u64)).await;
+        let request: BundleRequest = serde_json::from_value(json!({
+            "tx1": tx1,
+            "tx2": tx2,
+            "payment": {
+                "mode": "direct",
+                "formula": "flat",
+                "maxAmountWei": "500000000000000",
+                "expiry": (chrono::Utc::now() + chrono::Duration::minutes(5)).to_rfc3339()
+            }
+        }))
+        .unwrap();
+
+        (state, relay, request)
+    }
+
+    async fn submit_async(
+        state: &Arc<AppState>,
+        request: BundleRequest,
+    ) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+        submit_bundle(
+            State(state.clone()),
+            Query(SubmitBundleQuery { async_mode: true }),
+            HeaderMap::new(),
+            Json(request),
+        )
+        .await
+    }
+
+    async fn wait_for_state(state: &Arc<AppState>, bundle_id: &str, expected: &str) -> Value {
+        let mut bundle_state = Value::Null;
+        for _ in 0..50 {
+            let (_, Json(status_body)) =
+                get_bundle_status(State(state.clone()), Path(bundle_id.to_string())).await.unwrap();
+            bundle_state = status_body["state"].clone();
+            if bundle_state == expected {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        }
+        bundle_state
+    }
+
+    #[tokio::test]
+    async fn test_async_submission_returns_accepted_and_is_eventually_sent() {
+        let (state, _relay, request) = async_submission_setup(10).await;
+        state.submission_queue.spawn_workers(state.clone(), 1);
+
+        let (status, Json(body)) = submit_async(&state, request).await.unwrap();
+        assert_eq!(status, StatusCode::ACCEPTED);
+
+        let bundle_id = body["bundleId"].as_str().unwrap();
+        assert_eq!(wait_for_state(&state, bundle_id, "sent").await, "sent");
+    }
+
+    #[tokio::test]
+    async fn test_full_submission_queue_applies_backpressure() {
+        let (state, _relay, request) = async_submission_setup(1).await;
+
+        // No workers yet, so the single slot fills up
+        let (status, Json(body)) = submit_async(&state, request.clone()).await.unwrap();
+        assert_eq!(status, StatusCode::ACCEPTED);
+        let queued_id = body["bundleId"].as_str().unwrap().to_string();
+
+        let (status, _) = submit_async(&state, request).await.unwrap_err();
+        assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+
+        // Once workers drain the queue the queued bundle is submitted
+        state.submission_queue.spawn_workers(state.clone(), 2);
+        assert_eq!(wait_for_state(&state, &queued_id, "sent").await, "sent");
+    }
+
+    #[tokio::test]
+    async fn test_unknown_target_block_rejected_or_omitted_per_config() {
+        let (state, relay, request) = async_submission_setup(10).await;
+        let rpc = MockServer::start().await;
+        Mock::given(wiremock::matchers::method("POST"))
+            .respond_with(ResponseTemplate::new(500))
+            .mount(&rpc)
+            .await;
+
+        let mut config = state.config.clone();
+        config.network.rpc_url = Some(rpc.uri());
+        let rejecting = Arc::new(AppState::new(config.clone(), Database::new_in_memory().await.unwrap()));
+        let (status, Json(body)) = submit_bundle(
+            State(rejecting),
+            Query(SubmitBundleQuery::default()),
+            HeaderMap::new(),
+            Json(request.clone()),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert!(body["error"].as_str().unwrap().contains("target block"));
+        assert!(relay.received_requests().await.unwrap().is_empty());
+
+        config.targets.unknown_target_block = config::UnknownTargetBlock::Omit;
+        let omitting = Arc::new(AppState::new(config, Database::new_in_memory().await.unwrap()));
+        let (status, Json(body)) = submit_bundle(
+            State(omitting),
+            Query(SubmitBundleQuery::default()),
+            HeaderMap::new(),
+            Json(request),
+        )
+        .await
+        .unwrap();
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["submissions"][0]["status"], "submitted");
+
+        let sent: Value = serde_json::from_slice(&relay.received_requests().await.unwrap()[0].body).unwrap();
+        assert_eq!(sent["method"], "eth_sendBundle");
+        assert!(sent["params"][0].get("blockNumber").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_internal_error_details_logged_but_hidden_from_clients() {
+        const DETAIL: &str = "node at 10.0.0.7:8545 is out of sync";
+        let (state, _relay, request) = async_submission_setup(10).await;
+        let rpc = MockServer::start().await;
+        Mock::given(wiremock::matchers::method("POST"))
+            .respond_with(RpcError(DETAIL))
+            .mount(&rpc)
+            .await;
+
+        let logs = LogBuffer::default();
+        let writer = logs.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_writer(move || writer.clone())
+            .with_ansi(false)
+            .finish();
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let mut config = state.config.clone();
+        config.network.rpc_url = Some(rpc.uri());
+        let quiet = Arc::new(AppState::new(config.clone(), Database::new_in_memory().await.unwrap()));
+        let (status, Json(body)) = submit_bundle(
+            State(quiet),
+            Query(SubmitBundleQuery::default()),
+            HeaderMap::new(),
+            Json(request.clone()),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(body["error"], "Could not determine a target block (no targetBlock given and RPC failed)");
+        assert!(!body.to_string().contains(DETAIL));
+        assert!(logs.contents().contains(DETAIL));
+
+        config.security.verbose_errors = true;
+        let verbose = Arc::new(AppState::new(config, Database::new_in_memory().await.unwrap()));
+        let (_, Json(body)) = submit_bundle(
+            State(verbose),
+            Query(SubmitBundleQuery::default()),
+            HeaderMap::new(),
+            Json(request),
+        )
+        .await
+        .unwrap_err();
+        assert!(body["error"].as_str().unwrap().contains(DETAIL));
+    }
+
+    #[tokio::test]
+    async fn test_successful_submission_observes_payment_histogram() {
+        let (state, _relay, request) = async_submission_setup(10).await;
+
+        let (status, _) = submit_bundle(
+            State(state.clone()),
+            Query(SubmitBundleQuery::default()),
+            HeaderMap::new(),
+            Json(request),
+        )
+        .await
+        .unwrap();
+        assert_eq!(status, StatusCode::OK);
+
+        let rendered = state.metrics.render("atomic_bundler");
+        assert!(rendered.contains("atomic_bundler_payment_amount_gwei_count{formula=\"flat\"} 1"));
+        assert!(rendered.contains("atomic_bundler_payment_amount_gwei_sum{formula=\"flat\"} 100000"));
+    }
+
+    #[tokio::test]
+    async fn test_bundle_targets_blocks_ahead_without_explicit_target() {
+        let (state, relay, request) = async_submission_setup(10).await;
+        let mut config = state.config.clone();
+        config.targets.blocks_ahead = 3;
+        let state = Arc::new(AppState::new(config, Database::new_in_memory().await.unwrap()));
+
+        let sent_blocks = |requests: Vec<wiremock::Request>| -> Vec<String> {
+            requests
+                .iter()
+                .map(|r| serde_json::from_slice::<Value>(&r.body).unwrap())
+                .filter(|body| body["method"] == "eth_sendBundle")
+                .map(|body| body["params"][0]["blockNumber"].as_str().unwrap().to_string())
+                .collect()
+        };
+
+        // The mocked chain head is block 100
+        let (status, Json(body)) = submit_bundle(
+            State(state.clone()),
+            Query(SubmitBundleQuery::default()),
+            HeaderMap::new(),
+            Json(request.clone()),
+        )
+        .await
+        .unwrap();
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["submissions"][0]["targetBlocks"], json!([101, 102, 103]));
+        assert_eq!(sent_blocks(relay.received_requests().await.unwrap()), vec!["0x65", "0x66", "0x67"]);
+
+        // An explicit target overrides blocks_ahead
+        relay.reset().await;
+        mock_rpc_method(&relay, "eth_sendBundle", json!("0xbundlehash")).await;
+        mock_rpc_method(&relay, "eth_blockNumber", json!("0x64")).await;
+        let mut request = request;
+        request.target_block = Some(200);
+        let (_, Json(body)) = submit_bundle(
+            State(state),
+            Query(SubmitBundleQuery::default()),
+            HeaderMap::new(),
+            Json(request),
+        )
+        .await
+        .unwrap();
+        assert_eq!(body["submissions"][0]["targetBlocks"], json!([200]));
+        assert_eq!(sent_blocks(relay.received_requests().await.unwrap()), vec!["0xc8"]);
+    }
+
+    #[tokio::test]
+    async fn test_underfunded_bundle_is_queued_until_balance_arrives() {
+        std::env::set_var(DEFAULT_SIGNER_KEY_ENV, TEST_SIGNER_KEY);
+        let (state, relay, mut request) = async_submission_setup(10).await;
+        request.tx2 = None;
+        // The signer is unfunded on the first balance check and funded afterwards
+        Mock::given(wiremock::matchers::body_partial_json(json!({ "method": "eth_getBalance" })))
+            .respond_with(crate::test_utils::RpcResult(json!("0x0")))
+            .up_to_n_times(1)
+            .mount(&relay)
+            .await;
+        mock_forging_rpc(&relay).await;
+        mock_rpc_method(&relay, "eth_getTransactionCount", json!("0x0")).await;
+
+        let mut config = state.config.clone();
+        config.payment.balance_grace_seconds = Some(60);
+        config.payment.balance_retry_interval_ms = 10;
+        let state = Arc::new(AppState::new(config, Database::new_in_memory().await.unwrap()));
+        state.submission_queue.spawn_workers(state.clone(), 1);
+
+        let (status, Json(body)) = submit_bundle(
+            State(state.clone()),
+            Query(SubmitBundleQuery::default()),
+            HeaderMap::new(),
+            Json(request),
+        )
+        .await
+        .unwrap();
+        assert_eq!(status, StatusCode::ACCEPTED);
+        assert_eq!(body["state"], "queued");
+
+        let bundle_id = body["bundleId"].as_str().unwrap();
+        assert_eq!(wait_for_state(&state, bundle_id, "sent").await, "sent");
+    }
+
+    #[tokio::test]
+    async fn test_created_bundle_has_location_header() {
+        let (state, _relay, request) = async_submission_setup(10).await;
+
+        let response = create_bundle(
+            State(state),
+            Query(SubmitBundleQuery::default()),
+            HeaderMap::new(),
+            Json(request),
+        )
+        .await
+        .into_response();
+        assert_eq!(response.status(), StatusCode::CREATED);
+
+        let location = response.headers()[header::LOCATION].to_str().unwrap().to_string();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let body: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(location, format!("/bundles/{}", body["bundleId"].as_str().unwrap()));
+    }
+
+    #[tokio::test]
+    async fn test_readyz_unavailable_when_rpc_down() {
+        let rpc = MockServer::start().await;
+        Mock::given(wiremock::matchers::method("POST"))
+            .respond_with(ResponseTemplate::new(500))
+            .mount(&rpc)
+            .await;
+
+        let mut config = Config::default();
+        config.network.rpc_url = Some(rpc.uri());
+        let state = Arc::new(AppState::new(config, Database::new_in_memory().await.unwrap()));
+
+        let (status, Json(body)) = readiness_check(State(state)).await.unwrap();
+
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(body["components"]["database"], "healthy");
+        assert_eq!(body["components"]["rpc"]["status"], "unhealthy");
+    }
+
+    #[test]
+    fn test_configured_max_amount_is_used_exactly() {
+        let mut payment_config = PaymentConfig::default();
+        payment_config.max_amount_wei = U256::from(123_456_789_000_000u64);
+
+        let params = forge_payment_params(
+            &payment_config,
+            PaymentFormula::Flat,
+            21_000,
+            U256::from(10_000_000_000u64),
+            U256::ZERO,
+        )
+        .unwrap();
+
+        assert_eq!(params.max_amount, U256::from(123_456_789_000_000u64));
+        assert_eq!(params.k2, payment_config.k2);
+    }
+
+    #[tokio::test]
+    async fn test_builder_outside_active_window_is_skipped() {
+        let mut config = Config::default();
+        let mut night_builder = config.builders[0].clone();
+        night_builder.name = "night".to_string();
+        night_builder.active_hours_utc = vec![config::HourWindow { start_hour: 22, end_hour: 6 }];
+        config.builders.push(night_builder);
+
+        let noon = chrono::DateTime::parse_from_rfc3339("2024-01-01T12:00:00Z").unwrap().with_timezone(&chrono::Utc);
+        let clock = Arc::new(MockClock::new(noon));
+        let state = AppState::new(config, Database::new_in_memory().await.unwrap()).with_clock(clock.clone());
+
+        let names: Vec<_> = active_builders(&state).iter().map(|b| b.name.clone()).collect();
+        assert_eq!(names, vec!["flashbots".to_string()]);
+
+        clock.advance(chrono::Duration::hours(12)); // midnight
+        let names: Vec<_> = active_builders(&state).iter().map(|b| b.name.clone()).collect();
+        assert_eq!(names, vec!["flashbots".to_string(), "night".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_payments_clamped_while_safe_mode_active() {
+        let state = create_test_state(false).await;
+        let ceiling = state.config.parse_limits().unwrap().safe_mode_ceiling_wei;
+        let payment = ceiling * U256::from(5u64);
+
+        assert_eq!(safe_payment_amount(&state, payment).await, payment);
+
+        state.activate_safe_mode("test").await;
+        assert_eq!(safe_payment_amount(&state, payment).await, ceiling);
+        assert_eq!(safe_payment_amount(&state, U256::from(1u64)).await, U256::from(1u64));
+
+        state.deactivate_safe_mode().await;
+        assert_eq!(safe_payment_amount(&state, payment).await, payment);
+    }
+
+    #[tokio::test]
+    async fn test_capped_payment_increments_counter() {
+        let state = create_test_state(false).await;
+        let ceiling = state.config.parse_limits().unwrap().safe_mode_ceiling_wei;
+        let counter = "atomic_bundler_payments_capped_total{reason=\"safe_mode\"}";
+
+        safe_payment_amount(&state, ceiling).await;
+        assert!(!state.metrics.render("atomic_bundler").contains(counter));
+
+        state.activate_safe_mode("test").await;
+        safe_payment_amount(&state, ceiling * U256::from(2u64)).await;
+        assert!(state.metrics.render("atomic_bundler").contains(&format!("{} 1", counter)));
+    }
+
+    #[tokio::test]
+    async fn test_emergency_stop_triggers_safe_mode() {
+        let state = create_test_state(false).await;
+        let limits = state.config.parse_limits().unwrap();
+        let anomalous = limits.emergency_stop_threshold_wei + U256::from(1u64);
+
+        assert_eq!(safe_payment_amount(&state, anomalous).await, limits.safe_mode_ceiling_wei);
+        assert!(state.is_safe_mode_active().await);
+    }
+
+    #[tokio::test]
+    async fn test_submission_reports_relay_latency() {
+        let (state, _relay, request) = async_submission_setup(10).await;
+        let slow_relay = MockServer::start().await;
+        Mock::given(wiremock::matchers::method("POST"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(json!({ "jsonrpc": "2.0", "id": 1, "result": "0xbundlehash" }))
+                    .set_delay(std::time::Duration::from_millis(20)),
+            )
+            .mount(&slow_relay)
+            .await;
+        let mut config = state.config.clone();
+        config.builders[0].relay_url = slow_relay.uri();
+        let state = Arc::new(AppState::new(config, Database::new_in_memory().await.unwrap()));
+
+        let (status, Json(body)) = submit_bundle(
+            State(state),
+            Query(SubmitBundleQuery::default()),
+            HeaderMap::new(),
+            Json(request),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["submissions"][0]["status"], "submitted");
+        assert!(body["submissions"][0]["latencyMs"].as_u64().unwrap() >= 20);
+    }
+
+    #[tokio::test]
+    async fn test_payment_expiry_beyond_cap_is_rejected() {
+        let (state, _relay, mut request) = async_submission_setup(10).await;
+        let max_expiry_seconds = state.config.targets.max_payment_expiry_seconds as i64;
+
+        request.payment.expiry = Utc::now() + chrono::Duration::seconds(max_expiry_seconds + 60);
+        let (status, Json(body)) = submit_bundle(
+            State(state.clone()),
+            Query(SubmitBundleQuery::default()),
+            HeaderMap::new(),
+            Json(request.clone()),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(body["error"].as_str().unwrap().contains("payment.expiry"));
+
+        request.payment.expiry = Utc::now() + chrono::Duration::seconds(60);
+        let (status, _) = submit_bundle(
+            State(state),
+            Query(SubmitBundleQuery::default()),
+            HeaderMap::new(),
+            Json(request),
+        )
+        .await
+        .unwrap();
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_admin_stats_sums_spend_per_submitter() {
+        let database = Database::new_in_memory().await.unwrap();
+        let now = Utc::now();
+        let expires_at = now + chrono::Duration::minutes(5);
+        for (id, submitter, amount) in [
+            ("a-1", "searcher_a", 100u64),
+            ("a-2", "searcher_a", 250),
+            ("b-1", "searcher_b", 40),
+        ] {
+            database.insert_bundle(id, "0xabc", submitter, now, expires_at).await.unwrap();
+            database.record_bundle_payment(id, U256::from(amount)).await.unwrap();
+        }
+        let state = Arc::new(AppState::new(Config::default(), database));
+
+        let (status, Json(body)) = admin_stats(
+            State(state.clone()),
+            Query(StatsQuery { by: Some("submitter".to_string()) }),
+        )
+        .await
+        .unwrap();
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["spendWei"], "390");
+        assert_eq!(body["submitters"][0]["submitter"], "searcher_a");
+        assert_eq!(body["submitters"][0]["bundles"], 2);
+        assert_eq!(body["submitters"][0]["spendWei"], "350");
+        assert_eq!(body["submitters"][1]["submitter"], "searcher_b");
+        assert_eq!(body["submitters"][1]["spendWei"], "40");
+
+        let (status, _) = admin_stats(State(state), Query(StatsQuery { by: Some("builder".to_string()) }))
+            .await
+            .unwrap_err();
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    #[test]
+    fn test_submitter_derived_from_bearer_token() {
+        let api_tokens = std::collections::HashMap::from([("searcher_a".to_string(), "secret-a".to_string())]);
+        let mut headers = HeaderMap::new();
+        assert_eq!(submitter_from_headers(&headers, &api_tokens), "anonymous");
+
+        headers.insert(header::AUTHORIZATION, "Bearer secret-a".parse().unwrap());
+        assert_eq!(submitter_from_headers(&headers, &api_tokens), "searcher_a");
+
+        headers.insert(header::AUTHORIZATION, "Bearer unknown".parse().unwrap());
+        let submitter = submitter_from_headers(&headers, &api_tokens);
+        assert!(submitter.starts_with("token:"));
+        assert!(!submitter.contains("unknown"));
+    }
+
+    #[tokio::test]
+    async fn test_mixed_relay_outcomes_flag_partial_submission() {
+        let (state, _relay, request) = async_submission_setup(10).await;
+        let rejecting_relay = MockServer::start().await;
+        Mock::given(wiremock::matchers::method("POST"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "error": { "code": -32000, "message": "bundle rejected" }
+            })))
+            .mount(&rejecting_relay)
+            .await;
+
+        let mut config = state.config.clone();
+        let mut rejecting = config.builders[0].clone();
+        rejecting.name = "rejecting".to_string();
+        rejecting.relay_url = rejecting_relay.uri();
+        rejecting.max_retries = 0;
+        config.builders.push(rejecting);
+        let state = Arc::new(AppState::new(config, Database::new_in_memory().await.unwrap()));
+
+        let (_, Json(body)) = submit_async(&state, request).await.unwrap();
+        let bundle_id = body["bundleId"].as_str().unwrap().to_string();
+        assert_eq!(wait_for_state(&state, &bundle_id, "sent").await, "sent");
+
+        let (_, Json(status_body)) = get_bundle_status(State(state), Path(bundle_id)).await.unwrap();
+        assert_eq!(status_body["partiallySubmitted"], true);
+    }
+
+    #[tokio::test]
+    async fn test_cancel_all_cancels_and_fails_in_flight_bundles() {
+        let relay = MockServer::start().await;
+        Mock::given(wiremock::matchers::method("POST"))
+            .and(wiremock::matchers::body_partial_json(json!({ "method": "eth_cancelBundle" })))
+            .respond_with(crate::test_utils::RpcResult(json!(null)))
+            .expect(2)
+            .mount(&relay)
+            .await;
+
+        let mut config = Config::default();
+        config.builders[0].relay_url = relay.uri();
+        let relay_name = config.builders[0].name.clone();
+        let state = Arc::new(AppState::new(config, Database::new_in_memory().await.unwrap()));
+
+        let now = Utc::now();
+        let bundle_ids = [Uuid::new_v4().to_string(), Uuid::new_v4().to_string()];
+        for bundle_id in &bundle_ids {
+            state.database.insert_bundle(bundle_id, "0xabc", "anonymous", now, now + chrono::Duration::minutes(5)).await.unwrap();
+            state.database.update_bundle_state(bundle_id, "sent", now).await.unwrap();
+            state.database.record_relay_submission(bundle_id, &relay_name, "0xbundlehash", &[], now).await.unwrap();
+        }
+
+        let (status, Json(body)) = cancel_all_bundles(State(state.clone()), HeaderMap::new()).await.unwrap();
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["cancelled"], 2);
+        for bundle_id in &bundle_ids {
+            assert_eq!(state.database.get_bundle(bundle_id).await.unwrap().unwrap().state, "failed");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cancel_covers_every_target_block() {
+        let relay = MockServer::start().await;
+        let bundle_id = Uuid::new_v4();
+        for block in [100, 101] {
+            let replacement_uuid = relay_client::block_replacement_uuid(bundle_id, Some(block));
+            Mock::given(wiremock::matchers::body_partial_json(json!({
+                "method": "eth_cancelBundle",
+                "params": [{ "replacementUuid": replacement_uuid }]
+            })))
+            .respond_with(crate::test_utils::RpcResult(json!(null)))
+            .expect(1)
+            .mount(&relay)
+            .await;
+        }
+
+        let mut config = Config::default();
+        config.builders[0].relay_url = relay.uri();
+        let relay_name = config.builders[0].name.clone();
+        let state = Arc::new(AppState::new(config, Database::new_in_memory().await.unwrap()));
+
+        let now = Utc::now();
+        let id = bundle_id.to_string();
+        state.database.insert_bundle(&id, "0xabc", "anonymous", now, now + chrono::Duration::minutes(5)).await.unwrap();
+        state.database.update_bundle_state(&id, "sent", now).await.unwrap();
+        state.database.record_relay_submission(&id, &relay_name, "0xbundlehash", &[100, 101], now).await.unwrap();
+
+        assert!(cancel_at_relays(&state, &id).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_killswitch_toggle_writes_audit_row() {
+        let mut config = Config::default();
+        config.security.admin_api_key = Some("admin-secret".to_string());
+        let state = Arc::new(AppState::new(config, Database::new_in_memory().await.unwrap()));
+
+        let mut headers = HeaderMap::new();
+        headers.insert(header::AUTHORIZATION, "Bearer admin-secret".parse().unwrap());
+        toggle_killswitch(State(state.clone()), headers, Json(json!({ "activate": true })))
+            .await
+            .unwrap();
+
+        let entries = state.database.admin_audit(10).await.unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].action, "killswitch");
+        assert_eq!(entries[0].actor, "admin");
+        assert_eq!(entries[0].details, json!({ "activate": true }));
+
+        let (status, Json(body)) = admin_audit(State(state), Query(AuditQuery::default())).await.unwrap();
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["entries"][0]["action"], "killswitch");
+    }
+
+    #[test]
+    fn test_resolve_payment_formula_falls_back_to_config_default() {
+        let mut payment_config = PaymentConfig {
+            formula: PaymentFormula::Gas,
+            ..PaymentConfig::default()
+        };
+        assert_eq!(resolve_payment_formula("Flat", &payment_config), Ok(PaymentFormula::Flat));
+        assert_eq!(resolve_payment_formula("quadratic", &payment_config), Ok(PaymentFormula::Gas));
+
+        payment_config.strict_formula = true;
+        assert_eq!(resolve_payment_formula("basefee", &payment_config), Ok(PaymentFormula::Basefee));
+        assert!(resolve_payment_formula("quadratic", &payment_config).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_unknown_payment_formula_rejected_when_strict() {
+        let (state, _relay, mut request) = async_submission_setup(10).await;
+        let mut config = state.config.clone();
+        config.payment.strict_formula = true;
+        let state = Arc::new(AppState::new(config, Database::new_in_memory().await.unwrap()));
+
+        request.payment.formula = "quadratic".to_string();
+        let (status, Json(body)) = submit_bundle(
+            State(state),
+            Query(SubmitBundleQuery::default()),
+            HeaderMap::new(),
+            Json(request),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["error"], "Unknown payment formula: quadratic");
+    }
+
+    #[tokio::test]
+    async fn test_payment_quote_reports_breakdown() {
+        let state = Arc::new(AppState::new(Config::default(), Database::new_in_memory().await.unwrap()));
+        let k2 = state.config.payment.k2;
+
+        let (status, Json(body)) = payment_quote(
+            State(state),
+            Query(PaymentQuoteQuery {
+                formula: Some("basefee".to_string()),
+                gas_used: 100_000,
+                base_fee_per_gas: 1_000_000_000,
+                priority_fee_per_gas: 500_000_000,
+            }),
+        )
+        .await
+        .unwrap();
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["formula"], "basefee");
+        assert_eq!(body["effectiveGasPrice"], "1500000000");
+        assert_eq!(body["gasComponentWei"], "150000000000000");
+        assert_eq!(body["baseComponentWei"], k2.to_string());
+        assert_eq!(body["preCapAmountWei"], (U256::from(150_000_000_000_000u64) + k2).to_string());
+        assert_eq!(body["amountWei"], body["preCapAmountWei"]);
+        assert_eq!(body["wasCapped"], false);
+    }
+
+    #[tokio::test]
+    async fn test_payment_expiry_at_now_is_rejected() {
+        let (state, _relay, mut request) = async_submission_setup(10).await;
+        let now = chrono::DateTime::parse_from_rfc3339("2024-01-01T12:00:00Z").unwrap().with_timezone(&Utc);
+        let state = Arc::new(
+            AppState::new(state.config.clone(), Database::new_in_memory().await.unwrap())
+                .with_clock(Arc::new(MockClock::new(now))),
+        );
+
+        request.payment.expiry = now;
+        let (status, Json(body)) = submit_bundle(
+            State(state.clone()),
+            Query(SubmitBundleQuery::default()),
+            HeaderMap::new(),
+            Json(request),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(body["error"].as_str().unwrap().contains("not in the future"));
+        assert!(state.database.bundles_in_state("queued").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_status_reports_ttl_seconds() {
+        let now = chrono::DateTime::parse_from_rfc3339("2024-01-01T12:00:00Z").unwrap().with_timezone(&Utc);
+        let clock = Arc::new(MockClock::new(now));
+        let database = Database::new_in_memory().await.unwrap();
+        let bundle_id = Uuid::new_v4().to_string();
+        database
+            .insert_bundle(&bundle_id, "0xabc", "anonymous", now, now + chrono::Duration::seconds(90))
+            .await
+            .unwrap();
+        let state = Arc::new(AppState::new(Config::default(), database).with_clock(clock.clone()));
+
+        let (_, Json(body)) = get_bundle_status(State(state.clone()), Path(bundle_id.clone())).await.unwrap();
+        assert_eq!(body["ttlSeconds"], 90);
+
+        clock.advance(chrono::Duration::seconds(120));
+        let (_, Json(body)) = get_bundle_status(State(state), Path(bundle_id)).await.unwrap();
+        assert_eq!(body["ttlSeconds"], 0);
+    }
+
+    #[tokio::test]
+    async fn test_signer_address_matches_configured_key() {
+        std::env::set_var(DEFAULT_SIGNER_KEY_ENV, TEST_SIGNER_KEY);
+        let state = Arc::new(AppState::new(Config::default(), Database::new_in_memory().await.unwrap()));
+
+        let (status, Json(body)) = signer_address_info(State(state)).await.unwrap();
+        assert_eq!(status, StatusCode::OK);
+        let expected = alloy::signers::local::PrivateKeySigner::from_str(TEST_SIGNER_KEY).unwrap().address();
+        assert_eq!(body["address"], format!("0x{:x}", expected));
+        assert!(!body.to_string().contains(TEST_SIGNER_KEY.trim_start_matches("0x")));
+    }
+
+    #[tokio::test]
+    async fn test_status_reports_relay_health() {
+        let state = Arc::new(AppState::new(Config::default(), Database::new_in_memory().await.unwrap()));
+        let name = state.config.builders[0].name.clone();
+        state.relay_health.write().await.update_health(&name, Err("connection refused".to_string()));
+
+        let (_, Json(body)) = system_status(State(state)).await.unwrap();
+        let relay = &body["components"]["relays"][0];
+        assert_eq!(relay["name"], name);
+        assert_eq!(relay["status"], "degraded");
+        assert_eq!(relay["consecutive_failures"], 1);
+        assert_eq!(relay["error_message"], "connection refused");
+    }
+
+    #[tokio::test]
+    async fn test_empty_tx1_is_rejected() {
+        let (state, _relay, mut request) = async_submission_setup(10).await;
+
+        for tx1 in [Bytes::new(), Bytes::from_static(&[0x02])] {
+            request.tx1 = tx1;
+            let (status, Json(body)) = submit_bundle(
+                State(state.clone()),
+                Query(SubmitBundleQuery::default()),
+                HeaderMap::new(),
+                Json(request.clone()),
+            )
+            .await
+            .unwrap_err();
+
+            assert_eq!(status, StatusCode::BAD_REQUEST);
+            assert!(body["error"].as_str().unwrap().starts_with("tx1 "));
+        }
+    }
+
+    /// Sign an EIP-7702 tx1 carrying `authorizations` copies of one signed authorization
+    async fn forge_test_7702_tx1(authorizations: usize) -> Bytes {
+        use alloy::consensus::{SignableTransaction, TxEip7702, TxEnvelope};
+        use alloy::eips::eip2718::Encodable2718;
+        use alloy::eips::eip7702::Authorization;
+        use alloy::network::TxSigner;
+        use alloy::signers::SignerSync;
+
+        let signer = alloy::signers::local::PrivateKeySigner::from_str(TEST_SIGNER_KEY).unwrap();
+        let authorization = Authorization { chain_id: 1, address: Address::repeat_byte(0x33), nonce: 1 };
+        let auth_signature = signer.sign_hash_sync(&authorization.signature_hash()).unwrap();
+        let mut tx = TxEip7702 {
+            chain_id: 1,
+            gas_limit: 100_000,
+            max_fee_per_gas: 30_000_000_000,
+            to: Address::repeat_byte(0x22),
+            authorization_list: vec![authorization.into_signed(auth_signature); authorizations],
+            ..Default::default()
+        };
+        let signature = signer.sign_transaction(&mut tx).await.unwrap();
+        let envelope: TxEnvelope = tx.into_signed(signature).into();
+        Bytes::from(envelope.encoded_2718())
+    }
+
+    #[tokio::test]
+    async fn test_7702_tx1_forwarded_with_authorization_list_intact() {
+        let tx1 = forge_test_7702_tx1(1).await;
+        assert!(validate_tx1(&tx1).is_ok());
+        let expected_auth = simulator::decode_raw_transaction(&tx1.to_string())
+            .unwrap()
+            .authorization_list()
+            .unwrap()
+            .to_vec();
+
+        let (state, relay, mut request) = async_submission_setup(10).await;
+        request.tx1 = tx1.clone();
+        let (status, _) = submit_bundle(
+            State(state),
+            Query(SubmitBundleQuery::default()),
+            HeaderMap::new(),
+            Json(request),
+        )
+        .await
+        .unwrap();
+        assert_eq!(status, StatusCode::OK);
+
+        let sent: Value = relay
+            .received_requests()
+            .await
+            .unwrap()
+            .iter()
+            .map(|r| serde_json::from_slice::<Value>(&r.body).unwrap())
+            .find(|body| body["method"] == "eth_sendBundle")
+            .unwrap();
+        let forwarded = sent["params"][0]["txs"][0].as_str().unwrap();
+        assert_eq!(forwarded, tx1.to_string());
+
+        let decoded = simulator::decode_raw_transaction(forwarded).unwrap();
+        assert_eq!(decoded.authorization_list().unwrap(), expected_auth.as_slice());
+    }
+
+    #[tokio::test]
+    async fn test_7702_tx1_without_authorizations_is_rejected() {
+        let tx1 = forge_test_7702_tx1(0).await;
+        assert!(matches!(validate_tx1(&tx1), Err(TransactionError::InvalidFormat(_))));
+    }
+
+    #[tokio::test]
+    async fn test_unsigned_tx1_is_rejected() {
+        use alloy::consensus::{SignableTransaction, Signed, TxEip1559, TxEnvelope};
+        use alloy::eips::eip2718::Encodable2718;
+
+        let tx = TxEip1559 {
+            chain_id: 1,
+            gas_limit: 21_000,
+            max_fee_per_gas: 30_000_000_000,
+            to: TxKind::Call(Address::repeat_byte(0x22)),
+            ..Default::default()
+        };
+
+        // Signing payload without any signature attached
+        let unsigned = Bytes::from(tx.encoded_for_signing());
+        assert!(matches!(validate_tx1(&unsigned), Err(TransactionError::InvalidFormat(_))));
+
+        // Well-formed envelope whose signature recovers no signer
+        let zero_signature = alloy::primitives::Signature::from_rs_and_parity(U256::ZERO, U256::ZERO, false).unwrap();
+        let envelope: TxEnvelope = Signed::new_unchecked(tx, zero_signature, Default::default()).into();
+        let unsigned = Bytes::from(envelope.encoded_2718());
+        assert!(matches!(validate_tx1(&unsigned), Err(TransactionError::InvalidSignature)));
+
+        let (state, _relay, mut request) = async_submission_setup(10).await;
+        request.tx1 = unsigned;
+        let (status, Json(body)) = submit_bundle(
+            State(state),
+            Query(SubmitBundleQuery::default()),
+            HeaderMap::new(),
+            Json(request),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["error"], "tx1 rejected: Invalid transaction signature");
+    }
+
+    #[tokio::test]
+    async fn test_builders_are_paid_by_their_configured_signers() {
+        const SECOND_SIGNER_KEY: &str =
+            "0x59c6995e998f97a5a0044966f0945389dc9e86dae88c7a8412f4603b6b78690d";
+        std::env::set_var("ATOMIC_BUNDLER_TEST_SIGNER_A", TEST_SIGNER_KEY);
+        std::env::set_var("ATOMIC_BUNDLER_TEST_SIGNER_B", SECOND_SIGNER_KEY);
+
+        let mut config = Config::default();
+        config.payment.signers.insert("a".to_string(), "ATOMIC_BUNDLER_TEST_SIGNER_A".to_string());
+        config.payment.signers.insert("b".to_string(), "ATOMIC_BUNDLER_TEST_SIGNER_B".to_string());
+        let mut second_builder = config.builders[0].clone();
+        second_builder.name = "second".to_string();
+        second_builder.signer_key_ref = Some("b".to_string());
+        config.builders[0].signer_key_ref = Some("a".to_string());
+        config.builders.push(second_builder);
+
+        let targets = config
+            .builders
+            .iter()
+            .enumerate()
+            .map(|(i, builder)| (builder, Address::repeat_byte(0x11 + i as u8), 21_000))
+            .collect();
+        let plans = plan_signers(&config, targets).unwrap();
+
+        let expected = [
+            ("flashbots", "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266"),
+            ("second", "0x70997970C51812dc3A010C7d01b50e0d17dc79C8"),
+        ];
+        assert_eq!(plans.len(), expected.len());
+        for (plan, (builder_name, signer)) in plans.iter().zip(expected) {
+            let (builder, builder_addr, gas_limit) = plan.builders[0];
+            assert_eq!(builder.name, builder_name);
+
+            let (tx2_hex, _) = PaymentTransactionForger::new()
+                .forge_flat_transfer_hex(builder_addr, U256::from(1u64), 1, 0, 30_000_000_000, 0, gas_limit, &plan.key)
+                .await
+                .unwrap();
+            let envelope = simulator::decode_raw_transaction(&tx2_hex).unwrap();
+            assert_eq!(simulator::recover_sender(&envelope).unwrap().to_string(), signer);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_observe_only_mode_never_contacts_relays() {
+        let (state, relay, request) = async_submission_setup(10).await;
+        let mut config = state.config.clone();
+        config.server.observe_only = true;
+        let state = Arc::new(AppState::new(config, Database::new_in_memory().await.unwrap()));
+
+        let (status, Json(body)) = submit_bundle(
+            State(state.clone()),
+            Query(SubmitBundleQuery::default()),
+            HeaderMap::new(),
+            Json(request),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["observeOnly"], true);
+        assert_eq!(body["submissions"][0]["status"], "observed");
+        assert!(relay.received_requests().await.unwrap().is_empty());
+
+        let bundle_id = body["bundleId"].as_str().unwrap();
+        let record = state.database.get_bundle(bundle_id).await.unwrap().unwrap();
+        assert_eq!(record.state, "queued");
+    }
+
+    #[tokio::test]
+    async fn test_low_payment_warning_against_recent_tips() {
+        let (state, _relay, request) = async_submission_setup(10).await;
+        let rpc = MockServer::start().await;
+        mock_rpc_method(
+            &rpc,
+            "eth_feeHistory",
+            json!({
+                "oldestBlock": "0x64",
+                "baseFeePerGas": ["0x1", "0x1"],
+                "gasUsedRatio": [0.5],
+                "reward": [["0x2540be400"]] // 10 gwei
+            }),
+        )
+        .await;
+        mock_rpc_method(&rpc, "eth_blockNumber", json!("0x64")).await;
+        let mut config = state.config.clone();
+        config.network.rpc_url = Some(rpc.uri());
+        config.payment.low_payment_warning_blocks = 5;
+        let state = Arc::new(AppState::new(config, Database::new_in_memory().await.unwrap()));
+
+        // 0.0001 ETH is far below half of 42_000 gas * 10 gwei = 0.00042 ETH
+        let (_, Json(body)) = submit_bundle(
+            State(state.clone()),
+            Query(SubmitBundleQuery::default()),
+            HeaderMap::new(),
+            Json(request.clone()),
+        )
+        .await
+        .unwrap();
+        let warnings = body["warnings"].as_array().unwrap();
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].as_str().unwrap().contains("below 50% of recent inclusion cost"));
+
+        // A payment at the reference level passes without warnings
+        assert!(low_payment_warning(&state, &format!("{}", request.tx1), U256::from(420_000_000_000_000u64))
+            .await
+            .is_none());
+    }
+
+    /// Mount the RPC methods tx2 forging needs, except `eth_getTransactionCount`
+    async fn mock_forging_rpc(server: &MockServer) {
+        mock_rpc_method(server, "eth_getBlockByNumber", block_json(100)).await;
+        mock_rpc_method(server, "eth_getCode", json!("0x")).await;
+        mock_rpc_method(server, "eth_getBalance", json!("0xde0b6b3a7640000")).await;
+    }
+
+    #[tokio::test]
+    async fn test_bundle_txs_lists_tx1_then_forged_tx2() {
+        std::env::set_var(DEFAULT_SIGNER_KEY_ENV, TEST_SIGNER_KEY);
+        let (state, relay, mut request) = async_submission_setup(10).await;
+        request.tx2 = None;
+        mock_forging_rpc(&relay).await;
+        mock_rpc_method(&relay, "eth_getTransactionCount", json!("0x0")).await;
+
+        let mut config = state.config.clone();
+        config.server.include_bundle_txs = true;
+        let state = Arc::new(AppState::new(config, Database::new_in_memory().await.unwrap()));
+
+        let (status, Json(body)) = submit_bundle(
+            State(state),
+            Query(SubmitBundleQuery::default()),
+            HeaderMap::new(),
+            Json(request.clone()),
+        )
+        .await
+        .unwrap();
+        assert_eq!(status, StatusCode::OK);
+
+        let bundle_txs = body["submissions"][0]["bundleTxs"].as_array().unwrap();
+        assert_eq!(bundle_txs.len(), 2);
+        assert_eq!(bundle_txs[0], request.tx1.to_string());
+
+        let tx2 = simulator::decode_raw_transaction(bundle_txs[1].as_str().unwrap()).unwrap();
+        assert_eq!(tx2.to(), TxKind::Call(Address::repeat_byte(0x11)));
+        assert_eq!(
+            simulator::recover_sender(&tx2).unwrap().to_string(),
+            "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_nonce_too_low_reforges_tx2_at_refreshed_nonce() {
+        std::env::set_var(DEFAULT_SIGNER_KEY_ENV, TEST_SIGNER_KEY);
+        let relay = MockServer::start().await;
+        Mock::given(wiremock::matchers::body_partial_json(json!({ "method": "eth_sendBundle" })))
+            .respond_with(RpcError("nonce too low: next nonce 5, tx nonce 0"))
+            .up_to_n_times(1)
+            .mount(&relay)
+            .await;
+        mock_rpc_method(&relay, "eth_sendBundle", json!("0xbundlehash")).await;
+        mock_rpc_method(&relay, "eth_blockNumber", json!("0x64")).await;
+        mock_forging_rpc(&relay).await;
+        // The signer's nonce advances between the first forge and the refresh
+        Mock::given(wiremock::matchers::body_partial_json(json!({ "method": "eth_getTransactionCount" })))
+            .respond_with(crate::test_utils::RpcResult(json!("0x0")))
+            .up_to_n_times(1)
+            .mount(&relay)
+            .await;
+        mock_rpc_method(&relay, "eth_getTransactionCount", json!("0x5")).await;
+
+        let (state, _, mut request) = async_submission_setup(10).await;
+        request.tx2 = None;
+        let mut config = state.config.clone();
+        config.network.rpc_url = Some(relay.uri());
+        config.builders[0].relay_url = relay.uri();
+        let state = Arc::new(AppState::new(config, Database::new_in_memory().await.unwrap()));
+
+        let (status, Json(body)) = submit_bundle(
+            State(state),
+            Query(SubmitBundleQuery::default()),
+            HeaderMap::new(),
+            Json(request),
+        )
+        .await
+        .unwrap();
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["submissions"][0]["status"], "submitted");
+
+        let tx2_nonces: Vec<u64> = relay
+            .received_requests()
+            .await
+            .unwrap()
+            .iter()
+            .map(|r| serde_json::from_slice::<Value>(&r.body).unwrap())
+            .filter(|body| body["method"] == "eth_sendBundle")
+            .map(|body| {
+                let tx2 = body["params"][0]["txs"][1].as_str().unwrap().to_string();
+                simulator::decode_raw_transaction(&tx2).unwrap().nonce()
+            })
+            .collect();
+        assert_eq!(tx2_nonces, vec![0, 5]);
+    }
+
+    #[tokio::test]
+    async fn test_proportional_split_pays_each_builder_half() {
+        std::env::set_var(DEFAULT_SIGNER_KEY_ENV, TEST_SIGNER_KEY);
+        let mut tx2_values = Vec::new();
+        for split in [PaymentSplit::Full, PaymentSplit::Proportional] {
+            let (state, relay, mut request) = async_submission_setup(10).await;
+            request.tx2 = None;
+            mock_forging_rpc(&relay).await;
+            mock_rpc_method(&relay, "eth_getTransactionCount", json!("0x0")).await;
+
+            let mut config = state.config.clone();
+            config.payment.split = split;
+            let mut second_builder = config.builders[0].clone();
+            second_builder.name = "second".to_string();
+            second_builder.payment_address = Address::repeat_byte(0x12).to_string();
+            config.builders.push(second_builder);
+            let state = Arc::new(AppState::new(config, Database::new_in_memory().await.unwrap()));
+
+            let (status, _) = submit_bundle(
+                State(state),
+                Query(SubmitBundleQuery::default()),
+                HeaderMap::new(),
+                Json(request),
+            )
+            .await
+            .unwrap();
+            assert_eq!(status, StatusCode::OK);
+
+            let values: Vec<U256> = relay
+                .received_requests()
+                .await
+                .unwrap()
+                .iter()
+                .map(|r| serde_json::from_slice::<Value>(&r.body).unwrap())
+                .filter(|body| body["method"] == "eth_sendBundle")
+                .map(|body| {
+                    let tx2 = body["params"][0]["txs"][1].as_str().unwrap().to_string();
+                    simulator::decode_raw_transaction(&tx2).unwrap().value()
+                })
+                .collect();
+            assert_eq!(values.len(), 2);
+            tx2_values.push(values);
+        }
+
+        let budget = tx2_values[0][0];
+        assert!(budget > U256::ZERO);
+        assert_eq!(tx2_values[0], vec![budget, budget]);
+        assert_eq!(tx2_values[1], vec![budget / U256::from(2u64), budget / U256::from(2u64)]);
+    }
+
+    #[tokio::test]
+    async fn test_response_reports_gas_formula_and_params_used() {
+        std::env::set_var(DEFAULT_SIGNER_KEY_ENV, TEST_SIGNER_KEY);
+        let mut amounts = Vec::new();
+        for formula in ["flat", "gas"] {
+            let (state, relay, mut request) = async_submission_setup(10).await;
+            request.tx2 = None;
+            request.payment.formula = formula.to_string();
+            mock_forging_rpc(&relay).await;
+            mock_rpc_method(&relay, "eth_getTransactionCount", json!("0x0")).await;
+
+            let (status, Json(body)) = submit_bundle(
+                State(state.clone()),
+                Query(SubmitBundleQuery::default()),
+                HeaderMap::new(),
+                Json(request),
+            )
+            .await
+            .unwrap();
+            assert_eq!(status, StatusCode::OK);
+            assert_eq!(body["formulaUsed"], formula);
+            assert_eq!(body["k1Used"], json!(state.config.payment.k1));
+            assert_eq!(body["k2Used"], state.config.payment.k2.to_string());
+            amounts.push(U256::from_str(body["paymentAmountWei"].as_str().unwrap()).unwrap());
+        }
+
+        // Gas estimation is not mocked, so tx1 falls back to 21000 gas: k1 * 21000 + k2
+        assert_ne!(amounts[0], amounts[1]);
+        assert_eq!(amounts[1], amounts[0] + U256::from(21_000u64));
+    }
+
+    #[tokio::test]
+    async fn test_each_request_formula_forges_its_payment_amount() {
+        std::env::set_var(DEFAULT_SIGNER_KEY_ENV, TEST_SIGNER_KEY);
+        let k2 = PaymentConfig::default().k2;
+        // tx1 estimates 21000 gas, plus 21000 for tx2; base fee is 1 gwei and tx2 carries no tip
+        let gas_used = U256::from(42_000u64);
+        let expected = [
+            ("flat", k2),
+            ("gas", gas_used + k2),
+            ("basefee", gas_used * U256::from(1_000_000_000u64) + k2),
+        ];
+
+        for (formula, expected_wei) in expected {
+            let (state, relay, mut request) = async_submission_setup(10).await;
+            request.tx2 = None;
+            request.payment.formula = formula.to_string();
+            mock_forging_rpc(&relay).await;
+            mock_rpc_method(&relay, "eth_getTransactionCount", json!("0x0")).await;
+            mock_rpc_method(&relay, "eth_estimateGas", json!("0x5208")).await;
+
+            let (status, Json(body)) = submit_bundle(
+                State(state),
+                Query(SubmitBundleQuery::default()),
+                HeaderMap::new(),
+                Json(request),
+            )
+            .await
+            .unwrap();
+            assert_eq!(status, StatusCode::OK);
+            assert_eq!(body["paymentAmountWei"], expected_wei.to_string(), "formula {}", formula);
+
+            let sent: Vec<Value> = relay
+                .received_requests()
+                .await
+                .unwrap()
+                .iter()
+                .map(|r| serde_json::from_slice::<Value>(&r.body).unwrap())
+                .filter(|body| body["method"] == "eth_sendBundle")
+                .collect();
+            let tx2 = sent[0]["params"][0]["txs"][1].as_str().unwrap().to_string();
+            assert_eq!(simulator::decode_raw_transaction(&tx2).unwrap().value(), expected_wei);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_payment_uses_simulated_gas_when_enabled() {
+        std::env::set_var(DEFAULT_SIGNER_KEY_ENV, TEST_SIGNER_KEY);
+        let mut amounts = Vec::new();
+        for use_simulated_gas in [false, true] {
+            let (state, relay, mut request) = async_submission_setup(10).await;
+            request.tx2 = None;
+            request.payment.formula = "gas".to_string();
+            mock_forging_rpc(&relay).await;
+            mock_rpc_method(&relay, "eth_getTransactionCount", json!("0x0")).await;
+            // Estimation over-reports (30000) what the simulation actually used (25000)
+            mock_rpc_method(&relay, "eth_estimateGas", json!("0x7530")).await;
+            mock_rpc_method(&relay, "eth_simulateV1", json!([{ "calls": [{ "status": "0x1", "gasUsed": "0x61a8" }] }])).await;
+
+            let mut config = state.config.clone();
+            config.payment.use_simulated_gas = use_simulated_gas;
+            let state = Arc::new(AppState::new(config, Database::new_in_memory().await.unwrap()));
+
+            let (status, Json(body)) = submit_bundle(
+                State(state),
+                Query(SubmitBundleQuery::default()),
+                HeaderMap::new(),
+                Json(request),
+            )
+            .await
+            .unwrap();
+            assert_eq!(status, StatusCode::OK);
+            amounts.push(U256::from_str(body["paymentAmountWei"].as_str().unwrap()).unwrap());
+        }
+
+        let k2 = PaymentConfig::default().k2;
+        assert_eq!(amounts[0], U256::from(30_000u64 + 21_000) + k2);
+        assert_eq!(amounts[1], U256::from(25_000u64 + 21_000) + k2);
+    }
+
+    #[tokio::test]
+    async fn test_tx2_max_fee_is_raised_to_configured_floor() {
+        std::env::set_var(DEFAULT_SIGNER_KEY_ENV, TEST_SIGNER_KEY);
+        let (state, relay, mut request) = async_submission_setup(10).await;
+        request.tx2 = None;
+        // The mocked 1 gwei base fee derives a 1.5 gwei max fee
+        mock_forging_rpc(&relay).await;
+        mock_rpc_method(&relay, "eth_getTransactionCount", json!("0x0")).await;
+
+        let mut config = state.config.clone();
+        config.payment.tx2_min_max_fee_gwei = 5.0;
+        let state = Arc::new(AppState::new(config, Database::new_in_memory().await.unwrap()));
+
+        let (status, _) = submit_bundle(
+            State(state),
+            Query(SubmitBundleQuery::default()),
+            HeaderMap::new(),
+            Json(request),
+        )
+        .await
+        .unwrap();
+        assert_eq!(status, StatusCode::OK);
+
+        let sent: Value = relay
+            .received_requests()
+            .await
+            .unwrap()
+            .iter()
+            .map(|r| serde_json::from_slice::<Value>(&r.body).unwrap())
+            .find(|body| body["method"] == "eth_sendBundle")
+            .unwrap();
+        let tx2 = simulator::decode_raw_transaction(sent["params"][0]["txs"][1].as_str().unwrap()).unwrap();
+        assert_eq!(tx2.max_fee_per_gas(), 5_000_000_000);
+    }
+
+    #[tokio::test]
+    async fn test_forged_payment_above_per_bundle_cap_is_rejected() {
+        std::env::set_var(DEFAULT_SIGNER_KEY_ENV, TEST_SIGNER_KEY);
+        let (state, relay, mut request) = async_submission_setup(10).await;
+        request.tx2 = None;
+        mock_forging_rpc(&relay).await;
+        mock_rpc_method(&relay, "eth_getTransactionCount", json!("0x0")).await;
+
+        let mut config = state.config.clone();
+        config.limits.per_bundle_cap_wei = "1000".to_string();
+        let state = Arc::new(AppState::new(config, Database::new_in_memory().await.unwrap()));
+
+        let (status, Json(body)) = submit_bundle(
+            State(state),
+            Query(SubmitBundleQuery::default()),
+            HeaderMap::new(),
+            Json(request),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+        assert!(body["error"].as_str().unwrap().contains("per-bundle cap"), "{}", body);
+        assert!(!relay
+            .received_requests()
+            .await
+            .unwrap()
+            .iter()
+            .any(|r| serde_json::from_slice::<Value>(&r.body).unwrap()["method"] == "eth_sendBundle"));
+    }
+
+    #[tokio::test]
+    async fn test_daily_cap_counts_spending_of_sent_bundles() {
+        let (state, _relay, request) = async_submission_setup(10).await;
+        let mut config = state.config.clone();
+        config.limits.daily_cap_wei = "150000000000000".to_string(); // 0.00015 ETH
+        let state = Arc::new(AppState::new(config, Database::new_in_memory().await.unwrap()));
+        let submit = |request: BundleRequest| {
+            submit_bundle(
+                State(state.clone()),
+                Query(SubmitBundleQuery::default()),
+                HeaderMap::new(),
+                Json(request),
+            )
+        };
+
+        let (status, _) = submit(request.clone()).await.unwrap();
+        assert_eq!(status, StatusCode::OK);
+        let spent = state.database.get_daily_spending(Utc::now().date_naive()).await.unwrap().unwrap();
+        assert_eq!(spent.total_amount_wei, U256::from(100_000_000_000_000u64));
+        assert_eq!(spent.bundle_count, 1);
+
+        // Another 0.0001 ETH payment would take today's spending past the cap
+        let (status, Json(body)) = submit(request).await.unwrap_err();
+        assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+        assert!(body["error"].as_str().unwrap().contains("daily cap"), "{}", body);
+    }
+
+    #[tokio::test]
+    async fn test_rejected_bundle_releases_its_spending_reservation() {
+        let (state, _relay, request) = async_submission_setup(10).await;
+        let relay = MockServer::start().await;
+        Mock::given(wiremock::matchers::body_partial_json(json!({ "method": "eth_sendBundle" })))
+            .respond_with(RpcError("bundle rejected"))
+            .mount(&relay)
+            .await;
+        mock_rpc_method(&relay, "eth_blockNumber", json!("0x64")).await;
+        let mut config = state.config.clone();
+        config.network.rpc_url = Some(relay.uri());
+        config.builders[0].relay_url = relay.uri();
+        config.limits.daily_cap_wei = "150000000000000".to_string(); // 0.00015 ETH
+        let state = Arc::new(AppState::new(config, Database::new_in_memory().await.unwrap()));
+
+        // Both attempts fit under the cap because the first one's reservation was given back
+        for _ in 0..2 {
+            let (status, Json(body)) = submit_bundle(
+                State(state.clone()),
+                Query(SubmitBundleQuery::default()),
+                HeaderMap::new(),
+                Json(request.clone()),
+            )
+            .await
+            .unwrap();
+            assert_eq!(status, StatusCode::BAD_GATEWAY, "{}", body);
+        }
+
+        let spent = state.database.get_daily_spending(Utc::now().date_naive()).await.unwrap().unwrap();
+        assert_eq!(spent.total_amount_wei, U256::ZERO);
+        assert_eq!(spent.bundle_count, 0);
+    }
+
+    #[tokio::test]
+    async fn test_monthly_cap_counts_earlier_days_of_the_month() {
+        let now = chrono::DateTime::parse_from_rfc3339("2024-01-15T12:00:00Z").unwrap().with_timezone(&Utc);
+        let (state, _relay, mut request) = async_submission_setup(10).await;
+        request.payment.expiry = now + chrono::Duration::minutes(5);
+        let mut config = state.config.clone();
+        config.limits.monthly_cap_wei = Some("1000000000000000".to_string()); // 0.001 ETH
+        let state = Arc::new(
+            AppState::new(config, Database::new_in_memory().await.unwrap()).with_clock(Arc::new(MockClock::new(now))),
+        );
+
+        for (date, amount_wei) in [("2023-12-31", 900_000_000_000_000u64), ("2024-01-03", 950_000_000_000_000u64)] {
+            let date = chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap();
+            let spending = DailySpending {
+                date,
+                total_amount_wei: U256::from(amount_wei),
+                bundle_count: 3,
+                updated_at: now,
+            };
+            state.database.upsert_daily_spending(&spending).await.unwrap();
+        }
+
+        // Today is untouched, but January already spent 0.00095 of the 0.001 ETH cap
+        let (status, Json(body)) = submit_bundle(
+            State(state),
+            Query(SubmitBundleQuery::default()),
+            HeaderMap::new(),
+            Json(request),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+        assert!(body["error"].as_str().unwrap().contains("monthly cap"), "{}", body);
+    }
+
+    #[tokio::test]
+    async fn test_unpaid_request_is_sent_as_private_transaction() {
+        let (state, relay, mut request) = async_submission_setup(10).await;
+        request.tx2 = None;
+        request.payment.max_amount_wei = "0".to_string();
+        mock_rpc_method(&relay, "eth_sendPrivateTransaction", json!("0xprivatehash")).await;
+
+        let mut config = state.config.clone();
+        config.payment.private_tx_when_unpaid = true;
+        let state = Arc::new(AppState::new(config, Database::new_in_memory().await.unwrap()));
+
+        let (status, Json(body)) = submit_bundle(
+            State(state.clone()),
+            Query(SubmitBundleQuery::default()),
+            HeaderMap::new(),
+            Json(request.clone()),
+        )
+        .await
+        .unwrap();
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["privateTransaction"], true);
+        assert_eq!(body["submissions"][0]["response"], "0xprivatehash");
+
+        let methods: Vec<Value> = relay
+            .received_requests()
+            .await
+            .unwrap()
+            .iter()
+            .map(|r| serde_json::from_slice::<Value>(&r.body).unwrap())
+            .filter(|body| body["method"] != "eth_blockNumber")
+            .collect();
+        assert_eq!(methods.len(), 1, "{:?}", methods);
+        assert_eq!(methods[0]["method"], "eth_sendPrivateTransaction");
+        assert_eq!(methods[0]["params"][0]["tx"], format!("{}", request.tx1));
+        assert_eq!(methods[0]["params"][0]["maxBlockNumber"], "0x65");
+
+        let bundle_id = body["bundleId"].as_str().unwrap();
+        let record = state.database.get_bundle(bundle_id).await.unwrap().unwrap();
+        assert_eq!(record.state, "sent");
+    }
+
+    #[tokio::test]
+    async fn test_private_transaction_rejected_by_every_builder_is_a_bad_gateway() {
+        let (state, relay, mut request) = async_submission_setup(10).await;
+        request.tx2 = None;
+        request.payment.max_amount_wei = "0".to_string();
+        Mock::given(wiremock::matchers::body_partial_json(json!({ "method": "eth_sendPrivateTransaction" })))
+            .respond_with(RpcError("method not supported"))
+            .mount(&relay)
+            .await;
+
+        let mut config = state.config.clone();
+        config.payment.private_tx_when_unpaid = true;
+        let state = Arc::new(AppState::new(config, Database::new_in_memory().await.unwrap()));
+
+        let (status, Json(body)) = submit_bundle(
+            State(state.clone()),
+            Query(SubmitBundleQuery::default()),
+            HeaderMap::new(),
+            Json(request),
+        )
+        .await
+        .unwrap();
+        assert_eq!(status, StatusCode::BAD_GATEWAY);
+        assert_eq!(body["submissions"][0]["status"], "failed");
+
+        let bundle_id = body["bundleId"].as_str().unwrap();
+        let record = state.database.get_bundle(bundle_id).await.unwrap().unwrap();
+        assert_eq!(record.state, "failed");
+    }
+
+    #[tokio::test]
+    async fn test_stale_rpc_block_rejects_bundle() {
+        std::env::set_var(DEFAULT_SIGNER_KEY_ENV, TEST_SIGNER_KEY);
+        let (state, relay, mut request) = async_submission_setup(10).await;
+        request.tx2 = None;
+        // The mocked latest block has timestamp 100, decades in the past
+        mock_forging_rpc(&relay).await;
+        mock_rpc_method(&relay, "eth_getTransactionCount", json!("0x0")).await;
+
+        let mut config = state.config.clone();
+        config.network.max_block_staleness_seconds = Some(60);
+        let state = Arc::new(AppState::new(config, Database::new_in_memory().await.unwrap()));
+
+        let (status, Json(body)) = submit_bundle(
+            State(state),
+            Query(SubmitBundleQuery::default()),
+            HeaderMap::new(),
+            Json(request),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert!(body["error"].as_str().unwrap().starts_with("RPC is stale: latest block 100"));
+        let sent_bundles = relay
+            .received_requests()
+            .await
+            .unwrap()
+            .iter()
+            .filter(|r| serde_json::from_slice::<Value>(&r.body).unwrap()["method"] == "eth_sendBundle")
+            .count();
+        assert_eq!(sent_bundles, 0);
+    }
+
+    #[tokio::test]
+    async fn test_retry_budget_caps_relay_requests_across_builders() {
+        std::env::set_var(DEFAULT_SIGNER_KEY_ENV, TEST_SIGNER_KEY);
+        let (state, rpc, mut request) = async_submission_setup(10).await;
+        request.tx2 = None;
+        mock_forging_rpc(&rpc).await;
+        mock_rpc_method(&rpc, "eth_getTransactionCount", json!("0x0")).await;
+
+        let failing_relay = MockServer::start().await;
+        Mock::given(wiremock::matchers::method("POST"))
+            .respond_with(ResponseTemplate::new(503))
+            .mount(&failing_relay)
+            .await;
+
+        let mut config = state.config.clone();
+        config.targets.bundle_retry_budget = Some(2);
+        config.builders[0].relay_url = failing_relay.uri();
+        config.builders[0].max_retries = 3;
+        config.builders[0].max_backoff_ms = 10;
+        for name in ["second", "third"] {
+            let mut builder = config.builders[0].clone();
+            builder.name = name.to_string();
+            config.builders.push(builder);
+        }
+        let state = Arc::new(AppState::new(config, Database::new_in_memory().await.unwrap()));
+
+        let (_, Json(body)) = submit_bundle(
+            State(state),
+            Query(SubmitBundleQuery::default()),
+            HeaderMap::new(),
+            Json(request),
+        )
+        .await
+        .unwrap();
+        assert!(body["submissions"].as_array().unwrap().iter().all(|s| s["status"] == "failed"));
+
+        // One attempt per builder plus the two budgeted retries, instead of 3 x (1 + 3)
+        assert_eq!(failing_relay.received_requests().await.unwrap().len(), 5);
+    }
+}
@@ -12,17 +12,29 @@ use std::sync::Arc;
 pub fn create_routes() -> Router<Arc<AppState>> {
     Router::new()
         // Bundle endpoints
-        .route("/bundles", post(handlers::submit_bundle))
+        .route("/bundles", post(handlers::create_bundle))
         .route("/bundles/:bundle_id", get(handlers::get_bundle_status))
+        .route("/payment/quote", get(handlers::payment_quote))
         
         // Health and status endpoints
         .route("/healthz", get(handlers::health_check))
+        .route("/readyz", get(handlers::readiness_check))
         .route("/status", get(handlers::system_status))
+        .route("/metrics", get(handlers::prometheus_metrics))
         
         // Admin endpoints
         .route("/admin/config/reload", post(handlers::reload_config))
+        .route("/admin/config/export", get(handlers::export_config))
         .route("/admin/killswitch", post(handlers::toggle_killswitch))
+        .route("/admin/safe-mode", post(handlers::toggle_safe_mode))
         .route("/admin/metrics", get(handlers::admin_metrics))
+        .route("/admin/stats", get(handlers::admin_stats))
+        .route("/admin/bundles/cancel-all", post(handlers::cancel_all_bundles))
+        .route("/admin/audit", get(handlers::admin_audit))
+        .route("/admin/signer/address", get(handlers::signer_address_info))
+
+        // Debug endpoints (disabled unless server.debug_endpoints_enabled)
+        .route("/debug/decode", post(handlers::decode_transaction))
         
         // Legacy endpoint names (for compatibility)
         .route("/config/reload", post(handlers::reload_config))
@@ -40,18 +52,13 @@ mod tests {
     };
     use config::Config;
     use std::sync::Arc;
-    use tokio::sync::RwLock;
     use tower::util::ServiceExt;
 
     async fn create_test_state() -> Arc<AppState> {
         let config = Config::default();
         let database = Database::new_in_memory().await.unwrap();
         
-        Arc::new(AppState {
-            config,
-            database,
-            killswitch: Arc::new(RwLock::new(false)),
-        })
+        Arc::new(AppState::new(config, database))
     }
 
     #[tokio::test]
@@ -97,4 +104,59 @@ mod tests {
         // This might fail due to validation, but the route should exist
         assert!(response.status().is_client_error() || response.status().is_success());
     }
+
+    #[tokio::test]
+    async fn test_status_endpoint_reports_relays() {
+        let state = create_test_state().await;
+        let name = state.config.builders[0].name.clone();
+        let app = create_routes().with_state(state.clone());
+
+        let status_body = |response: axum::response::Response| async move {
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+            serde_json::from_slice::<serde_json::Value>(&body).unwrap()
+        };
+
+        let request = Request::builder().uri("/status").body(Body::empty()).unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = status_body(response).await;
+        let relays = body["components"]["relays"].as_array().unwrap();
+        assert_eq!(relays.len(), 1);
+        assert_eq!(relays[0]["name"], name.as_str());
+        assert_eq!(relays[0]["status"], "unknown");
+        assert!(relays[0]["response_time_ms"].is_null());
+        assert_eq!(relays[0]["consecutive_failures"], 0);
+        assert_eq!(body["status"], "operational");
+
+        for _ in 0..types::UNHEALTHY_AFTER_FAILURES {
+            state.relay_health.write().await.update_health(&name, Err("timeout".to_string()));
+        }
+        let request = Request::builder().uri("/status").body(Body::empty()).unwrap();
+        let body = status_body(app.oneshot(request).await.unwrap()).await;
+        assert_eq!(body["components"]["relays"][0]["status"], "unhealthy");
+        assert_eq!(body["status"], "degraded");
+    }
+
+    #[tokio::test]
+    async fn test_config_export_endpoint_returns_redacted_yaml() {
+        let mut config = Config::default();
+        config.security.admin_api_key = Some("admin-secret".to_string());
+        let state = Arc::new(AppState::new(config, Database::new_in_memory().await.unwrap()));
+        let app = create_routes().with_state(state);
+
+        let request = Request::builder()
+            .uri("/admin/config/export")
+            .body(Body::empty())
+            .unwrap();
+
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()["content-type"], "application/yaml");
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let yaml = String::from_utf8(body.to_vec()).unwrap();
+        assert!(!yaml.contains("admin-secret"));
+        let exported = config::ConfigLoader::load_from_str(&yaml).unwrap();
+        assert_eq!(exported.security.admin_api_key.as_deref(), Some("<redacted>"));
+    }
 }
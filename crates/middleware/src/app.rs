@@ -1,8 +1,23 @@
 //! Main application structure and lifecycle management
 
-use crate::{api::ApiServer, database::Database, scheduler::Scheduler};
+use crate::{
+    api::ApiServer,
+    clock::{Clock, SystemClock},
+    database::Database,
+    fees::PriorityFeeCache,
+    killswitch::{DatabaseKillswitchStore, InMemoryKillswitchStore, KillswitchStore},
+    metrics::Metrics,
+    nonce::{warm_signer_nonces, NonceManager},
+    rpc::{check_clock_drift, RpcHealthCache},
+    safe_mode::{DatabaseSafeModeStore, InMemorySafeModeStore, SafeModeStore},
+    scheduler::{ResubmissionTracker, Scheduler},
+    status_cache::BundleStatusCache,
+    worker::SubmissionQueue,
+};
 use anyhow::{Context, Result};
-use config::Config;
+use config::{BuilderConfig, Config};
+use relay_client::{RelayClient, RelayHealthMonitor};
+use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use tracing::{info, warn};
@@ -12,7 +27,31 @@ use tracing::{info, warn};
 pub struct AppState {
     pub config: Config,
     pub database: Database,
-    pub killswitch: Arc<RwLock<bool>>,
+    /// Killswitch state; in-memory by default, persisted in the database in production
+    pub killswitch: Arc<dyn KillswitchStore>,
+    /// Safe mode clamps every payment to `limits.safe_mode_ceiling_wei` until cleared; in-memory
+    /// by default, persisted in the database in production
+    pub safe_mode: Arc<dyn SafeModeStore>,
+    /// Time source for time-dependent behavior
+    pub clock: Arc<dyn Clock>,
+    /// Suggested priority fee, refreshed per block
+    pub priority_fee_cache: PriorityFeeCache,
+    /// Bundles accepted with `?async=true`, drained by the submission workers
+    pub submission_queue: SubmissionQueue,
+    /// Prometheus metrics exposed on /metrics
+    pub metrics: Metrics,
+    /// Submitted bundles the scheduler may resubmit
+    pub resubmissions: ResubmissionTracker,
+    /// Cached RPC connectivity probe used by /readyz
+    pub rpc_health: RpcHealthCache,
+    /// Signer nonces prefetched at startup
+    pub nonce_manager: NonceManager,
+    /// Last known health of each enabled builder relay, refreshed by the scheduler
+    pub relay_health: Arc<RwLock<RelayHealthMonitor>>,
+    /// Recently served bundle records; invalidated whenever a bundle changes state
+    pub bundle_status_cache: BundleStatusCache,
+    /// One client per configured builder, so relay connections are pooled across requests
+    relay_clients: HashMap<String, RelayClient>,
 }
 
 /// Main application that coordinates all components
@@ -39,11 +78,15 @@ impl Application {
             .context("Failed to run database migrations")?;
 
         // Create shared application state
-        let state = Arc::new(AppState {
-            config: config.clone(),
-            database,
-            killswitch: Arc::new(RwLock::new(false)),
-        });
+        let killswitch = Arc::new(DatabaseKillswitchStore::new(database.clone()));
+        let safe_mode = Arc::new(DatabaseSafeModeStore::new(database.clone()));
+        let state = Arc::new(
+            AppState::new(config.clone(), database)
+                .with_killswitch_store(killswitch)
+                .with_safe_mode_store(safe_mode),
+        );
+        check_clock_drift(&state).await.context("Clock drift check failed")?;
+        warm_signer_nonces(&state).await;
 
         // Initialize API server
         let api_server = ApiServer::new(state.clone())
@@ -82,6 +125,11 @@ impl Application {
 
         info!("Background scheduler started");
 
+        // Start background submission workers
+        let workers = self.state.config.server.submission_workers;
+        self.state.submission_queue.spawn_workers(self.state.clone(), workers);
+        info!(workers = workers, "Submission workers started");
+
         // Start API server (this will block until shutdown)
         info!("Starting API server on {}:{}", 
             self.state.config.server.host, 
@@ -105,10 +153,7 @@ impl Application {
         info!("Shutting down application...");
 
         // Set killswitch to stop processing new requests
-        {
-            let mut killswitch = self.state.killswitch.write().await;
-            *killswitch = true;
-        }
+        self.state.activate_killswitch().await;
         info!("Killswitch activated - no new bundles will be processed");
 
         // Shutdown API server
@@ -135,7 +180,7 @@ impl Application {
 
     /// Check if the killswitch is activated
     pub async fn is_killswitch_active(&self) -> bool {
-        *self.state.killswitch.read().await
+        self.state.is_killswitch_active().await
     }
 
     /// Get application state
@@ -145,22 +190,347 @@ impl Application {
 }
 
 impl AppState {
+    /// Create application state with the system clock and an inactive killswitch
+    pub fn new(config: Config, database: Database) -> Self {
+        let submission_queue = SubmissionQueue::new(config.server.submission_queue_capacity);
+        let bundle_status_cache =
+            BundleStatusCache::new(config.server.status_cache_ttl_ms, config.server.status_cache_capacity);
+        let enabled_relays = config
+            .to_builder_relays()
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|relay| relay.enabled)
+            .collect();
+        let relay_clients = config
+            .builders
+            .iter()
+            .filter_map(|builder| Some((builder.name.clone(), RelayClient::new(config.builder_relay(builder).ok()?))))
+            .collect();
+        Self {
+            config,
+            database,
+            killswitch: Arc::new(InMemoryKillswitchStore::new()),
+            safe_mode: Arc::new(InMemorySafeModeStore::new()),
+            clock: Arc::new(SystemClock),
+            priority_fee_cache: PriorityFeeCache::new(),
+            submission_queue,
+            metrics: Metrics::new(),
+            resubmissions: ResubmissionTracker::new(),
+            rpc_health: RpcHealthCache::new(),
+            nonce_manager: NonceManager::new(),
+            relay_health: Arc::new(RwLock::new(RelayHealthMonitor::new(enabled_relays))),
+            bundle_status_cache,
+            relay_clients,
+        }
+    }
+
+    /// The long-lived client for `builder`'s relay.
+    ///
+    /// Clones share the underlying connection pool; per-request settings such as a retry budget
+    /// can be applied to the clone.
+    pub fn relay_client(&self, builder: &BuilderConfig) -> Result<RelayClient, String> {
+        match self.relay_clients.get(&builder.name) {
+            Some(client) => Ok(client.clone()),
+            None => self.config.builder_relay(builder).map(RelayClient::new),
+        }
+    }
+
+    /// Replace the time source
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Replace the killswitch backing store
+    pub fn with_killswitch_store(mut self, killswitch: Arc<dyn KillswitchStore>) -> Self {
+        self.killswitch = killswitch;
+        self
+    }
+
+    /// Replace the safe mode backing store
+    pub fn with_safe_mode_store(mut self, safe_mode: Arc<dyn SafeModeStore>) -> Self {
+        self.safe_mode = safe_mode;
+        self
+    }
+
     /// Check if the killswitch is activated
+    ///
+    /// When `security.killswitch_auto_deactivate_seconds` is set, a killswitch that has
+    /// been active for longer than the window is deactivated here. If the store can't be
+    /// read the killswitch is treated as active. The deactivation only applies to the
+    /// activation that was read, so a concurrent re-activation is never undone.
     pub async fn is_killswitch_active(&self) -> bool {
-        *self.killswitch.read().await
+        loop {
+            let activated_at = match self.killswitch.activated_at().await {
+                Ok(Some(activated_at)) => activated_at,
+                Ok(None) => return false,
+                Err(e) => {
+                    warn!(error = %e, "Failed to read killswitch state; treating it as active");
+                    return true;
+                }
+            };
+
+            let Some(window_seconds) = self.config.security.killswitch_auto_deactivate_seconds else {
+                return true;
+            };
+
+            if self.clock.now() - activated_at < chrono::Duration::seconds(window_seconds as i64) {
+                return true;
+            }
+
+            match self.killswitch.deactivate_if(activated_at).await {
+                Ok(true) => {
+                    warn!(
+                        activated_at = %activated_at,
+                        window_seconds = window_seconds,
+                        "KILLSWITCH AUTO-DEACTIVATED - grace period elapsed, resuming request processing"
+                    );
+                    return false;
+                }
+                // Deactivated or re-activated since the read; look again
+                Ok(false) => continue,
+                Err(e) => {
+                    tracing::error!(error = %e, "Failed to persist killswitch deactivation");
+                    return false;
+                }
+            }
+        }
     }
 
     /// Activate the killswitch
     pub async fn activate_killswitch(&self) {
-        let mut killswitch = self.killswitch.write().await;
-        *killswitch = true;
+        if let Err(e) = self.killswitch.activate(self.clock.now()).await {
+            tracing::error!(error = %e, "Failed to persist killswitch activation");
+        }
         warn!("Killswitch activated - system will stop processing new requests");
     }
 
     /// Deactivate the killswitch
     pub async fn deactivate_killswitch(&self) {
-        let mut killswitch = self.killswitch.write().await;
-        *killswitch = false;
+        if let Err(e) = self.killswitch.deactivate().await {
+            tracing::error!(error = %e, "Failed to persist killswitch deactivation");
+        }
         info!("Killswitch deactivated - system will resume processing requests");
     }
+
+    /// Check if safe mode is active; if the store can't be read safe mode is treated as active
+    pub async fn is_safe_mode_active(&self) -> bool {
+        match self.safe_mode.activated_at().await {
+            Ok(activated_at) => activated_at.is_some(),
+            Err(e) => {
+                warn!(error = %e, "Failed to read safe mode state; treating it as active");
+                true
+            }
+        }
+    }
+
+    /// Activate safe mode; it stays on until cleared via the admin API
+    pub async fn activate_safe_mode(&self, reason: &str) {
+        if let Err(e) = self.safe_mode.activate(self.clock.now()).await {
+            tracing::error!(error = %e, "Failed to persist safe mode activation");
+        }
+        warn!(reason = %reason, "Safe mode activated - payments capped to the safe ceiling");
+    }
+
+    /// Clear safe mode
+    pub async fn deactivate_safe_mode(&self) {
+        if let Err(e) = self.safe_mode.deactivate().await {
+            tracing::error!(error = %e, "Failed to persist safe mode deactivation");
+        }
+        info!("Safe mode cleared - payments follow the configured formula again");
+    }
+
+    /// Move a bundle to `bundle_state` and drop its cached status.
+    ///
+    /// Bundles that landed or failed are no longer resubmitted.
+    pub async fn set_bundle_state(&self, id: &str, bundle_state: &str) -> Result<()> {
+        if matches!(bundle_state, "landed" | "failed") {
+            if let Ok(bundle_id) = uuid::Uuid::parse_str(id) {
+                self.resubmissions.forget(bundle_id).await;
+            }
+        }
+        let result = self.database.update_bundle_state(id, bundle_state, self.clock.now()).await;
+        self.bundle_status_cache.invalidate(id);
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::MockClock;
+    use chrono::Utc;
+
+    #[tokio::test]
+    async fn test_killswitch_auto_deactivates_after_grace_period() {
+        let mut config = Config::default();
+        config.security.killswitch_auto_deactivate_seconds = Some(60);
+        let clock = Arc::new(MockClock::new(Utc::now()));
+        let database = Database::new_in_memory().await.unwrap();
+        let state = AppState::new(config, database).with_clock(clock.clone());
+
+        state.activate_killswitch().await;
+        assert!(state.is_killswitch_active().await);
+
+        clock.advance(chrono::Duration::seconds(59));
+        assert!(state.is_killswitch_active().await);
+
+        clock.advance(chrono::Duration::seconds(1));
+        assert!(!state.is_killswitch_active().await);
+        assert!(state.killswitch.activated_at().await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_killswitch_stays_active_without_grace_period() {
+        let clock = Arc::new(MockClock::new(Utc::now()));
+        let database = Database::new_in_memory().await.unwrap();
+        let state = AppState::new(Config::default(), database).with_clock(clock.clone());
+
+        state.activate_killswitch().await;
+        clock.advance(chrono::Duration::days(1));
+        assert!(state.is_killswitch_active().await);
+    }
+
+    #[tokio::test]
+    async fn test_killswitch_stores_behave_alike() {
+        let mut config = Config::default();
+        config.security.killswitch_auto_deactivate_seconds = Some(60);
+
+        let database = Database::new_in_memory().await.unwrap();
+        let stores: Vec<Arc<dyn KillswitchStore>> = vec![
+            Arc::new(InMemoryKillswitchStore::new()),
+            Arc::new(DatabaseKillswitchStore::new(database.clone())),
+        ];
+
+        for store in stores {
+            let clock = Arc::new(MockClock::new(Utc::now()));
+            let state = AppState::new(config.clone(), database.clone())
+                .with_clock(clock.clone())
+                .with_killswitch_store(store.clone());
+
+            assert!(!state.is_killswitch_active().await);
+            state.activate_killswitch().await;
+            assert!(state.is_killswitch_active().await);
+            assert_eq!(store.activated_at().await.unwrap(), Some(clock.now()));
+
+            clock.advance(chrono::Duration::seconds(60));
+            assert!(!state.is_killswitch_active().await);
+
+            state.activate_killswitch().await;
+            state.deactivate_killswitch().await;
+            assert!(!state.is_killswitch_active().await);
+            assert_eq!(store.activated_at().await.unwrap(), None);
+        }
+    }
+
+    /// Re-activates the killswitch right after the first read, as an operator racing a request would
+    #[derive(Debug)]
+    struct ReactivatedAfterRead {
+        inner: InMemoryKillswitchStore,
+        reactivate_at: std::sync::Mutex<Option<chrono::DateTime<Utc>>>,
+    }
+
+    #[async_trait::async_trait]
+    impl KillswitchStore for ReactivatedAfterRead {
+        async fn activated_at(&self) -> Result<Option<chrono::DateTime<Utc>>> {
+            let activated_at = self.inner.activated_at().await?;
+            let reactivate_at = self.reactivate_at.lock().unwrap().take();
+            if let Some(at) = reactivate_at {
+                self.inner.activate(at).await?;
+            }
+            Ok(activated_at)
+        }
+
+        async fn activate(&self, at: chrono::DateTime<Utc>) -> Result<()> {
+            self.inner.activate(at).await
+        }
+
+        async fn deactivate(&self) -> Result<()> {
+            self.inner.deactivate().await
+        }
+
+        async fn deactivate_if(&self, activated_at: chrono::DateTime<Utc>) -> Result<bool> {
+            self.inner.deactivate_if(activated_at).await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_auto_deactivation_spares_a_concurrent_reactivation() {
+        let mut config = Config::default();
+        config.security.killswitch_auto_deactivate_seconds = Some(60);
+        let clock = Arc::new(MockClock::new(Utc::now()));
+        let store = Arc::new(ReactivatedAfterRead {
+            inner: InMemoryKillswitchStore::new(),
+            reactivate_at: std::sync::Mutex::new(None),
+        });
+        let state = AppState::new(config, Database::new_in_memory().await.unwrap())
+            .with_clock(clock.clone())
+            .with_killswitch_store(store.clone());
+
+        state.activate_killswitch().await;
+        clock.advance(chrono::Duration::seconds(60));
+        *store.reactivate_at.lock().unwrap() = Some(clock.now());
+
+        // The expired activation was read, but the fresh one must survive
+        assert!(state.is_killswitch_active().await);
+        assert_eq!(store.inner.activated_at().await.unwrap(), Some(clock.now()));
+
+        // The database store applies the same condition
+        let database = Database::new_in_memory().await.unwrap();
+        let persisted = DatabaseKillswitchStore::new(database);
+        let first = clock.now();
+        persisted.activate(first).await.unwrap();
+        persisted.activate(first + chrono::Duration::seconds(1)).await.unwrap();
+        assert!(!persisted.deactivate_if(first).await.unwrap());
+        assert!(persisted.activated_at().await.unwrap().is_some());
+        assert!(persisted.deactivate_if(first + chrono::Duration::seconds(1)).await.unwrap());
+        assert!(persisted.activated_at().await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_database_killswitch_survives_new_state() {
+        let database = Database::new_in_memory().await.unwrap();
+        let store = Arc::new(DatabaseKillswitchStore::new(database.clone()));
+        let state = AppState::new(Config::default(), database.clone()).with_killswitch_store(store);
+        state.activate_killswitch().await;
+
+        let restarted = AppState::new(Config::default(), database.clone())
+            .with_killswitch_store(Arc::new(DatabaseKillswitchStore::new(database)));
+        assert!(restarted.is_killswitch_active().await);
+    }
+
+    #[tokio::test]
+    async fn test_database_safe_mode_survives_new_state() {
+        let database = Database::new_in_memory().await.unwrap();
+        let state = AppState::new(Config::default(), database.clone())
+            .with_safe_mode_store(Arc::new(DatabaseSafeModeStore::new(database.clone())));
+        state.activate_safe_mode("test").await;
+
+        let restarted = AppState::new(Config::default(), database.clone())
+            .with_safe_mode_store(Arc::new(DatabaseSafeModeStore::new(database.clone())));
+        assert!(restarted.is_safe_mode_active().await);
+
+        restarted.deactivate_safe_mode().await;
+        let restarted = AppState::new(Config::default(), database.clone())
+            .with_safe_mode_store(Arc::new(DatabaseSafeModeStore::new(database)));
+        assert!(!restarted.is_safe_mode_active().await);
+    }
+
+    #[tokio::test]
+    async fn test_relay_clients_reuse_connections_across_requests() {
+        let (relay_url, connections) = crate::test_utils::spawn_json_server(|request| async move {
+            serde_json::json!({ "jsonrpc": "2.0", "id": request["id"], "result": "0x64" })
+        })
+        .await;
+        let mut config = Config::default();
+        config.builders[0].relay_url = relay_url;
+        let builder = config.builders[0].clone();
+        let state = AppState::new(config, Database::new_in_memory().await.unwrap());
+
+        // Each request looks the client up afresh, as the handlers and scheduler do
+        for _ in 0..3 {
+            state.relay_client(&builder).unwrap().health_check().await.unwrap();
+        }
+        assert_eq!(connections.load(std::sync::atomic::Ordering::SeqCst), 1);
+    }
 }
@@ -1,8 +1,48 @@
 //! Database operations and connection management
 
+use alloy::primitives::U256;
 use anyhow::{Context, Result};
-use config::DatabaseConfig;
-use sqlx::{sqlite::SqlitePool, Pool, Sqlite};
+use std::collections::BTreeMap;
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
+use config::{DatabaseConfig, SqliteSynchronous};
+use sqlx::{
+    sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions},
+    Pool, Row, Sqlite, SqliteExecutor,
+};
+use std::time::Duration;
+use types::DailySpending;
+
+/// Persisted bundle lifecycle record
+#[derive(Debug, Clone)]
+pub struct BundleRecord {
+    pub id: String,
+    pub tx1_hash: String,
+    pub state: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub submitter: String,
+    /// Some relays accepted the bundle while others rejected it
+    pub partially_submitted: bool,
+}
+
+/// Bundle count and total payment attributed to one submitter
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubmitterSpend {
+    pub submitter: String,
+    pub bundle_count: u64,
+    pub total_amount_wei: U256,
+}
+
+/// One recorded admin action
+#[derive(Debug, Clone)]
+pub struct AdminAuditEntry {
+    pub id: i64,
+    pub action: String,
+    pub actor: String,
+    pub details: serde_json::Value,
+    pub created_at: DateTime<Utc>,
+}
 
 /// Database connection manager
 #[derive(Debug, Clone)]
@@ -13,18 +53,12 @@ pub struct Database {
 impl Database {
     /// Create a new database connection
     pub async fn new(config: &DatabaseConfig) -> Result<Self> {
-        let pool = SqlitePool::connect_with(
-            sqlx::sqlite::SqliteConnectOptions::new()
-                .filename(&config.url.strip_prefix("sqlite:").unwrap_or(&config.url))
-                .create_if_missing(true)
-                .journal_mode(if config.wal_mode {
-                    sqlx::sqlite::SqliteJournalMode::Wal
-                } else {
-                    sqlx::sqlite::SqliteJournalMode::Delete
-                })
-        )
-        .await
-        .context("Failed to connect to database")?;
+        let pool = SqlitePoolOptions::new()
+            .max_connections(config.max_connections)
+            .acquire_timeout(Duration::from_secs(config.connection_timeout_seconds))
+            .connect_with(connect_options(config))
+            .await
+            .context("Failed to connect to database")?;
 
         Ok(Self { pool })
     }
@@ -32,7 +66,12 @@ impl Database {
     /// Create an in-memory database for testing
     #[cfg(test)]
     pub async fn new_in_memory() -> Result<Self> {
-        let pool = SqlitePool::connect(":memory:")
+        // Each SQLite in-memory connection is its own database, so keep exactly one alive
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .idle_timeout(None)
+            .max_lifetime(None)
+            .connect(":memory:")
             .await
             .context("Failed to create in-memory database")?;
         
@@ -58,7 +97,9 @@ impl Database {
                 expires_at DATETIME,
                 block_hash TEXT,
                 block_number INTEGER,
-                gas_used INTEGER
+                gas_used INTEGER,
+                submitter TEXT NOT NULL DEFAULT 'anonymous',
+                partially_submitted BOOLEAN NOT NULL DEFAULT 0
             )
             "#,
         )
@@ -66,6 +107,15 @@ impl Database {
         .await
         .context("Failed to create bundles table")?;
 
+        self.add_missing_columns(
+            "bundles",
+            &[
+                ("submitter", "TEXT NOT NULL DEFAULT 'anonymous'"),
+                ("partially_submitted", "BOOLEAN NOT NULL DEFAULT 0"),
+            ],
+        )
+        .await?;
+
         sqlx::query(
             r#"
             CREATE TABLE IF NOT EXISTS relay_submissions (
@@ -77,6 +127,7 @@ impl Database {
                 response_data TEXT,
                 error_message TEXT,
                 retry_count INTEGER DEFAULT 0,
+                target_blocks TEXT NOT NULL DEFAULT '',
                 FOREIGN KEY (bundle_id) REFERENCES bundles(id)
             )
             "#,
@@ -85,6 +136,9 @@ impl Database {
         .await
         .context("Failed to create relay_submissions table")?;
 
+        self.add_missing_columns("relay_submissions", &[("target_blocks", "TEXT NOT NULL DEFAULT ''")])
+            .await?;
+
         sqlx::query(
             r#"
             CREATE TABLE IF NOT EXISTS daily_spending (
@@ -99,9 +153,465 @@ impl Database {
         .await
         .context("Failed to create daily_spending table")?;
 
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS killswitch (
+                id INTEGER PRIMARY KEY CHECK (id = 1),
+                activated_at DATETIME
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await
+        .context("Failed to create killswitch table")?;
+
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS safe_mode (
+                id INTEGER PRIMARY KEY CHECK (id = 1),
+                activated_at DATETIME
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await
+        .context("Failed to create safe_mode table")?;
+
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS admin_audit (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                action TEXT NOT NULL,
+                actor TEXT NOT NULL,
+                details TEXT NOT NULL DEFAULT '{}',
+                created_at DATETIME NOT NULL
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await
+        .context("Failed to create admin_audit table")?;
+
+        Ok(())
+    }
+
+    /// Add the `columns` that databases created by older versions lack to `table`
+    async fn add_missing_columns(&self, table: &str, columns: &[(&str, &str)]) -> Result<()> {
+        for (column, definition) in columns {
+            let exists: bool = sqlx::query_scalar("SELECT COUNT(*) > 0 FROM pragma_table_info(?) WHERE name = ?")
+                .bind(table)
+                .bind(column)
+                .fetch_one(&self.pool)
+                .await
+                .with_context(|| format!("Failed to inspect {} table", table))?;
+            if !exists {
+                sqlx::query(&format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition))
+                    .execute(&self.pool)
+                    .await
+                    .with_context(|| format!("Failed to add {}.{} column", table, column))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Append an admin action to the audit log
+    pub async fn record_admin_action(
+        &self,
+        action: &str,
+        actor: &str,
+        details: &serde_json::Value,
+        at: DateTime<Utc>,
+    ) -> Result<()> {
+        sqlx::query("INSERT INTO admin_audit (action, actor, details, created_at) VALUES (?, ?, ?, ?)")
+            .bind(action)
+            .bind(actor)
+            .bind(details.to_string())
+            .bind(at)
+            .execute(&self.pool)
+            .await
+            .context("Failed to record admin action")?;
+        Ok(())
+    }
+
+    /// The most recent admin actions, newest first
+    pub async fn admin_audit(&self, limit: u32) -> Result<Vec<AdminAuditEntry>> {
+        let rows = sqlx::query(
+            "SELECT id, action, actor, details, created_at FROM admin_audit ORDER BY id DESC LIMIT ?",
+        )
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to fetch admin audit")?;
+
+        rows.iter()
+            .map(|row| {
+                let details: String = row.try_get("details")?;
+                Ok(AdminAuditEntry {
+                    id: row.try_get("id")?,
+                    action: row.try_get("action")?,
+                    actor: row.try_get("actor")?,
+                    details: serde_json::from_str(&details)
+                        .with_context(|| format!("Invalid admin audit details: {}", details))?,
+                    created_at: row.try_get("created_at")?,
+                })
+            })
+            .collect()
+    }
+
+    /// When the persisted killswitch was activated, or None if it is inactive
+    pub async fn killswitch_activated_at(&self) -> Result<Option<DateTime<Utc>>> {
+        let activated_at: Option<Option<DateTime<Utc>>> =
+            sqlx::query_scalar("SELECT activated_at FROM killswitch WHERE id = 1")
+                .fetch_optional(&self.pool)
+                .await
+                .context("Failed to read killswitch")?;
+        Ok(activated_at.flatten())
+    }
+
+    /// Persist the killswitch activation time (None deactivates it)
+    pub async fn set_killswitch_activated_at(&self, activated_at: Option<DateTime<Utc>>) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO killswitch (id, activated_at) VALUES (1, ?) \
+             ON CONFLICT(id) DO UPDATE SET activated_at = excluded.activated_at",
+        )
+        .bind(activated_at)
+        .execute(&self.pool)
+        .await
+        .context("Failed to update killswitch")?;
+        Ok(())
+    }
+
+    /// When persisted safe mode was activated, or None if it is inactive
+    pub async fn safe_mode_activated_at(&self) -> Result<Option<DateTime<Utc>>> {
+        let activated_at: Option<Option<DateTime<Utc>>> =
+            sqlx::query_scalar("SELECT activated_at FROM safe_mode WHERE id = 1")
+                .fetch_optional(&self.pool)
+                .await
+                .context("Failed to read safe mode")?;
+        Ok(activated_at.flatten())
+    }
+
+    /// Persist the safe mode activation time (None clears it)
+    pub async fn set_safe_mode_activated_at(&self, activated_at: Option<DateTime<Utc>>) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO safe_mode (id, activated_at) VALUES (1, ?) \
+             ON CONFLICT(id) DO UPDATE SET activated_at = excluded.activated_at",
+        )
+        .bind(activated_at)
+        .execute(&self.pool)
+        .await
+        .context("Failed to update safe mode")?;
+        Ok(())
+    }
+
+    /// Deactivate the persisted killswitch if it is still the activation from `activated_at`.
+    ///
+    /// Returns whether it was deactivated.
+    pub async fn clear_killswitch_activated_at(&self, activated_at: DateTime<Utc>) -> Result<bool> {
+        let result = sqlx::query("UPDATE killswitch SET activated_at = NULL WHERE id = 1 AND activated_at = ?")
+            .bind(activated_at)
+            .execute(&self.pool)
+            .await
+            .context("Failed to update killswitch")?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Record a newly accepted bundle in the `queued` state
+    pub async fn insert_bundle(
+        &self,
+        id: &str,
+        tx1_hash: &str,
+        submitter: &str,
+        now: DateTime<Utc>,
+        expires_at: DateTime<Utc>,
+    ) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO bundles (id, tx1_hash, state, payment_amount_wei, created_at, updated_at, expires_at, submitter) \
+             VALUES (?, ?, 'queued', '0', ?, ?, ?, ?)",
+        )
+        .bind(id)
+        .bind(tx1_hash)
+        .bind(now)
+        .bind(now)
+        .bind(expires_at)
+        .bind(submitter)
+        .execute(&self.pool)
+        .await
+        .context("Failed to insert bundle")?;
+        Ok(())
+    }
+
+    /// Update the lifecycle state of a bundle
+    pub async fn update_bundle_state(&self, id: &str, state: &str, now: DateTime<Utc>) -> Result<()> {
+        sqlx::query("UPDATE bundles SET state = ?, updated_at = ? WHERE id = ?")
+            .bind(state)
+            .bind(now)
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .context("Failed to update bundle state")?;
+        Ok(())
+    }
+
+    /// Flag whether only some of the relays accepted a bundle
+    pub async fn set_partially_submitted(&self, id: &str, partially_submitted: bool) -> Result<()> {
+        sqlx::query("UPDATE bundles SET partially_submitted = ? WHERE id = ?")
+            .bind(partially_submitted)
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .context("Failed to update partial submission flag")?;
         Ok(())
     }
 
+    /// Record a relay's acceptance of a bundle along with its response (usually the bundle hash)
+    /// and the target blocks it was accepted for
+    pub async fn record_relay_submission(
+        &self,
+        bundle_id: &str,
+        relay_name: &str,
+        response: &str,
+        target_blocks: &[u64],
+        now: DateTime<Utc>,
+    ) -> Result<()> {
+        let target_blocks: Vec<String> = target_blocks.iter().map(u64::to_string).collect();
+        sqlx::query(
+            "INSERT INTO relay_submissions (bundle_id, relay_name, submitted_at, status, response_data, target_blocks) \
+             VALUES (?, ?, ?, 'submitted', ?, ?)",
+        )
+        .bind(bundle_id)
+        .bind(relay_name)
+        .bind(now)
+        .bind(response)
+        .bind(target_blocks.join(","))
+        .execute(&self.pool)
+        .await
+        .context("Failed to record relay submission")?;
+        Ok(())
+    }
+
+    /// Relays that accepted a bundle, with their recorded responses
+    pub async fn relay_submissions(&self, bundle_id: &str) -> Result<Vec<(String, Option<String>)>> {
+        let rows = sqlx::query(
+            "SELECT relay_name, response_data FROM relay_submissions WHERE bundle_id = ? AND status = 'submitted'",
+        )
+        .bind(bundle_id)
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to fetch relay submissions")?;
+
+        rows.iter()
+            .map(|row| Ok((row.try_get("relay_name")?, row.try_get("response_data")?)))
+            .collect()
+    }
+
+    /// Relays that accepted a bundle, with the target blocks each accepted it for
+    pub async fn relay_submission_targets(&self, bundle_id: &str) -> Result<Vec<(String, Vec<u64>)>> {
+        let rows = sqlx::query(
+            "SELECT relay_name, target_blocks FROM relay_submissions WHERE bundle_id = ? AND status = 'submitted'",
+        )
+        .bind(bundle_id)
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to fetch relay submissions")?;
+
+        rows.iter()
+            .map(|row| {
+                let target_blocks: String = row.try_get("target_blocks")?;
+                let target_blocks = target_blocks
+                    .split(',')
+                    .filter(|block| !block.is_empty())
+                    .map(|block| block.parse::<u64>().with_context(|| format!("Invalid target block: {}", block)))
+                    .collect::<Result<Vec<u64>>>()?;
+                Ok((row.try_get("relay_name")?, target_blocks))
+            })
+            .collect()
+    }
+
+    /// Ids and tx1 hashes of all bundles in `state`
+    pub async fn bundles_in_state(&self, state: &str) -> Result<Vec<(String, String)>> {
+        let rows = sqlx::query("SELECT id, tx1_hash FROM bundles WHERE state = ?")
+            .bind(state)
+            .fetch_all(&self.pool)
+            .await
+            .context("Failed to fetch bundles by state")?;
+
+        rows.iter()
+            .map(|row| Ok((row.try_get("id")?, row.try_get("tx1_hash")?)))
+            .collect()
+    }
+
+    /// Record the payment amount paid to builders for a bundle
+    pub async fn record_bundle_payment(&self, id: &str, amount_wei: U256) -> Result<()> {
+        sqlx::query("UPDATE bundles SET payment_amount_wei = ? WHERE id = ?")
+            .bind(amount_wei.to_string())
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .context("Failed to record bundle payment")?;
+        Ok(())
+    }
+
+    /// Bundle counts and total payments grouped by submitter, ordered by submitter
+    pub async fn spend_by_submitter(&self) -> Result<Vec<SubmitterSpend>> {
+        let rows = sqlx::query("SELECT submitter, payment_amount_wei FROM bundles")
+            .fetch_all(&self.pool)
+            .await
+            .context("Failed to fetch bundle spend")?;
+
+        // Amounts are stored as decimal strings, so sum them as U256 rather than in SQL
+        let mut totals: BTreeMap<String, SubmitterSpend> = BTreeMap::new();
+        for row in rows {
+            let submitter: String = row.try_get("submitter")?;
+            let amount: String = row.try_get("payment_amount_wei")?;
+            let amount = amount
+                .parse::<U256>()
+                .with_context(|| format!("Invalid payment_amount_wei: {}", amount))?;
+            let entry = totals.entry(submitter.clone()).or_insert_with(|| SubmitterSpend {
+                submitter,
+                bundle_count: 0,
+                total_amount_wei: U256::ZERO,
+            });
+            entry.bundle_count += 1;
+            entry.total_amount_wei += amount;
+        }
+        Ok(totals.into_values().collect())
+    }
+
+    /// The spending recorded for `date`, if any
+    pub async fn get_daily_spending(&self, date: NaiveDate) -> Result<Option<DailySpending>> {
+        fetch_daily_spending(&self.pool, date).await
+    }
+
+    /// Insert or replace the spending recorded for `spending.date`
+    pub async fn upsert_daily_spending(&self, spending: &DailySpending) -> Result<()> {
+        store_daily_spending(&self.pool, spending).await
+    }
+
+    /// Total spending recorded from the first of `date`'s month through `date`
+    pub async fn month_to_date_spending(&self, date: NaiveDate) -> Result<U256> {
+        sum_month_to_date_spending(&self.pool, date).await
+    }
+
+    /// Add `amount` and one bundle to `date`'s spending unless `check` objects.
+    ///
+    /// `check` sees the day's spending and the month-to-date total before the addition. Returns
+    /// the updated day, or the objection (leaving the totals untouched).
+    pub async fn reserve_daily_spending<F>(
+        &self,
+        date: NaiveDate,
+        amount: U256,
+        now: DateTime<Utc>,
+        check: F,
+    ) -> Result<std::result::Result<DailySpending, String>>
+    where
+        F: FnOnce(&DailySpending, U256) -> Option<String>,
+    {
+        self.modify_daily_spending(date, now, |mut day, month_to_date| {
+            if let Some(objection) = check(&day, month_to_date) {
+                return Err(objection);
+            }
+            day.total_amount_wei = day.total_amount_wei.checked_add(amount).ok_or("daily spending overflow")?;
+            day.bundle_count = day.bundle_count.saturating_add(1);
+            Ok(day)
+        })
+        .await
+    }
+
+    /// Add `amount` and one bundle to `date`'s spending
+    pub async fn add_daily_spending(&self, date: NaiveDate, amount: U256, now: DateTime<Utc>) -> Result<DailySpending> {
+        self.reserve_daily_spending(date, amount, now, |_, _| None)
+            .await?
+            .map_err(anyhow::Error::msg)
+    }
+
+    /// Undo a reservation: take `amount` and one bundle off `date`'s spending
+    pub async fn release_daily_spending(&self, date: NaiveDate, amount: U256, now: DateTime<Utc>) -> Result<()> {
+        self.modify_daily_spending(date, now, |mut day, _| {
+            day.total_amount_wei = day.total_amount_wei.saturating_sub(amount);
+            day.bundle_count = day.bundle_count.saturating_sub(1);
+            Ok(day)
+        })
+        .await?
+        .map_err(anyhow::Error::msg)?;
+        Ok(())
+    }
+
+    /// Read, update and store `date`'s spending in one write transaction.
+    ///
+    /// U256 totals exceed SQLite's integers and can't be summed in SQL, so the update runs here.
+    /// The transaction opens with a write (seeding an empty row for the day), which takes the
+    /// write lock before the read, so concurrent updates queue up (within `busy_timeout_ms`)
+    /// instead of overwriting each other. `modify` receives the day and the month-to-date total;
+    /// an `Err` rolls back, as does dropping the future before it completes.
+    async fn modify_daily_spending<F>(
+        &self,
+        date: NaiveDate,
+        now: DateTime<Utc>,
+        modify: F,
+    ) -> Result<std::result::Result<DailySpending, String>>
+    where
+        F: FnOnce(DailySpending, U256) -> std::result::Result<DailySpending, String>,
+    {
+        let mut tx = self.pool.begin().await.context("Failed to begin daily spending transaction")?;
+        sqlx::query("INSERT OR IGNORE INTO daily_spending (date, total_amount_wei, bundle_count, updated_at) VALUES (?, '0', 0, ?)")
+            .bind(date)
+            .bind(now)
+            .execute(&mut *tx)
+            .await
+            .context("Failed to lock daily spending")?;
+
+        let day = fetch_daily_spending(&mut *tx, date).await?.context("Daily spending row missing")?;
+        let month_to_date = sum_month_to_date_spending(&mut *tx, date).await?;
+        let mut updated = match modify(day, month_to_date) {
+            Ok(updated) => updated,
+            // Dropping `tx` rolls back the seeded row
+            Err(objection) => return Ok(Err(objection)),
+        };
+        updated.updated_at = now;
+        store_daily_spending(&mut *tx, &updated).await?;
+        tx.commit().await.context("Failed to commit daily spending")?;
+        Ok(Ok(updated))
+    }
+
+    /// Mark bundles that have been `sent` since before `sent_before` as `failed`.
+    ///
+    /// Returns the ids of the bundles failed.
+    pub async fn fail_stuck_bundles(&self, sent_before: DateTime<Utc>, now: DateTime<Utc>) -> Result<Vec<String>> {
+        sqlx::query_scalar("UPDATE bundles SET state = 'failed', updated_at = ? WHERE state = 'sent' AND updated_at < ? RETURNING id")
+            .bind(now)
+            .bind(sent_before)
+            .fetch_all(&self.pool)
+            .await
+            .context("Failed to fail stuck bundles")
+    }
+
+    /// Look up a bundle by id
+    pub async fn get_bundle(&self, id: &str) -> Result<Option<BundleRecord>> {
+        let row = sqlx::query(
+            "SELECT id, tx1_hash, state, created_at, updated_at, expires_at, submitter, partially_submitted \
+             FROM bundles WHERE id = ?",
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .context("Failed to fetch bundle")?;
+
+        row.map(|row| -> Result<BundleRecord> {
+            Ok(BundleRecord {
+                id: row.try_get("id")?,
+                tx1_hash: row.try_get("tx1_hash")?,
+                state: row.try_get("state")?,
+                created_at: row.try_get("created_at")?,
+                updated_at: row.try_get("updated_at")?,
+                expires_at: row.try_get("expires_at")?,
+                submitter: row.try_get("submitter")?,
+                partially_submitted: row.try_get("partially_submitted")?,
+            })
+        })
+        .transpose()
+    }
+
     /// Perform a health check on the database
     pub async fn health_check(&self) -> Result<()> {
         sqlx::query("SELECT 1")
@@ -123,6 +633,84 @@ impl Database {
     }
 }
 
+/// SQLite connection options derived from the database config
+fn connect_options(config: &DatabaseConfig) -> SqliteConnectOptions {
+    let synchronous = match config.synchronous {
+        SqliteSynchronous::Off => sqlx::sqlite::SqliteSynchronous::Off,
+        SqliteSynchronous::Normal => sqlx::sqlite::SqliteSynchronous::Normal,
+        SqliteSynchronous::Full => sqlx::sqlite::SqliteSynchronous::Full,
+        SqliteSynchronous::Extra => sqlx::sqlite::SqliteSynchronous::Extra,
+    };
+
+    SqliteConnectOptions::new()
+        .filename(config.url.strip_prefix("sqlite:").unwrap_or(&config.url))
+        .create_if_missing(true)
+        .journal_mode(if config.wal_mode {
+            SqliteJournalMode::Wal
+        } else {
+            SqliteJournalMode::Delete
+        })
+        .busy_timeout(Duration::from_millis(config.busy_timeout_ms))
+        .synchronous(synchronous)
+        .foreign_keys(config.foreign_keys)
+}
+
+async fn fetch_daily_spending<'e>(executor: impl SqliteExecutor<'e>, date: NaiveDate) -> Result<Option<DailySpending>> {
+    let row = sqlx::query("SELECT date, total_amount_wei, bundle_count, updated_at FROM daily_spending WHERE date = ?")
+        .bind(date)
+        .fetch_optional(executor)
+        .await
+        .context("Failed to fetch daily spending")?;
+
+    row.map(|row| daily_spending_from_row(&row)).transpose()
+}
+
+async fn store_daily_spending<'e>(executor: impl SqliteExecutor<'e>, spending: &DailySpending) -> Result<()> {
+    // U256 exceeds SQLite's integers, so the total is stored as a decimal string
+    sqlx::query(
+        "INSERT INTO daily_spending (date, total_amount_wei, bundle_count, updated_at) VALUES (?, ?, ?, ?) \
+         ON CONFLICT(date) DO UPDATE SET total_amount_wei = excluded.total_amount_wei, \
+         bundle_count = excluded.bundle_count, updated_at = excluded.updated_at",
+    )
+    .bind(spending.date)
+    .bind(spending.total_amount_wei.to_string())
+    .bind(spending.bundle_count)
+    .bind(spending.updated_at)
+    .execute(executor)
+    .await
+    .context("Failed to record daily spending")?;
+    Ok(())
+}
+
+async fn sum_month_to_date_spending<'e>(executor: impl SqliteExecutor<'e>, date: NaiveDate) -> Result<U256> {
+    let month_start = date.with_day(1).context("Invalid date")?;
+    let rows = sqlx::query("SELECT date, total_amount_wei, bundle_count, updated_at FROM daily_spending WHERE date >= ? AND date <= ?")
+        .bind(month_start)
+        .bind(date)
+        .fetch_all(executor)
+        .await
+        .context("Failed to fetch monthly spending")?;
+
+    let mut total = U256::ZERO;
+    for row in rows {
+        total = total.saturating_add(daily_spending_from_row(&row)?.total_amount_wei);
+    }
+    Ok(total)
+}
+
+/// Decode a `daily_spending` row, parsing the decimal `total_amount_wei` back into a U256
+fn daily_spending_from_row(row: &sqlx::sqlite::SqliteRow) -> Result<DailySpending> {
+    let total: String = row.try_get("total_amount_wei")?;
+    Ok(DailySpending {
+        date: row.try_get("date")?,
+        total_amount_wei: total
+            .parse::<U256>()
+            .with_context(|| format!("Invalid total_amount_wei: {}", total))?,
+        bundle_count: row.try_get("bundle_count")?,
+        updated_at: row.try_get("updated_at")?,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -151,5 +739,248 @@ mod tests {
         assert!(table_names.contains(&"bundles".to_string()));
         assert!(table_names.contains(&"relay_submissions".to_string()));
         assert!(table_names.contains(&"daily_spending".to_string()));
+        assert!(table_names.contains(&"killswitch".to_string()));
+        assert!(table_names.contains(&"safe_mode".to_string()));
+        assert!(table_names.contains(&"admin_audit".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_bundle_state_roundtrip() {
+        let db = Database::new_in_memory().await.unwrap();
+        let now = Utc::now();
+
+        let expires_at = now + chrono::Duration::minutes(5);
+        db.insert_bundle("bundle-1", "0xabc", "anonymous", now, expires_at).await.unwrap();
+        assert_eq!(db.get_bundle("bundle-1").await.unwrap().unwrap().state, "queued");
+
+        db.update_bundle_state("bundle-1", "sent", now).await.unwrap();
+        let record = db.get_bundle("bundle-1").await.unwrap().unwrap();
+        assert_eq!(record.state, "sent");
+        assert_eq!(record.tx1_hash, "0xabc");
+        assert_eq!(record.expires_at, Some(expires_at));
+
+        assert!(db.get_bundle("missing").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_daily_spending_roundtrip() {
+        let db = Database::new_in_memory().await.unwrap();
+        let date = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+        let now = Utc::now();
+        assert!(db.get_daily_spending(date).await.unwrap().is_none());
+
+        let mut spending = DailySpending {
+            date,
+            total_amount_wei: U256::from(100_000_000_000_000u64),
+            bundle_count: 1,
+            updated_at: now,
+        };
+        db.upsert_daily_spending(&spending).await.unwrap();
+        let stored = db.get_daily_spending(date).await.unwrap().unwrap();
+        assert_eq!(stored.total_amount_wei, spending.total_amount_wei);
+        assert_eq!(stored.bundle_count, 1);
+
+        // Increment past what SQLite's 64-bit integers could hold
+        let large = U256::from(u128::MAX) * U256::from(1_000u64);
+        spending.total_amount_wei += large;
+        spending.bundle_count += 1;
+        spending.updated_at = now + chrono::Duration::minutes(1);
+        db.upsert_daily_spending(&spending).await.unwrap();
+
+        let stored = db.get_daily_spending(date).await.unwrap().unwrap();
+        assert_eq!(stored.total_amount_wei, large + U256::from(100_000_000_000_000u64));
+        assert_eq!(stored.bundle_count, 2);
+        assert_eq!(stored.updated_at, spending.updated_at);
+
+        // One row per day, holding the decimal string
+        let raw: Vec<String> = sqlx::query_scalar("SELECT total_amount_wei FROM daily_spending")
+            .fetch_all(db.pool())
+            .await
+            .unwrap();
+        assert_eq!(raw, vec![stored.total_amount_wei.to_string()]);
+
+        let other_day = date.succ_opt().unwrap();
+        assert!(db.get_daily_spending(other_day).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_corrupt_daily_spending_total_is_an_error() {
+        let db = Database::new_in_memory().await.unwrap();
+        let date = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+        sqlx::query("INSERT INTO daily_spending (date, total_amount_wei, bundle_count, updated_at) VALUES (?, ?, ?, ?)")
+            .bind(date)
+            .bind("not a number")
+            .bind(1u32)
+            .bind(Utc::now())
+            .execute(db.pool())
+            .await
+            .unwrap();
+
+        assert!(db.get_daily_spending(date).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_writes_wait_out_locks() {
+        let path = std::env::temp_dir().join(format!("atomic_bundler_{}.db", uuid::Uuid::new_v4()));
+        let config = DatabaseConfig {
+            url: format!("sqlite:{}", path.display()),
+            max_connections: 8,
+            busy_timeout_ms: 10_000,
+            ..DatabaseConfig::default()
+        };
+        let db = Database::new(&config).await.unwrap();
+        db.migrate().await.unwrap();
+
+        let now = Utc::now();
+        let writes: Vec<_> = (0..32).map(|i| {
+            let db = db.clone();
+            tokio::spawn(async move {
+                let id = format!("bundle-{}", i);
+                db.insert_bundle(&id, "0xabc", "anonymous", now, now + chrono::Duration::minutes(5)).await?;
+                db.update_bundle_state(&id, "sent", now).await
+            })
+        }).collect();
+        for write in writes {
+            write.await.unwrap().expect("write failed under contention");
+        }
+
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM bundles WHERE state = 'sent'")
+            .fetch_one(db.pool())
+            .await
+            .unwrap();
+        assert_eq!(count, 32);
+
+        db.close().await.unwrap();
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[tokio::test]
+    async fn test_dropped_spending_update_rolls_back() {
+        let db = Database::new_in_memory().await.unwrap();
+        let date = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+        let now = Utc::now();
+
+        // Drop the update right after `check` ran, while its write is still in flight
+        let in_transaction = std::sync::atomic::AtomicBool::new(false);
+        let mut reservation = Box::pin(db.reserve_daily_spending(date, U256::from(100u64), now, |_, _| {
+            in_transaction.store(true, std::sync::atomic::Ordering::SeqCst);
+            None
+        }));
+        while !in_transaction.load(std::sync::atomic::Ordering::SeqCst) {
+            let poll = std::future::poll_fn(|cx| std::task::Poll::Ready(std::future::Future::poll(reservation.as_mut(), cx))).await;
+            assert!(poll.is_pending(), "reservation finished before it could be dropped");
+            tokio::task::yield_now().await;
+        }
+        drop(reservation);
+
+        // The single pooled connection must come back usable, without the dropped reservation
+        let stored = db.add_daily_spending(date, U256::from(1u64), now).await.unwrap();
+        assert_eq!(stored.total_amount_wei, U256::from(1u64));
+        assert_eq!(stored.bundle_count, 1);
+    }
+
+    async fn file_database() -> (Database, std::path::PathBuf) {
+        let path = std::env::temp_dir().join(format!("atomic_bundler_{}.db", uuid::Uuid::new_v4()));
+        let config = DatabaseConfig {
+            url: format!("sqlite:{}", path.display()),
+            max_connections: 8,
+            busy_timeout_ms: 10_000,
+            ..DatabaseConfig::default()
+        };
+        let db = Database::new(&config).await.unwrap();
+        db.migrate().await.unwrap();
+        (db, path)
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_spending_additions_all_count() {
+        let (db, path) = file_database().await;
+        let date = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+        let now = Utc::now();
+
+        let additions: Vec<_> = (1..=32u64).map(|i| {
+            let db = db.clone();
+            tokio::spawn(async move { db.add_daily_spending(date, U256::from(i), now).await })
+        }).collect();
+        for addition in additions {
+            addition.await.unwrap().expect("addition failed under contention");
+        }
+
+        let stored = db.get_daily_spending(date).await.unwrap().unwrap();
+        assert_eq!(stored.total_amount_wei, U256::from(32u64 * 33 / 2));
+        assert_eq!(stored.bundle_count, 32);
+
+        db.close().await.unwrap();
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_reservations_respect_the_cap() {
+        let (db, path) = file_database().await;
+        let date = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+        let now = Utc::now();
+        let cap = U256::from(500u64);
+
+        let reservations: Vec<_> = (0..32).map(|_| {
+            let db = db.clone();
+            tokio::spawn(async move {
+                db.reserve_daily_spending(date, U256::from(100u64), now, |day, _| {
+                    (day.total_amount_wei + U256::from(100u64) > cap).then(|| "daily cap exceeded".to_string())
+                })
+                .await
+            })
+        }).collect();
+        let mut accepted = 0;
+        for reservation in reservations {
+            match reservation.await.unwrap().expect("reservation failed under contention") {
+                Ok(_) => accepted += 1,
+                Err(objection) => assert_eq!(objection, "daily cap exceeded"),
+            }
+        }
+        assert_eq!(accepted, 5);
+
+        let stored = db.get_daily_spending(date).await.unwrap().unwrap();
+        assert_eq!(stored.total_amount_wei, cap);
+        assert_eq!(stored.bundle_count, 5);
+
+        db.close().await.unwrap();
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[tokio::test]
+    async fn test_reservation_check_sees_month_to_date_and_release_undoes_it() {
+        let db = Database::new_in_memory().await.unwrap();
+        let first = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let date = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+        let now = Utc::now();
+        db.add_daily_spending(first, U256::from(300u64), now).await.unwrap();
+        db.add_daily_spending(date, U256::from(200u64), now).await.unwrap();
+
+        let mut seen = None;
+        let reserved = db
+            .reserve_daily_spending(date, U256::from(50u64), now, |day, month_to_date| {
+                seen = Some((day.total_amount_wei, month_to_date));
+                None
+            })
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(seen, Some((U256::from(200u64), U256::from(500u64))));
+        assert_eq!(reserved.total_amount_wei, U256::from(250u64));
+        assert_eq!(reserved.bundle_count, 2);
+
+        // An objection leaves the day untouched
+        let objection = db
+            .reserve_daily_spending(date, U256::from(50u64), now, |_, _| Some("no".to_string()))
+            .await
+            .unwrap();
+        assert_eq!(objection.unwrap_err(), "no");
+        assert_eq!(db.get_daily_spending(date).await.unwrap().unwrap().total_amount_wei, U256::from(250u64));
+
+        db.release_daily_spending(date, U256::from(50u64), now).await.unwrap();
+        let stored = db.get_daily_spending(date).await.unwrap().unwrap();
+        assert_eq!(stored.total_amount_wei, U256::from(200u64));
+        assert_eq!(stored.bundle_count, 1);
+        assert_eq!(db.month_to_date_spending(date).await.unwrap(), U256::from(500u64));
     }
 }
@@ -0,0 +1,192 @@
+//! Network fee helpers used when pricing tx2
+
+use alloy::primitives::Address;
+use alloy::providers::{Provider, ProviderBuilder};
+use anyhow::{Context, Result};
+use tokio::sync::RwLock;
+
+/// Suggested priority fee (`eth_maxPriorityFeePerGas`), cached per block
+#[derive(Debug, Default)]
+pub struct PriorityFeeCache {
+    /// Block number the cached fee was fetched at, and the fee in wei
+    cached: RwLock<Option<(u64, u128)>>,
+}
+
+impl PriorityFeeCache {
+    /// Create an empty cache
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the suggested priority fee, fetching it at most once per block
+    pub async fn get(&self, rpc_url: &str, block_number: u64) -> Result<u128> {
+        if let Some((cached_block, fee)) = *self.cached.read().await {
+            if cached_block == block_number {
+                return Ok(fee);
+            }
+        }
+
+        let provider = ProviderBuilder::new().on_http(rpc_url.parse().context("Invalid RPC URL")?);
+        let fee = provider
+            .get_max_priority_fee_per_gas()
+            .await
+            .context("eth_maxPriorityFeePerGas failed")?;
+
+        *self.cached.write().await = Some((block_number, fee));
+        Ok(fee)
+    }
+}
+
+/// Gas limit for a plain ETH transfer to an EOA
+pub const EOA_TRANSFER_GAS_LIMIT: u64 = 21_000;
+
+/// Pick the tx2 gas limit for a payment target.
+///
+/// Contracts (e.g. coinbase forwarders or escrows) get `contract_gas_limit`; EOAs, or targets
+/// whose code cannot be fetched, get the plain transfer limit.
+pub async fn tx2_gas_limit(rpc_url: &str, payment_address: Address, contract_gas_limit: u64) -> u64 {
+    match fetch_code_size(rpc_url, payment_address).await {
+        Ok(0) => EOA_TRANSFER_GAS_LIMIT,
+        Ok(_) => contract_gas_limit.max(EOA_TRANSFER_GAS_LIMIT),
+        Err(e) => {
+            tracing::warn!(
+                payment_address = %payment_address,
+                error = %e,
+                "Failed to probe payment address code; assuming EOA gas limit for tx2"
+            );
+            EOA_TRANSFER_GAS_LIMIT
+        }
+    }
+}
+
+async fn fetch_code_size(rpc_url: &str, address: Address) -> Result<usize> {
+    let provider = ProviderBuilder::new().on_http(rpc_url.parse().context("Invalid RPC URL")?);
+    let code = provider.get_code_at(address).await.context("eth_getCode failed")?;
+    Ok(code.len())
+}
+
+/// Suggested max fee per gas from `eth_feeHistory`, scaled by `multiplier`
+pub async fn fee_history_max_fee(rpc_url: &str, multiplier: f64) -> Result<u128> {
+    let provider = ProviderBuilder::new().on_http(rpc_url.parse().context("Invalid RPC URL")?);
+    let estimation = provider
+        .estimate_eip1559_fees(None)
+        .await
+        .context("eth_feeHistory fee estimation failed")?;
+    Ok(scale_priority_fee(estimation.max_fee_per_gas, multiplier))
+}
+
+/// Median priority fee (50th percentile reward) paid over the last `blocks` blocks
+pub async fn recent_median_priority_fee(rpc_url: &str, blocks: u64) -> Result<u128> {
+    let provider = ProviderBuilder::new().on_http(rpc_url.parse().context("Invalid RPC URL")?);
+    let history = provider
+        .get_fee_history(blocks, alloy::rpc::types::BlockNumberOrTag::Latest, &[50.0])
+        .await
+        .context("eth_feeHistory failed")?;
+
+    let mut rewards: Vec<u128> = history
+        .reward
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|block_rewards| block_rewards.first().copied())
+        .collect();
+    anyhow::ensure!(!rewards.is_empty(), "eth_feeHistory returned no rewards");
+
+    rewards.sort_unstable();
+    Ok(rewards[rewards.len() / 2])
+}
+
+/// Scale a suggested priority fee by the configured multiplier
+pub fn scale_priority_fee(suggested_wei: u128, multiplier: f64) -> u128 {
+    if multiplier <= 0.0 {
+        return 0;
+    }
+    (suggested_wei as f64 * multiplier) as u128
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::mock_rpc_method;
+    use serde_json::json;
+    use wiremock::MockServer;
+
+    #[tokio::test]
+    async fn test_suggested_priority_fee_is_fetched_and_cached() {
+        let server = MockServer::start().await;
+        mock_rpc_method(&server, "eth_maxPriorityFeePerGas", json!("0x3b9aca00")).await;
+
+        let cache = PriorityFeeCache::new();
+        let fee = cache.get(&server.uri(), 100).await.unwrap();
+        assert_eq!(fee, 1_000_000_000);
+
+        // Same block is served from the cache
+        server.reset().await;
+        assert_eq!(cache.get(&server.uri(), 100).await.unwrap(), 1_000_000_000);
+
+        // A new block triggers a refresh
+        assert!(cache.get(&server.uri(), 101).await.is_err());
+    }
+
+    #[test]
+    fn test_priority_fee_scaling() {
+        assert_eq!(scale_priority_fee(1_000_000_000, 0.5), 500_000_000);
+        assert_eq!(scale_priority_fee(1_000_000_000, 1.0), 1_000_000_000);
+        assert_eq!(scale_priority_fee(1_000_000_000, 0.0), 0);
+    }
+
+    #[tokio::test]
+    async fn test_max_fee_from_fee_history() {
+        let server = MockServer::start().await;
+        mock_rpc_method(
+            &server,
+            "eth_feeHistory",
+            json!({
+                "oldestBlock": "0x64",
+                "baseFeePerGas": ["0x2540be400", "0x2540be400"], // 10 gwei
+                "gasUsedRatio": [0.5],
+                "reward": [["0x3b9aca00"]] // 1 gwei
+            }),
+        )
+        .await;
+
+        // Suggested max fee is 2 * base fee + median reward = 21 gwei
+        assert_eq!(fee_history_max_fee(&server.uri(), 1.0).await.unwrap(), 21_000_000_000);
+        assert_eq!(fee_history_max_fee(&server.uri(), 1.5).await.unwrap(), 31_500_000_000);
+    }
+
+    #[tokio::test]
+    async fn test_recent_median_priority_fee() {
+        let server = MockServer::start().await;
+        mock_rpc_method(
+            &server,
+            "eth_feeHistory",
+            json!({
+                "oldestBlock": "0x64",
+                "baseFeePerGas": ["0x1", "0x1", "0x1", "0x1"],
+                "gasUsedRatio": [0.5, 0.5, 0.5],
+                "reward": [["0x3b9aca00"], ["0x77359400"], ["0x0"]] // 1, 2 and 0 gwei
+            }),
+        )
+        .await;
+
+        assert_eq!(recent_median_priority_fee(&server.uri(), 3).await.unwrap(), 1_000_000_000);
+    }
+
+    #[tokio::test]
+    async fn test_contract_payment_target_uses_contract_gas_limit() {
+        let server = MockServer::start().await;
+        mock_rpc_method(&server, "eth_getCode", json!("0x6080604052")).await;
+
+        let gas_limit = tx2_gas_limit(&server.uri(), Address::repeat_byte(0x11), 60_000).await;
+        assert_eq!(gas_limit, 60_000);
+    }
+
+    #[tokio::test]
+    async fn test_eoa_payment_target_uses_transfer_gas_limit() {
+        let server = MockServer::start().await;
+        mock_rpc_method(&server, "eth_getCode", json!("0x")).await;
+
+        let gas_limit = tx2_gas_limit(&server.uri(), Address::repeat_byte(0x11), 60_000).await;
+        assert_eq!(gas_limit, EOA_TRANSFER_GAS_LIMIT);
+    }
+}
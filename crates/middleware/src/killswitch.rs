@@ -0,0 +1,95 @@
+//! Killswitch state storage
+
+use crate::database::Database;
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use tokio::sync::RwLock;
+
+/// Backing store for the killswitch: active while an activation time is recorded
+#[async_trait::async_trait]
+pub trait KillswitchStore: Send + Sync + std::fmt::Debug {
+    /// When the killswitch was activated, or None while it is inactive
+    async fn activated_at(&self) -> Result<Option<DateTime<Utc>>>;
+
+    /// Activate the killswitch as of `at`
+    async fn activate(&self, at: DateTime<Utc>) -> Result<()>;
+
+    /// Deactivate the killswitch
+    async fn deactivate(&self) -> Result<()>;
+
+    /// Deactivate the killswitch only if it is still the activation from `activated_at`.
+    ///
+    /// Returns false, leaving the killswitch alone, if it was deactivated or re-activated since.
+    async fn deactivate_if(&self, activated_at: DateTime<Utc>) -> Result<bool>;
+}
+
+/// Process-local killswitch; resets to inactive on restart
+#[derive(Debug, Default)]
+pub struct InMemoryKillswitchStore {
+    activated_at: RwLock<Option<DateTime<Utc>>>,
+}
+
+impl InMemoryKillswitchStore {
+    /// Create an inactive killswitch
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait::async_trait]
+impl KillswitchStore for InMemoryKillswitchStore {
+    async fn activated_at(&self) -> Result<Option<DateTime<Utc>>> {
+        Ok(*self.activated_at.read().await)
+    }
+
+    async fn activate(&self, at: DateTime<Utc>) -> Result<()> {
+        *self.activated_at.write().await = Some(at);
+        Ok(())
+    }
+
+    async fn deactivate(&self) -> Result<()> {
+        *self.activated_at.write().await = None;
+        Ok(())
+    }
+
+    async fn deactivate_if(&self, activated_at: DateTime<Utc>) -> Result<bool> {
+        let mut current = self.activated_at.write().await;
+        if *current != Some(activated_at) {
+            return Ok(false);
+        }
+        *current = None;
+        Ok(true)
+    }
+}
+
+/// Killswitch persisted in the database so it survives restarts
+#[derive(Debug, Clone)]
+pub struct DatabaseKillswitchStore {
+    database: Database,
+}
+
+impl DatabaseKillswitchStore {
+    /// Store the killswitch in `database` (which must be migrated)
+    pub fn new(database: Database) -> Self {
+        Self { database }
+    }
+}
+
+#[async_trait::async_trait]
+impl KillswitchStore for DatabaseKillswitchStore {
+    async fn activated_at(&self) -> Result<Option<DateTime<Utc>>> {
+        self.database.killswitch_activated_at().await
+    }
+
+    async fn activate(&self, at: DateTime<Utc>) -> Result<()> {
+        self.database.set_killswitch_activated_at(Some(at)).await
+    }
+
+    async fn deactivate(&self) -> Result<()> {
+        self.database.set_killswitch_activated_at(None).await
+    }
+
+    async fn deactivate_if(&self, activated_at: DateTime<Utc>) -> Result<bool> {
+        self.database.clear_killswitch_activated_at(activated_at).await
+    }
+}
@@ -9,9 +9,20 @@ use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 mod api;
 mod app;
+mod clock;
 mod database;
+mod fees;
+mod killswitch;
+mod metrics;
+mod nonce;
+mod rpc;
+mod safe_mode;
 mod scheduler;
+mod status_cache;
 mod storage;
+mod worker;
+#[cfg(test)]
+mod test_utils;
 
 use app::Application;
 
@@ -62,6 +73,7 @@ async fn main() -> Result<()> {
         .map(|b| b.name.clone())
         .collect();
     info!("Enabled builders: {}", enabled_builders.join(", "));
+    info!(summary = %config.summary(), "Startup configuration summary");
 
     // Create and start the application
     let mut app = Application::new(config).await
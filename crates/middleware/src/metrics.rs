@@ -0,0 +1,179 @@
+//! In-process metrics rendered in the Prometheus text exposition format
+
+use alloy::primitives::U256;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::Mutex;
+use types::{PaymentFormula, RelayHealth};
+
+/// Upper bounds (in gwei) of the payment amount histogram buckets
+const PAYMENT_GWEI_BUCKETS: [f64; 8] = [
+    1_000.0,
+    10_000.0,
+    100_000.0,
+    1_000_000.0,
+    10_000_000.0,
+    100_000_000.0,
+    1_000_000_000.0,
+    10_000_000_000.0,
+];
+
+const WEI_PER_GWEI: f64 = 1_000_000_000.0;
+
+/// Cumulative histogram with fixed bucket bounds
+#[derive(Debug, Clone, Default)]
+struct Histogram {
+    bucket_counts: [u64; PAYMENT_GWEI_BUCKETS.len()],
+    count: u64,
+    sum: f64,
+}
+
+impl Histogram {
+    fn observe(&mut self, value: f64) {
+        for (bound, bucket) in PAYMENT_GWEI_BUCKETS.iter().zip(self.bucket_counts.iter_mut()) {
+            if value <= *bound {
+                *bucket += 1;
+            }
+        }
+        self.count += 1;
+        self.sum += value;
+    }
+}
+
+/// Why a payment was reduced below its calculated amount
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum CapReason {
+    /// Clamped to `payment.max_amount_wei`
+    MaxAmount,
+    /// Clamped to `limits.safe_mode_ceiling_wei` while safe mode is active
+    SafeMode,
+}
+
+impl CapReason {
+    fn label(self) -> &'static str {
+        match self {
+            CapReason::MaxAmount => "max_amount",
+            CapReason::SafeMode => "safe_mode",
+        }
+    }
+}
+
+/// Application metrics
+#[derive(Debug, Default)]
+pub struct Metrics {
+    /// Payment amounts in gwei, keyed by payment formula
+    payment_amounts: Mutex<BTreeMap<String, Histogram>>,
+    /// Number of capped payments, keyed by cap reason
+    capped_payments: Mutex<BTreeMap<CapReason, u64>>,
+    /// Number of relay health status changes, keyed by relay and direction
+    relay_health_transitions: Mutex<BTreeMap<(String, String), u64>>,
+}
+
+impl Metrics {
+    /// Create an empty metrics registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record the payment amount of a successfully submitted bundle
+    pub fn observe_payment(&self, formula: &PaymentFormula, amount_wei: U256) {
+        let amount_gwei = u128::try_from(amount_wei).unwrap_or(u128::MAX) as f64 / WEI_PER_GWEI;
+        let mut payment_amounts = self.payment_amounts.lock().expect("metrics lock poisoned");
+        payment_amounts
+            .entry(formula.as_str().to_string())
+            .or_default()
+            .observe(amount_gwei);
+    }
+
+    /// Count a payment that was capped for `reason`
+    pub fn observe_capped_payment(&self, reason: CapReason) {
+        let mut capped_payments = self.capped_payments.lock().expect("metrics lock poisoned");
+        *capped_payments.entry(reason).or_default() += 1;
+    }
+
+    /// Count a relay moving from health status `from` to `to`
+    pub fn observe_relay_health_transition(&self, relay: &str, from: &RelayHealth, to: &RelayHealth) {
+        let direction = format!("{}_to_{}", health_label(from), health_label(to));
+        let mut transitions = self.relay_health_transitions.lock().expect("metrics lock poisoned");
+        *transitions.entry((relay.to_string(), direction)).or_default() += 1;
+    }
+
+    /// Render all metrics in the Prometheus text format
+    pub fn render(&self, namespace: &str) -> String {
+        let name = format!("{}_payment_amount_gwei", namespace);
+        let mut out = String::new();
+        let _ = writeln!(out, "# HELP {} Builder payment amounts in gwei by payment formula", name);
+        let _ = writeln!(out, "# TYPE {} histogram", name);
+
+        let payment_amounts = self.payment_amounts.lock().expect("metrics lock poisoned");
+        for (formula, histogram) in payment_amounts.iter() {
+            for (bound, count) in PAYMENT_GWEI_BUCKETS.iter().zip(histogram.bucket_counts.iter()) {
+                let _ = writeln!(out, "{}_bucket{{formula=\"{}\",le=\"{}\"}} {}", name, formula, bound, count);
+            }
+            let _ = writeln!(out, "{}_bucket{{formula=\"{}\",le=\"+Inf\"}} {}", name, formula, histogram.count);
+            let _ = writeln!(out, "{}_sum{{formula=\"{}\"}} {}", name, formula, histogram.sum);
+            let _ = writeln!(out, "{}_count{{formula=\"{}\"}} {}", name, formula, histogram.count);
+        }
+        drop(payment_amounts);
+
+        let name = format!("{}_payments_capped_total", namespace);
+        let _ = writeln!(out, "# HELP {} Payments reduced to a cap, by cap reason", name);
+        let _ = writeln!(out, "# TYPE {} counter", name);
+        let capped_payments = self.capped_payments.lock().expect("metrics lock poisoned");
+        for (reason, count) in capped_payments.iter() {
+            let _ = writeln!(out, "{}{{reason=\"{}\"}} {}", name, reason.label(), count);
+        }
+        drop(capped_payments);
+
+        let name = format!("{}_relay_health_transitions_total", namespace);
+        let _ = writeln!(out, "# HELP {} Relay health status changes, by relay and direction", name);
+        let _ = writeln!(out, "# TYPE {} counter", name);
+        let transitions = self.relay_health_transitions.lock().expect("metrics lock poisoned");
+        for ((relay, direction), count) in transitions.iter() {
+            let _ = writeln!(out, "{}{{relay=\"{}\",direction=\"{}\"}} {}", name, relay, direction, count);
+        }
+
+        out
+    }
+}
+
+fn health_label(health: &RelayHealth) -> &'static str {
+    match health {
+        RelayHealth::Healthy => "healthy",
+        RelayHealth::Degraded => "degraded",
+        RelayHealth::Unhealthy => "unhealthy",
+        RelayHealth::Unknown => "unknown",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_payment_histogram_buckets_by_formula() {
+        let metrics = Metrics::new();
+        // 0.0001 ETH = 100_000 gwei
+        metrics.observe_payment(&PaymentFormula::Flat, U256::from(100_000_000_000_000u64));
+
+        let rendered = metrics.render("atomic_bundler");
+        assert!(rendered.contains("# TYPE atomic_bundler_payment_amount_gwei histogram"));
+        assert!(rendered.contains("atomic_bundler_payment_amount_gwei_bucket{formula=\"flat\",le=\"10000\"} 0"));
+        assert!(rendered.contains("atomic_bundler_payment_amount_gwei_bucket{formula=\"flat\",le=\"100000\"} 1"));
+        assert!(rendered.contains("atomic_bundler_payment_amount_gwei_count{formula=\"flat\"} 1"));
+        assert!(!rendered.contains("formula=\"gas\""));
+    }
+
+    #[test]
+    fn test_capped_payments_counted_by_reason() {
+        let metrics = Metrics::new();
+        metrics.observe_capped_payment(CapReason::MaxAmount);
+        metrics.observe_capped_payment(CapReason::MaxAmount);
+        metrics.observe_capped_payment(CapReason::SafeMode);
+
+        let rendered = metrics.render("atomic_bundler");
+        assert!(rendered.contains("# TYPE atomic_bundler_payments_capped_total counter"));
+        assert!(rendered.contains("atomic_bundler_payments_capped_total{reason=\"max_amount\"} 2"));
+        assert!(rendered.contains("atomic_bundler_payments_capped_total{reason=\"safe_mode\"} 1"));
+    }
+}
@@ -0,0 +1,131 @@
+//! Payment signer nonce warmup
+
+use crate::api::handlers::{rpc_url, DEFAULT_SIGNER_KEY_ENV};
+use crate::app::AppState;
+use alloy::primitives::Address;
+use alloy::providers::{Provider, ProviderBuilder};
+use alloy::signers::local::PrivateKeySigner;
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::str::FromStr;
+use tokio::sync::RwLock;
+
+/// Signer nonces fetched ahead of the first submission
+#[derive(Debug, Default)]
+pub struct NonceManager {
+    nonces: RwLock<HashMap<Address, u64>>,
+}
+
+impl NonceManager {
+    /// Create an empty nonce cache
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Fetch the current nonce of `address` and cache it
+    pub async fn warm(&self, rpc_url: &str, address: Address) -> Result<u64> {
+        let nonce = fetch_nonce(rpc_url, address).await?;
+        self.nonces.write().await.insert(address, nonce);
+        Ok(nonce)
+    }
+
+    /// Fetch the current on-chain nonce of `address`, dropping any cached (now stale) nonce
+    pub async fn refresh(&self, rpc_url: &str, address: Address) -> Result<u64> {
+        self.nonces.write().await.remove(&address);
+        fetch_nonce(rpc_url, address).await
+    }
+
+    /// The cached nonce of `address`, if warmed
+    pub async fn cached(&self, address: Address) -> Option<u64> {
+        self.nonces.read().await.get(&address).copied()
+    }
+
+    /// Remove and return the cached nonce of `address`.
+    ///
+    /// A warmed nonce is only good for one submission; later ones fetch a fresh nonce.
+    pub async fn take(&self, address: Address) -> Option<u64> {
+        self.nonces.write().await.remove(&address)
+    }
+}
+
+async fn fetch_nonce(rpc_url: &str, address: Address) -> Result<u64> {
+    let provider = ProviderBuilder::new().on_http(rpc_url.parse().context("Invalid RPC URL")?);
+    provider
+        .get_transaction_count(address)
+        .await
+        .context("eth_getTransactionCount failed")
+}
+
+/// Prefetch the nonce of every configured tx2 signer.
+///
+/// Skipped when `payment.warm_signer_nonces` is off or no RPC is configured; signers whose key
+/// is unset are ignored and fetch failures are logged.
+pub async fn warm_signer_nonces(state: &AppState) {
+    let rpc_configured = std::env::var("ETH_RPC_URL").is_ok() || state.config.network.rpc_url.is_some();
+    if !state.config.payment.warm_signer_nonces || !rpc_configured {
+        return;
+    }
+
+    let rpc_url = rpc_url(state);
+    let key_envs = std::iter::once(DEFAULT_SIGNER_KEY_ENV).chain(state.config.payment.signers.values().map(String::as_str));
+    for key_env in key_envs {
+        let Some(signer) = std::env::var(key_env).ok().and_then(|key| PrivateKeySigner::from_str(&key).ok()) else {
+            continue;
+        };
+        let address = signer.address();
+        match state.nonce_manager.warm(&rpc_url, address).await {
+            Ok(nonce) => tracing::info!(signer = %address, nonce = nonce, "Warmed payment signer nonce"),
+            Err(e) => tracing::warn!(signer = %address, error = %e, "Failed to warm payment signer nonce"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::Database;
+    use crate::test_utils::mock_rpc_method;
+    use config::Config;
+    use serde_json::json;
+    use wiremock::MockServer;
+
+    const WARM_SIGNER_KEY: &str = "0x59c6995e998f97a5a0044966f0945389dc9e86dae88c7a8412f4603b6b78690d";
+
+    #[tokio::test]
+    async fn test_signer_nonce_warmed_before_first_submission() {
+        let rpc = MockServer::start().await;
+        mock_rpc_method(&rpc, "eth_getTransactionCount", json!("0x7")).await;
+
+        std::env::set_var("ATOMIC_BUNDLER_TEST_WARM_SIGNER", WARM_SIGNER_KEY);
+        let mut config = Config::default();
+        config.network.rpc_url = Some(rpc.uri());
+        config.payment.signers.insert("warm".to_string(), "ATOMIC_BUNDLER_TEST_WARM_SIGNER".to_string());
+        let state = AppState::new(config, Database::new_in_memory().await.unwrap());
+        let address = PrivateKeySigner::from_str(WARM_SIGNER_KEY).unwrap().address();
+
+        assert_eq!(state.nonce_manager.cached(address).await, None);
+        warm_signer_nonces(&state).await;
+        assert_eq!(state.nonce_manager.cached(address).await, Some(7));
+
+        // The warmed nonce serves one submission only
+        assert_eq!(state.nonce_manager.take(address).await, Some(7));
+        assert_eq!(state.nonce_manager.cached(address).await, None);
+    }
+
+    #[tokio::test]
+    async fn test_warmup_disabled_by_config() {
+        let rpc = MockServer::start().await;
+        mock_rpc_method(&rpc, "eth_getTransactionCount", json!("0x7")).await;
+
+        std::env::set_var("ATOMIC_BUNDLER_TEST_COLD_SIGNER", WARM_SIGNER_KEY);
+        let mut config = Config::default();
+        config.network.rpc_url = Some(rpc.uri());
+        config.payment.warm_signer_nonces = false;
+        config.payment.signers.insert("cold".to_string(), "ATOMIC_BUNDLER_TEST_COLD_SIGNER".to_string());
+        let state = AppState::new(config, Database::new_in_memory().await.unwrap());
+
+        warm_signer_nonces(&state).await;
+        let address = PrivateKeySigner::from_str(WARM_SIGNER_KEY).unwrap().address();
+        assert_eq!(state.nonce_manager.cached(address).await, None);
+    }
+}
@@ -0,0 +1,238 @@
+//! Ethereum RPC connectivity probing
+
+use crate::api::handlers::rpc_url;
+use crate::app::AppState;
+use alloy::providers::{Provider, ProviderBuilder};
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use serde_json::{json, Value};
+use std::collections::HashSet;
+use tokio::sync::RwLock;
+
+/// How long a probe result is reused before `eth_blockNumber` is called again
+const RPC_PROBE_TTL_SECONDS: i64 = 5;
+
+/// Cached result of the last RPC connectivity probe
+#[derive(Debug, Default)]
+pub struct RpcHealthCache {
+    /// When the last probe ran, and the block number it returned (None if it failed)
+    last_probe: RwLock<Option<(DateTime<Utc>, Option<u64>)>>,
+}
+
+impl RpcHealthCache {
+    /// Create an empty cache
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Latest block number reported by the RPC, or None if it is unreachable.
+    ///
+    /// Results (including failures) are cached for a few seconds.
+    pub async fn block_number(&self, rpc_url: &str, now: DateTime<Utc>) -> Option<u64> {
+        if let Some((probed_at, block_number)) = *self.last_probe.read().await {
+            if now - probed_at < chrono::Duration::seconds(RPC_PROBE_TTL_SECONDS) {
+                return block_number;
+            }
+        }
+
+        let block_number = probe_block_number(rpc_url).await;
+        *self.last_probe.write().await = Some((now, block_number));
+        block_number
+    }
+}
+
+async fn probe_block_number(rpc_url: &str) -> Option<u64> {
+    let provider = ProviderBuilder::new().on_http(rpc_url.parse().ok()?);
+    match provider.get_block_number().await {
+        Ok(block_number) => Some(block_number),
+        Err(e) => {
+            tracing::warn!(error = %e, "RPC connectivity check failed");
+            None
+        }
+    }
+}
+
+/// Compare the local clock against the RPC's latest block timestamp.
+///
+/// Skipped unless `network.max_clock_drift_seconds` is set and an RPC is configured. Drift beyond
+/// the threshold is logged, or returned as an error with `network.fail_on_clock_drift`; an
+/// unreachable RPC is only logged.
+pub async fn check_clock_drift(state: &AppState) -> Result<()> {
+    let rpc_configured = std::env::var("ETH_RPC_URL").is_ok() || state.config.network.rpc_url.is_some();
+    let Some(max_drift_seconds) = state.config.network.max_clock_drift_seconds.filter(|_| rpc_configured) else {
+        return Ok(());
+    };
+
+    let block_timestamp = match latest_block_timestamp(&rpc_url(state)).await {
+        Ok(timestamp) => timestamp,
+        Err(e) => {
+            tracing::warn!(error = %e, "Skipping clock drift check: could not fetch the latest block");
+            return Ok(());
+        }
+    };
+
+    let drift_seconds = state.clock.now().timestamp() - block_timestamp as i64;
+    if drift_seconds.unsigned_abs() <= max_drift_seconds {
+        return Ok(());
+    }
+
+    tracing::warn!(
+        drift_seconds = drift_seconds,
+        block_timestamp = block_timestamp,
+        max_drift_seconds = max_drift_seconds,
+        "Local clock drifts from the RPC's latest block timestamp"
+    );
+    if state.config.network.fail_on_clock_drift {
+        bail!(
+            "local clock is {}s away from the latest block timestamp (max {}s)",
+            drift_seconds,
+            max_drift_seconds
+        );
+    }
+    Ok(())
+}
+
+async fn latest_block_timestamp(rpc_url: &str) -> Result<u64> {
+    let provider = ProviderBuilder::new().on_http(rpc_url.parse().context("Invalid RPC URL")?);
+    let block = provider
+        .get_block_by_number(alloy::rpc::types::BlockNumberOrTag::Latest, false)
+        .await
+        .context("eth_getBlockByNumber failed")?
+        .context("Latest block not found")?;
+    Ok(block.header.timestamp)
+}
+
+/// Which of `tx_hashes` have a receipt, fetched with a single JSON-RPC batch request.
+///
+/// Fails if the RPC doesn't answer the batch with one successful response per hash, e.g.
+/// because it doesn't support batching; callers should then fall back to individual calls.
+/// `client` should carry a timeout so a hung RPC can't stall the caller.
+pub async fn batch_receipts_included(
+    client: &reqwest::Client,
+    rpc_url: &str,
+    tx_hashes: &[String],
+) -> Result<HashSet<String>> {
+    let batch: Vec<Value> = tx_hashes
+        .iter()
+        .enumerate()
+        .map(|(id, tx_hash)| {
+            json!({ "jsonrpc": "2.0", "id": id, "method": "eth_getTransactionReceipt", "params": [tx_hash] })
+        })
+        .collect();
+
+    let response: Value = client
+        .post(rpc_url)
+        .json(&batch)
+        .send()
+        .await
+        .context("Batched eth_getTransactionReceipt failed")?
+        .error_for_status()
+        .context("RPC rejected the batch request")?
+        .json()
+        .await
+        .context("Invalid batch response")?;
+
+    let Value::Array(responses) = response else {
+        bail!("RPC did not answer the batch request with an array");
+    };
+    if responses.len() != tx_hashes.len() {
+        bail!("RPC answered {} of {} batched calls", responses.len(), tx_hashes.len());
+    }
+
+    let mut included = HashSet::new();
+    for response in responses {
+        if let Some(error) = response.get("error") {
+            bail!("Batched receipt lookup failed: {}", error);
+        }
+        let tx_hash = response["id"]
+            .as_u64()
+            .and_then(|id| tx_hashes.get(id as usize))
+            .context("Batch response has an unknown id")?;
+        if !response["result"].is_null() {
+            included.insert(tx_hash.clone());
+        }
+    }
+    Ok(included)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::MockClock;
+    use crate::database::Database;
+    use crate::test_utils::{block_json, mock_rpc_method, LogBuffer};
+    use config::Config;
+    use serde_json::json;
+    use std::sync::Arc;
+    use wiremock::MockServer;
+
+    async fn drift_test_state(rpc_url: String, fail_on_clock_drift: bool) -> AppState {
+        let mut config = Config::default();
+        config.network.rpc_url = Some(rpc_url);
+        config.network.max_clock_drift_seconds = Some(30);
+        config.network.fail_on_clock_drift = fail_on_clock_drift;
+        let now = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        AppState::new(config, Database::new_in_memory().await.unwrap()).with_clock(Arc::new(MockClock::new(now)))
+    }
+
+    #[tokio::test]
+    async fn test_clock_drift_beyond_threshold_warns() {
+        let server = MockServer::start().await;
+        mock_rpc_method(&server, "eth_getBlockByNumber", block_json(1_700_000_000 - 3600)).await;
+
+        let logs = LogBuffer::default();
+        let writer = logs.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_writer(move || writer.clone())
+            .with_ansi(false)
+            .finish();
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let state = drift_test_state(server.uri(), false).await;
+        check_clock_drift(&state).await.unwrap();
+        let output = logs.contents();
+        assert!(output.contains("Local clock drifts from the RPC's latest block timestamp"));
+        assert!(output.contains("drift_seconds=3600"));
+
+        let state = drift_test_state(server.uri(), true).await;
+        assert!(check_clock_drift(&state).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_clock_within_threshold_passes() {
+        let server = MockServer::start().await;
+        mock_rpc_method(&server, "eth_getBlockByNumber", block_json(1_700_000_000 - 12)).await;
+
+        let state = drift_test_state(server.uri(), true).await;
+        check_clock_drift(&state).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_block_number_probe_is_cached() {
+        let server = MockServer::start().await;
+        mock_rpc_method(&server, "eth_blockNumber", json!("0x10")).await;
+
+        let cache = RpcHealthCache::new();
+        let now = Utc::now();
+        assert_eq!(cache.block_number(&server.uri(), now).await, Some(16));
+
+        // Within the TTL the cached value is served even if the RPC goes away
+        server.reset().await;
+        assert_eq!(cache.block_number(&server.uri(), now + chrono::Duration::seconds(1)).await, Some(16));
+        assert_eq!(cache.block_number(&server.uri(), now + chrono::Duration::seconds(10)).await, None);
+    }
+
+    #[tokio::test]
+    async fn test_hung_receipt_batch_times_out() {
+        let server = MockServer::start().await;
+        wiremock::Mock::given(wiremock::matchers::method("POST"))
+            .respond_with(wiremock::ResponseTemplate::new(200).set_body_json(json!([])).set_delay(std::time::Duration::from_secs(5)))
+            .mount(&server)
+            .await;
+
+        let client = reqwest::Client::builder().timeout(std::time::Duration::from_millis(100)).build().unwrap();
+        let hashes = vec!["0xabc".to_string(), "0xdef".to_string()];
+        let err = batch_receipts_included(&client, &server.uri(), &hashes).await.unwrap_err();
+        assert!(format!("{:#}", err).contains("Batched eth_getTransactionReceipt failed"), "{:#}", err);
+    }
+}
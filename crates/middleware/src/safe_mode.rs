@@ -0,0 +1,77 @@
+//! Safe mode state storage
+
+use crate::database::Database;
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use tokio::sync::RwLock;
+
+/// Backing store for safe mode: active while an activation time is recorded
+#[async_trait::async_trait]
+pub trait SafeModeStore: Send + Sync + std::fmt::Debug {
+    /// When safe mode was activated, or None while it is inactive
+    async fn activated_at(&self) -> Result<Option<DateTime<Utc>>>;
+
+    /// Activate safe mode as of `at`
+    async fn activate(&self, at: DateTime<Utc>) -> Result<()>;
+
+    /// Clear safe mode
+    async fn deactivate(&self) -> Result<()>;
+}
+
+/// Process-local safe mode; resets to inactive on restart
+#[derive(Debug, Default)]
+pub struct InMemorySafeModeStore {
+    activated_at: RwLock<Option<DateTime<Utc>>>,
+}
+
+impl InMemorySafeModeStore {
+    /// Create a store with safe mode inactive
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait::async_trait]
+impl SafeModeStore for InMemorySafeModeStore {
+    async fn activated_at(&self) -> Result<Option<DateTime<Utc>>> {
+        Ok(*self.activated_at.read().await)
+    }
+
+    async fn activate(&self, at: DateTime<Utc>) -> Result<()> {
+        *self.activated_at.write().await = Some(at);
+        Ok(())
+    }
+
+    async fn deactivate(&self) -> Result<()> {
+        *self.activated_at.write().await = None;
+        Ok(())
+    }
+}
+
+/// Safe mode persisted in the database so it survives restarts
+#[derive(Debug, Clone)]
+pub struct DatabaseSafeModeStore {
+    database: Database,
+}
+
+impl DatabaseSafeModeStore {
+    /// Store safe mode in `database` (which must be migrated)
+    pub fn new(database: Database) -> Self {
+        Self { database }
+    }
+}
+
+#[async_trait::async_trait]
+impl SafeModeStore for DatabaseSafeModeStore {
+    async fn activated_at(&self) -> Result<Option<DateTime<Utc>>> {
+        self.database.safe_mode_activated_at().await
+    }
+
+    async fn activate(&self, at: DateTime<Utc>) -> Result<()> {
+        self.database.set_safe_mode_activated_at(Some(at)).await
+    }
+
+    async fn deactivate(&self) -> Result<()> {
+        self.database.set_safe_mode_activated_at(None).await
+    }
+}
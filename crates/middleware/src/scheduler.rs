@@ -2,25 +2,162 @@
 
 use crate::app::AppState;
 use anyhow::Result;
+use chrono::{DateTime, Utc};
+use alloy::providers::{Provider, ProviderBuilder};
+use config::{BuilderConfig, InclusionStrategy};
+use relay_client::RelayManager;
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
 use std::sync::Arc;
+use tokio::sync::{Mutex, Semaphore};
+use tokio::task::JoinSet;
 use tokio::time::{interval, Duration};
+use uuid::Uuid;
+
+/// A bundle submitted to one builder that may be resubmitted later
+#[derive(Debug, Clone)]
+pub struct ResubmissionEntry {
+    pub builder: BuilderConfig,
+    pub txs: Vec<String>,
+    pub target_block: Option<u64>,
+    pub first_submitted_at: DateTime<Utc>,
+    pub last_attempt_at: DateTime<Utc>,
+    pub attempts: u32,
+}
+
+/// Submitted bundles awaiting resubmission, keyed by bundle id and builder name
+#[derive(Debug, Default)]
+pub struct ResubmissionTracker {
+    entries: Mutex<HashMap<(Uuid, String), ResubmissionEntry>>,
+}
+
+impl ResubmissionTracker {
+    /// Create an empty tracker
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Track a successful submission of `txs` to `builder`
+    pub async fn record(
+        &self,
+        bundle_id: Uuid,
+        builder: &BuilderConfig,
+        txs: Vec<String>,
+        target_block: Option<u64>,
+        now: DateTime<Utc>,
+    ) {
+        self.entries.lock().await.insert(
+            (bundle_id, builder.name.clone()),
+            ResubmissionEntry {
+                builder: builder.clone(),
+                txs,
+                target_block,
+                first_submitted_at: now,
+                last_attempt_at: now,
+                attempts: 0,
+            },
+        );
+    }
+
+    /// Stop resubmitting a bundle to every builder
+    pub async fn forget(&self, bundle_id: Uuid) {
+        self.entries.lock().await.retain(|(id, _), _| *id != bundle_id);
+    }
+
+    /// Aim later resubmissions of a bundle to `builder_name` at `target_block`
+    pub async fn retarget(&self, bundle_id: Uuid, builder_name: &str, target_block: u64) {
+        if let Some(entry) = self.entries.lock().await.get_mut(&(bundle_id, builder_name.to_string())) {
+            entry.target_block = Some(target_block);
+        }
+    }
+
+    /// Take the entries due for resubmission at `now`, marking them as attempted.
+    ///
+    /// Entries that have exhausted `max_attempts` or outlived `expiry_seconds` are dropped.
+    pub async fn take_due(
+        &self,
+        now: DateTime<Utc>,
+        interval_seconds: u64,
+        max_attempts: u32,
+        expiry_seconds: u64,
+    ) -> Vec<(Uuid, ResubmissionEntry)> {
+        let mut entries = self.entries.lock().await;
+        entries.retain(|_, entry| {
+            entry.attempts < max_attempts
+                && now - entry.first_submitted_at < chrono::Duration::seconds(expiry_seconds as i64)
+        });
+
+        let mut due = Vec::new();
+        for ((bundle_id, _), entry) in entries.iter_mut() {
+            if resubmission_due(entry.last_attempt_at, now, interval_seconds) {
+                entry.last_attempt_at = now;
+                entry.attempts += 1;
+                due.push((*bundle_id, entry.clone()));
+            }
+        }
+        due
+    }
+}
+
+/// Whether enough time has passed since the last attempt to resubmit
+fn resubmission_due(last_attempt_at: DateTime<Utc>, now: DateTime<Utc>, interval_seconds: u64) -> bool {
+    now - last_attempt_at >= chrono::Duration::seconds(interval_seconds as i64)
+}
+
+/// Run `checks` with at most `limit` of them in flight, returning results in completion order
+async fn run_bounded<T, Fut>(checks: impl IntoIterator<Item = Fut>, limit: usize) -> Vec<T>
+where
+    T: Send + 'static,
+    Fut: Future<Output = T> + Send + 'static,
+{
+    let permits = Arc::new(Semaphore::new(limit.max(1)));
+    let mut tasks = JoinSet::new();
+    for check in checks {
+        let permit = permits.clone().acquire_owned().await.expect("semaphore is never closed");
+        tasks.spawn(async move {
+            let result = check.await;
+            drop(permit);
+            result
+        });
+    }
+
+    let mut results = Vec::new();
+    while let Some(joined) = tasks.join_next().await {
+        match joined {
+            Ok(result) => results.push(result),
+            Err(e) => tracing::error!(error = %e, "Inclusion check task failed"),
+        }
+    }
+    results
+}
 
 /// Background task scheduler
 #[derive(Debug, Clone)]
 pub struct Scheduler {
     state: Arc<AppState>,
+    /// Clients for every enabled builder relay, health-checked on a timer
+    relays: Arc<RelayManager>,
+    /// HTTP client for batched RPC calls, bounded by `network.rpc_timeout_seconds`
+    rpc_client: reqwest::Client,
 }
 
 impl Scheduler {
     /// Create a new scheduler
     pub async fn new(state: Arc<AppState>) -> Result<Self> {
-        Ok(Self { state })
+        let relays = state.config.to_builder_relays().map_err(anyhow::Error::msg)?;
+        let relays = Arc::new(RelayManager::new(relays)?);
+        let rpc_client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(state.config.network.rpc_timeout_seconds))
+            .build()?;
+        Ok(Self { state, relays, rpc_client })
     }
 
     /// Run the scheduler
     pub async fn run(&mut self) -> Result<()> {
         let mut cleanup_interval = interval(Duration::from_secs(300)); // 5 minutes
         let mut health_check_interval = interval(Duration::from_secs(60)); // 1 minute
+        let mut resubmit_interval = interval(Duration::from_secs(1));
+        let mut inclusion_interval = interval(Duration::from_secs(12)); // one slot
 
         loop {
             tokio::select! {
@@ -34,6 +171,14 @@ impl Scheduler {
                         tracing::error!("Health check task failed: {}", e);
                     }
                 }
+                _ = resubmit_interval.tick() => {
+                    self.resubmit_due_bundles().await;
+                }
+                _ = inclusion_interval.tick() => {
+                    if let Err(e) = self.detect_inclusions().await {
+                        tracing::error!("Inclusion detection failed: {}", e);
+                    }
+                }
             }
         }
     }
@@ -47,14 +192,589 @@ impl Scheduler {
     /// Clean up expired bundles
     async fn cleanup_expired_bundles(&self) -> Result<()> {
         tracing::debug!("Running expired bundle cleanup");
-        // TODO: Implement cleanup logic
+        self.fail_stuck_bundles().await?;
         Ok(())
     }
 
-    /// Perform health checks on relays
+    /// Fail bundles stuck in `sent` for longer than `targets.stuck_bundle_timeout_seconds`
+    async fn fail_stuck_bundles(&self) -> Result<u64> {
+        let now = self.state.clock.now();
+        let timeout = chrono::Duration::seconds(self.state.config.targets.stuck_bundle_timeout_seconds as i64);
+        let failed = self.state.database.fail_stuck_bundles(now - timeout, now).await?;
+        if !failed.is_empty() {
+            tracing::warn!(count = failed.len(), "Marked bundles stuck in sent as failed");
+            self.state.bundle_status_cache.clear();
+        }
+        for bundle_id in &failed {
+            if let Ok(bundle_id) = Uuid::parse_str(bundle_id) {
+                self.state.resubmissions.forget(bundle_id).await;
+            }
+        }
+        Ok(failed.len() as u64)
+    }
+
+    /// Mark `sent` bundles as `landed` using `targets.inclusion_strategy`.
+    ///
+    /// tx1 receipts are fetched in batches and relay stats are queried for up to
+    /// `targets.inclusion_check_concurrency` bundles at once. Returns the number of bundles
+    /// marked landed.
+    async fn detect_inclusions(&self) -> Result<u64> {
+        let strategy = self.state.config.targets.inclusion_strategy;
+        let sent = self.state.database.bundles_in_state("sent").await?;
+        let receipts = match strategy {
+            InclusionStrategy::Receipt | InclusionStrategy::Both => {
+                let tx1_hashes: Vec<String> = sent.iter().map(|(_, tx1_hash)| tx1_hash.clone()).collect();
+                self.receipts_included(&tx1_hashes).await
+            }
+            InclusionStrategy::RelayStats => HashSet::new(),
+        };
+
+        let checks = sent.into_iter().map(|(bundle_id, tx1_hash)| {
+            let scheduler = self.clone();
+            let has_receipt = receipts.contains(&tx1_hash);
+            async move {
+                let included = match strategy {
+                    InclusionStrategy::Receipt => has_receipt,
+                    InclusionStrategy::RelayStats => scheduler.relay_stats_included(&bundle_id).await,
+                    InclusionStrategy::Both => has_receipt || scheduler.relay_stats_included(&bundle_id).await,
+                };
+                (bundle_id, included)
+            }
+        });
+        let results = run_bounded(checks, self.state.config.targets.inclusion_check_concurrency).await;
+
+        let mut landed = 0;
+        for (bundle_id, included) in results {
+            if included {
+                tracing::info!(bundle_id = %bundle_id, strategy = ?strategy, "Bundle landed");
+                self.state.set_bundle_state(&bundle_id, "landed").await?;
+                landed += 1;
+            }
+        }
+        Ok(landed)
+    }
+
+    /// The tx1 hashes the RPC has receipts for, requested `targets.receipt_batch_size` at a
+    /// time and one by one when the RPC doesn't support batching
+    async fn receipts_included(&self, tx1_hashes: &[String]) -> HashSet<String> {
+        let rpc_url = crate::api::handlers::rpc_url(&self.state);
+        let batch_size = self.state.config.targets.receipt_batch_size.max(1);
+        let mut included = HashSet::new();
+        for chunk in tx1_hashes.chunks(batch_size) {
+            if chunk.len() > 1 {
+                match crate::rpc::batch_receipts_included(&self.rpc_client, &rpc_url, chunk).await {
+                    Ok(batch) => {
+                        included.extend(batch);
+                        continue;
+                    }
+                    Err(e) => {
+                        tracing::debug!(error = %e, "Batched receipt lookup failed; fetching receipts individually");
+                    }
+                }
+            }
+
+            let checks = chunk.iter().cloned().map(|tx1_hash| {
+                let scheduler = self.clone();
+                async move {
+                    let has_receipt = scheduler.receipt_included(&tx1_hash).await;
+                    (tx1_hash, has_receipt)
+                }
+            });
+            let results = run_bounded(checks, self.state.config.targets.inclusion_check_concurrency).await;
+            included.extend(results.into_iter().filter(|(_, has_receipt)| *has_receipt).map(|(tx1_hash, _)| tx1_hash));
+        }
+        included
+    }
+
+    /// Whether the RPC has a receipt for tx1
+    async fn receipt_included(&self, tx1_hash: &str) -> bool {
+        let rpc_url = crate::api::handlers::rpc_url(&self.state);
+        let Ok(url) = rpc_url.parse() else {
+            return false;
+        };
+        let provider = ProviderBuilder::new().on_http(url);
+        match provider
+            .raw_request::<_, Option<serde_json::Value>>("eth_getTransactionReceipt".into(), (tx1_hash.to_string(),))
+            .await
+        {
+            Ok(receipt) => receipt.is_some(),
+            Err(e) => {
+                tracing::warn!(tx1_hash = %tx1_hash, error = %e, "Failed to fetch tx1 receipt");
+                false
+            }
+        }
+    }
+
+    /// Whether any builder that accepted the bundle reports it included via its stats endpoint
+    async fn relay_stats_included(&self, bundle_id: &str) -> bool {
+        let submissions = match self.state.database.relay_submissions(bundle_id).await {
+            Ok(submissions) => submissions,
+            Err(e) => {
+                tracing::warn!(bundle_id = %bundle_id, error = %e, "Failed to load relay submissions");
+                return false;
+            }
+        };
+
+        for (relay_name, bundle_hash) in submissions {
+            let Some(bundle_hash) = bundle_hash else {
+                continue;
+            };
+            let Some(builder) = self
+                .state
+                .config
+                .builders
+                .iter()
+                .find(|b| b.name == relay_name && b.status_url.is_some())
+            else {
+                continue;
+            };
+            let Ok(relay) = self.state.relay_client(builder) else {
+                continue;
+            };
+            match relay.bundle_included(&bundle_hash).await {
+                Ok(true) => return true,
+                Ok(false) => {}
+                Err(e) => {
+                    tracing::warn!(bundle_id = %bundle_id, builder = %relay_name, error = %e, "Bundle stats query failed");
+                }
+            }
+        }
+        false
+    }
+
+    /// Bundles whose last attempt is at least `targets.resubmit_interval_seconds` old
+    async fn due_resubmissions(&self) -> Vec<(Uuid, ResubmissionEntry)> {
+        let targets = &self.state.config.targets;
+        self.state
+            .resubmissions
+            .take_due(
+                self.state.clock.now(),
+                targets.resubmit_interval_seconds,
+                targets.resubmit_max,
+                targets.bundle_expiry_seconds,
+            )
+            .await
+    }
+
+    /// Resubmit bundles whose resubmission interval has elapsed.
+    ///
+    /// A bundle whose target block has been built is re-targeted at the next block; targeted
+    /// bundles are skipped while the chain head is unknown.
+    async fn resubmit_due_bundles(&self) {
+        let due = self.due_resubmissions().await;
+        let next_block = if due.iter().any(|(_, entry)| entry.target_block.is_some()) {
+            match crate::api::handlers::next_block(&crate::api::handlers::rpc_url(&self.state)).await {
+                Ok(next_block) => Some(next_block),
+                Err(e) => {
+                    tracing::warn!(error = %e, "Could not fetch the chain head; skipping targeted resubmissions");
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+        for (bundle_id, mut entry) in due {
+            if let Some(target_block) = entry.target_block {
+                let Some(next_block) = next_block else {
+                    continue;
+                };
+                if target_block < next_block {
+                    entry.target_block = Some(next_block);
+                    self.state.resubmissions.retarget(bundle_id, &entry.builder.name, next_block).await;
+                }
+            }
+
+            let relay = match self.state.relay_client(&entry.builder) {
+                Ok(relay) => relay,
+                Err(e) => {
+                    tracing::warn!(bundle_id = %bundle_id, error = %e, "Skipping resubmission");
+                    continue;
+                }
+            };
+
+            tracing::info!(
+                bundle_id = %bundle_id,
+                builder = %entry.builder.name,
+                attempt = entry.attempts,
+                target_block = entry.target_block,
+                "Resubmitting bundle"
+            );
+            let result = relay
+                .submit_bundle_with_uuid(
+                    entry.txs,
+                    entry.target_block,
+                    Some(relay_client::block_replacement_uuid(bundle_id, entry.target_block)),
+                )
+                .await;
+            match result {
+                // Recorded so cancellation also covers the new target's replacement UUID
+                Ok(bundle_hash) => {
+                    let target_blocks: Vec<u64> = entry.target_block.into_iter().collect();
+                    if let Err(e) = self
+                        .state
+                        .database
+                        .record_relay_submission(&bundle_id.to_string(), &entry.builder.name, &bundle_hash, &target_blocks, self.state.clock.now())
+                        .await
+                    {
+                        tracing::warn!(bundle_id = %bundle_id, builder = %entry.builder.name, error = %e, "Failed to record relay submission");
+                    }
+                }
+                Err(e) => {
+                    tracing::warn!(
+                        bundle_id = %bundle_id,
+                        builder = %entry.builder.name,
+                        error = %e,
+                        "Bundle resubmission failed"
+                    );
+                }
+            }
+        }
+    }
+
+    /// Health-check every enabled relay, recording the results in the shared monitor and
+    /// counting status changes in the metrics
     async fn health_check_relays(&self) -> Result<()> {
         tracing::debug!("Running relay health checks");
-        // TODO: Implement health check logic
+        for name in self.relays.relay_names() {
+            let Some(client) = self.relays.get_client(&name) else {
+                continue;
+            };
+            let outcome = client.health_check().await.map_err(|e| {
+                tracing::debug!(builder = %name, error = %e, "Relay health check failed");
+                e.to_string()
+            });
+
+            let transition = self.state.relay_health.write().await.update_health(&name, outcome);
+            if let Some((from, to)) = transition {
+                tracing::info!(builder = %name, from = ?from, to = ?to, "Relay health changed");
+                self.state.metrics.observe_relay_health_transition(&name, &from, &to);
+            }
+        }
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::{Clock, MockClock};
+    use crate::database::Database;
+    use crate::test_utils::mock_rpc_method;
+    use config::Config;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_bundle_not_resubmitted_before_interval_elapses() {
+        let mut config = Config::default();
+        config.targets.resubmit_interval_seconds = 12;
+        config.targets.resubmit_max = 3;
+        let builder = config.builders[0].clone();
+        let clock = Arc::new(MockClock::new(Utc::now()));
+        let database = Database::new_in_memory().await.unwrap();
+        let state = Arc::new(AppState::new(config, database).with_clock(clock.clone()));
+        let scheduler = Scheduler::new(state.clone()).await.unwrap();
+
+        let bundle_id = Uuid::new_v4();
+        state
+            .resubmissions
+            .record(bundle_id, &builder, vec!["0x01".to_string()], None, clock.now())
+            .await;
+
+        clock.advance(chrono::Duration::seconds(11));
+        assert!(scheduler.due_resubmissions().await.is_empty());
+
+        clock.advance(chrono::Duration::seconds(1));
+        let due = scheduler.due_resubmissions().await;
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].0, bundle_id);
+
+        // The attempt resets the interval
+        clock.advance(chrono::Duration::seconds(5));
+        assert!(scheduler.due_resubmissions().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_landed_or_failed_bundle_is_not_resubmitted() {
+        let mut config = Config::default();
+        config.targets.resubmit_interval_seconds = 12;
+        config.targets.resubmit_max = 3;
+        let builder = config.builders[0].clone();
+        let clock = Arc::new(MockClock::new(Utc::now()));
+        let database = Database::new_in_memory().await.unwrap();
+        let state = Arc::new(AppState::new(config, database).with_clock(clock.clone()));
+        let scheduler = Scheduler::new(state.clone()).await.unwrap();
+
+        let expires_at = clock.now() + chrono::Duration::hours(1);
+        for final_state in ["landed", "failed"] {
+            let bundle_id = Uuid::new_v4();
+            state.database.insert_bundle(&bundle_id.to_string(), "0xabc", "anonymous", clock.now(), expires_at).await.unwrap();
+            state
+                .resubmissions
+                .record(bundle_id, &builder, vec!["0x01".to_string()], None, clock.now())
+                .await;
+            state.set_bundle_state(&bundle_id.to_string(), final_state).await.unwrap();
+        }
+
+        clock.advance(chrono::Duration::seconds(12));
+        assert!(scheduler.due_resubmissions().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_resubmission_retargets_a_passed_block() {
+        let relay = wiremock::MockServer::start().await;
+        mock_rpc_method(&relay, "eth_sendBundle", json!("0xbundlehash")).await;
+        mock_rpc_method(&relay, "eth_blockNumber", json!("0x64")).await;
+
+        let mut config = Config::default();
+        config.network.rpc_url = Some(relay.uri());
+        config.builders[0].relay_url = relay.uri();
+        config.targets.resubmit_interval_seconds = 12;
+        config.targets.resubmit_max = 3;
+        let builder = config.builders[0].clone();
+        let clock = Arc::new(MockClock::new(Utc::now()));
+        let database = Database::new_in_memory().await.unwrap();
+        let state = Arc::new(AppState::new(config, database).with_clock(clock.clone()));
+        let scheduler = Scheduler::new(state.clone()).await.unwrap();
+
+        // Accepted for block 90; the chain head is now 100
+        let bundle_id = Uuid::new_v4();
+        state
+            .resubmissions
+            .record(bundle_id, &builder, vec!["0x01".to_string()], Some(90), clock.now())
+            .await;
+        clock.advance(chrono::Duration::seconds(12));
+        scheduler.resubmit_due_bundles().await;
+
+        let sent: Vec<serde_json::Value> = relay
+            .received_requests()
+            .await
+            .unwrap()
+            .iter()
+            .map(|request| serde_json::from_slice(&request.body).unwrap())
+            .filter(|body: &serde_json::Value| body["method"] == "eth_sendBundle")
+            .collect();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0]["params"][0]["blockNumber"], "0x65");
+
+        // Later attempts keep the new target, and cancellation knows about it
+        clock.advance(chrono::Duration::seconds(12));
+        assert_eq!(scheduler.due_resubmissions().await[0].1.target_block, Some(101));
+        let targets = state.database.relay_submission_targets(&bundle_id.to_string()).await.unwrap();
+        assert_eq!(targets, vec![(builder.name.clone(), vec![101])]);
+    }
+
+    #[tokio::test]
+    async fn test_stuck_sent_bundle_fails_after_timeout() {
+        let mut config = Config::default();
+        config.targets.stuck_bundle_timeout_seconds = 600;
+        let clock = Arc::new(MockClock::new(Utc::now()));
+        let database = Database::new_in_memory().await.unwrap();
+        let state = Arc::new(AppState::new(config, database).with_clock(clock.clone()));
+        let scheduler = Scheduler::new(state.clone()).await.unwrap();
+
+        let expires_at = clock.now() + chrono::Duration::hours(1);
+        state.database.insert_bundle("stuck", "0xabc", "anonymous", clock.now(), expires_at).await.unwrap();
+        state.database.update_bundle_state("stuck", "sent", clock.now()).await.unwrap();
+
+        clock.advance(chrono::Duration::seconds(599));
+        assert_eq!(scheduler.fail_stuck_bundles().await.unwrap(), 0);
+        assert_eq!(state.database.get_bundle("stuck").await.unwrap().unwrap().state, "sent");
+
+        clock.advance(chrono::Duration::seconds(2));
+        assert_eq!(scheduler.fail_stuck_bundles().await.unwrap(), 1);
+        assert_eq!(state.database.get_bundle("stuck").await.unwrap().unwrap().state, "failed");
+    }
+
+    /// State with `count` `sent` bundles accepted by the default builder, whose stats live at `stats_url`
+    async fn sent_bundles_state(
+        strategy: InclusionStrategy,
+        rpc_url: String,
+        stats_url: String,
+        count: usize,
+    ) -> Arc<AppState> {
+        let mut config = Config::default();
+        config.targets.inclusion_strategy = strategy;
+        config.network.rpc_url = Some(rpc_url);
+        config.builders[0].status_url = Some(stats_url);
+        let state = Arc::new(AppState::new(config, Database::new_in_memory().await.unwrap()));
+        insert_sent_bundles(&state, count).await;
+        state
+    }
+
+    /// State with one `sent` bundle accepted by the default builder, whose stats live at `stats_url`
+    async fn sent_bundle_state(strategy: InclusionStrategy, rpc_url: String, stats_url: String) -> Arc<AppState> {
+        sent_bundles_state(strategy, rpc_url, stats_url, 1).await
+    }
+
+    /// Record `count` bundles (`b1`, `b2`, ...) as sent to the default builder
+    async fn insert_sent_bundles(state: &AppState, count: usize) {
+        let now = Utc::now();
+        let relay_name = state.config.builders[0].name.clone();
+        for i in 1..=count {
+            let bundle_id = format!("b{}", i);
+            let tx1_hash = if i == 1 { "0xabc".to_string() } else { format!("0xabc{}", i) };
+            state
+                .database
+                .insert_bundle(&bundle_id, &tx1_hash, "anonymous", now, now + chrono::Duration::minutes(5))
+                .await
+                .unwrap();
+            state.database.update_bundle_state(&bundle_id, "sent", now).await.unwrap();
+            state.database.record_relay_submission(&bundle_id, &relay_name, "0xbundlehash", &[], now).await.unwrap();
+        }
+    }
+
+    /// Answers a JSON-RPC batch of `eth_getTransactionReceipt` calls with a receipt for each
+    struct BatchReceipts;
+
+    impl wiremock::Respond for BatchReceipts {
+        fn respond(&self, request: &wiremock::Request) -> wiremock::ResponseTemplate {
+            let calls: Vec<serde_json::Value> = serde_json::from_slice(&request.body).unwrap();
+            let responses: Vec<_> = calls
+                .iter()
+                .map(|call| json!({ "jsonrpc": "2.0", "id": call["id"], "result": { "transactionHash": call["params"][0] } }))
+                .collect();
+            wiremock::ResponseTemplate::new(200).set_body_json(responses)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_receipt_strategy_polls_rpc_only() {
+        let rpc = wiremock::MockServer::start().await;
+        mock_rpc_method(&rpc, "eth_getTransactionReceipt", json!({ "transactionHash": "0xabc", "blockNumber": "0x10" })).await;
+        let stats = wiremock::MockServer::start().await;
+        wiremock::Mock::given(wiremock::matchers::any())
+            .respond_with(wiremock::ResponseTemplate::new(500))
+            .expect(0)
+            .mount(&stats)
+            .await;
+
+        let state = sent_bundle_state(InclusionStrategy::Receipt, rpc.uri(), stats.uri()).await;
+        let scheduler = Scheduler::new(state.clone()).await.unwrap();
+        assert_eq!(scheduler.detect_inclusions().await.unwrap(), 1);
+        assert_eq!(state.database.get_bundle("b1").await.unwrap().unwrap().state, "landed");
+    }
+
+    #[tokio::test]
+    async fn test_relay_stats_strategy_queries_status_url_only() {
+        let rpc = wiremock::MockServer::start().await;
+        wiremock::Mock::given(wiremock::matchers::any())
+            .respond_with(wiremock::ResponseTemplate::new(500))
+            .expect(0)
+            .mount(&rpc)
+            .await;
+        let stats = wiremock::MockServer::start().await;
+        mock_rpc_method(&stats, "titan_getBundleStats", json!({ "status": "IncludedInBlock" })).await;
+
+        let state = sent_bundle_state(InclusionStrategy::RelayStats, rpc.uri(), stats.uri()).await;
+        let scheduler = Scheduler::new(state.clone()).await.unwrap();
+        assert_eq!(scheduler.detect_inclusions().await.unwrap(), 1);
+        assert_eq!(state.database.get_bundle("b1").await.unwrap().unwrap().state, "landed");
+    }
+
+    #[tokio::test]
+    async fn test_receipts_for_many_bundles_are_fetched_in_one_batch() {
+        let rpc = wiremock::MockServer::start().await;
+        wiremock::Mock::given(wiremock::matchers::method("POST"))
+            .respond_with(BatchReceipts)
+            .expect(1)
+            .mount(&rpc)
+            .await;
+
+        let state = sent_bundles_state(InclusionStrategy::Receipt, rpc.uri(), rpc.uri(), 20).await;
+        let scheduler = Scheduler::new(state.clone()).await.unwrap();
+        assert_eq!(scheduler.detect_inclusions().await.unwrap(), 20);
+        assert!(state.database.bundles_in_state("sent").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_receipts_fetched_individually_without_batch_support() {
+        // Only single calls are answered; the batch request gets a 404
+        let rpc = wiremock::MockServer::start().await;
+        mock_rpc_method(&rpc, "eth_getTransactionReceipt", json!({ "transactionHash": "0xabc", "blockNumber": "0x10" })).await;
+
+        let state = sent_bundles_state(InclusionStrategy::Receipt, rpc.uri(), rpc.uri(), 3).await;
+        let scheduler = Scheduler::new(state.clone()).await.unwrap();
+        assert_eq!(scheduler.detect_inclusions().await.unwrap(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_relay_stats_checks_run_concurrently_within_bound() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let in_flight = Arc::new(AtomicUsize::new(0));
+        let max_in_flight = Arc::new(AtomicUsize::new(0));
+        let (gate, _) = tokio::sync::watch::channel(false);
+        let gate = Arc::new(gate);
+        let (stats_url, _) = {
+            let in_flight = in_flight.clone();
+            let max_in_flight = max_in_flight.clone();
+            crate::test_utils::spawn_json_server(move |request| {
+                let in_flight = in_flight.clone();
+                let max_in_flight = max_in_flight.clone();
+                let gate = gate.clone();
+                async move {
+                    let now_in_flight = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                    max_in_flight.fetch_max(now_in_flight, Ordering::SeqCst);
+                    // Hold every response until two checks are in flight at once
+                    if now_in_flight >= 2 {
+                        gate.send_replace(true);
+                    }
+                    gate.subscribe().wait_for(|open| *open).await.unwrap();
+                    // Stop counting before the response can free a permit for the next check
+                    in_flight.fetch_sub(1, Ordering::SeqCst);
+                    json!({ "jsonrpc": "2.0", "id": request["id"], "result": { "status": "IncludedInBlock" } })
+                }
+            })
+            .await
+        };
+
+        let mut config = Config::default();
+        config.targets.inclusion_strategy = InclusionStrategy::RelayStats;
+        config.targets.inclusion_check_concurrency = 4;
+        config.builders[0].status_url = Some(stats_url);
+        let state = Arc::new(AppState::new(config, Database::new_in_memory().await.unwrap()));
+        insert_sent_bundles(&state, 12).await;
+        let scheduler = Scheduler::new(state).await.unwrap();
+
+        let landed = tokio::time::timeout(Duration::from_secs(10), scheduler.detect_inclusions())
+            .await
+            .expect("stats checks never ran concurrently")
+            .unwrap();
+        assert_eq!(landed, 12);
+        let max_in_flight = max_in_flight.load(Ordering::SeqCst);
+        assert!((2..=4).contains(&max_in_flight), "max in flight: {}", max_in_flight);
+    }
+
+    #[tokio::test]
+    async fn test_relay_health_transitions_are_counted() {
+        let relay = wiremock::MockServer::start().await;
+        mock_rpc_method(&relay, "eth_blockNumber", json!("0x64")).await;
+
+        let mut config = Config::default();
+        config.builders[0].relay_url = relay.uri();
+        let name = config.builders[0].name.clone();
+        let state = Arc::new(AppState::new(config, Database::new_in_memory().await.unwrap()));
+        let scheduler = Scheduler::new(state.clone()).await.unwrap();
+
+        scheduler.health_check_relays().await.unwrap();
+        scheduler.health_check_relays().await.unwrap();
+
+        relay.reset().await;
+        wiremock::Mock::given(wiremock::matchers::method("POST"))
+            .respond_with(wiremock::ResponseTemplate::new(503))
+            .mount(&relay)
+            .await;
+        scheduler.health_check_relays().await.unwrap();
+
+        let rendered = state.metrics.render("atomic_bundler");
+        assert!(rendered.contains(&format!(
+            "atomic_bundler_relay_health_transitions_total{{relay=\"{}\",direction=\"unknown_to_healthy\"}} 1",
+            name
+        )));
+        assert!(rendered.contains(&format!(
+            "atomic_bundler_relay_health_transitions_total{{relay=\"{}\",direction=\"healthy_to_degraded\"}} 1",
+            name
+        )));
+    }
+}
@@ -0,0 +1,204 @@
+//! Short-lived in-memory cache of bundle records served by the status endpoint
+
+use crate::database::BundleRecord;
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Mutex;
+
+/// A cached record with when it was loaded and last served
+#[derive(Debug)]
+struct CacheEntry {
+    record: BundleRecord,
+    loaded_at: DateTime<Utc>,
+    last_used_at: DateTime<Utc>,
+}
+
+/// Cached records plus a counter bumped by every invalidation
+#[derive(Debug, Default)]
+struct CacheState {
+    entries: HashMap<String, CacheEntry>,
+    invalidations: u64,
+}
+
+/// Bundle records cached for `ttl`, evicting the least recently used beyond `capacity`.
+///
+/// Writers must [`invalidate`](Self::invalidate) a bundle when its state changes.
+#[derive(Debug)]
+pub struct BundleStatusCache {
+    ttl: chrono::Duration,
+    capacity: usize,
+    state: Mutex<CacheState>,
+}
+
+impl BundleStatusCache {
+    /// Create a cache; a zero `ttl_ms` or `capacity` disables caching
+    pub fn new(ttl_ms: u64, capacity: usize) -> Self {
+        Self {
+            ttl: chrono::Duration::milliseconds(ttl_ms as i64),
+            capacity,
+            state: Mutex::new(CacheState::default()),
+        }
+    }
+
+    /// The cached record for `id` if still fresh, otherwise the result of `load` (which is cached)
+    pub async fn get_or_load<F, Fut>(&self, id: &str, now: DateTime<Utc>, load: F) -> Result<Option<BundleRecord>>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<Option<BundleRecord>>>,
+    {
+        if let Some(record) = self.get(id, now) {
+            return Ok(Some(record));
+        }
+
+        // A write landing while we load must not be shadowed by the stale record
+        let invalidations = self.state.lock().unwrap().invalidations;
+        let record = load().await?;
+        if let Some(record) = &record {
+            self.insert(record.clone(), now, invalidations);
+        }
+        Ok(record)
+    }
+
+    /// Drop the cached record for `id`
+    pub fn invalidate(&self, id: &str) {
+        let mut state = self.state.lock().unwrap();
+        state.invalidations += 1;
+        state.entries.remove(id);
+    }
+
+    /// Drop every cached record
+    pub fn clear(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.invalidations += 1;
+        state.entries.clear();
+    }
+
+    fn get(&self, id: &str, now: DateTime<Utc>) -> Option<BundleRecord> {
+        let mut state = self.state.lock().unwrap();
+        let entries = &mut state.entries;
+        let entry = entries.get_mut(id)?;
+        if now - entry.loaded_at >= self.ttl {
+            entries.remove(id);
+            return None;
+        }
+        entry.last_used_at = now;
+        Some(entry.record.clone())
+    }
+
+    fn insert(&self, record: BundleRecord, now: DateTime<Utc>, invalidations: u64) {
+        if self.capacity == 0 || self.ttl <= chrono::Duration::zero() {
+            return;
+        }
+
+        let mut state = self.state.lock().unwrap();
+        if state.invalidations != invalidations {
+            return;
+        }
+        let entries = &mut state.entries;
+        if entries.len() >= self.capacity && !entries.contains_key(&record.id) {
+            let least_recent = entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used_at)
+                .map(|(id, _)| id.clone());
+            if let Some(id) = least_recent {
+                entries.remove(&id);
+            }
+        }
+        entries.insert(record.id.clone(), CacheEntry { record, loaded_at: now, last_used_at: now });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::Database;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn record(id: &str, state: &str) -> BundleRecord {
+        let now = Utc::now();
+        BundleRecord {
+            id: id.to_string(),
+            tx1_hash: "0xabc".to_string(),
+            state: state.to_string(),
+            created_at: now,
+            updated_at: now,
+            expires_at: None,
+            submitter: "anonymous".to_string(),
+            partially_submitted: false,
+        }
+    }
+
+    /// Database wrapper counting how often bundles are looked up
+    struct CountingDatabase {
+        inner: Database,
+        lookups: AtomicUsize,
+    }
+
+    impl CountingDatabase {
+        async fn get_bundle(&self, id: &str) -> Result<Option<BundleRecord>> {
+            self.lookups.fetch_add(1, Ordering::SeqCst);
+            self.inner.get_bundle(id).await
+        }
+
+        fn lookups(&self) -> usize {
+            self.lookups.load(Ordering::SeqCst)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_lookup_within_ttl_skips_database() {
+        let now = Utc::now();
+        let database = CountingDatabase { inner: Database::new_in_memory().await.unwrap(), lookups: AtomicUsize::new(0) };
+        database
+            .inner
+            .insert_bundle("b1", "0xabc", "anonymous", now, now + chrono::Duration::minutes(5))
+            .await
+            .unwrap();
+        let cache = BundleStatusCache::new(1_000, 16);
+
+        cache.get_or_load("b1", now, || database.get_bundle("b1")).await.unwrap();
+        let cached = cache
+            .get_or_load("b1", now + chrono::Duration::milliseconds(500), || database.get_bundle("b1"))
+            .await
+            .unwrap();
+        assert_eq!(cached.unwrap().state, "queued");
+        assert_eq!(database.lookups(), 1);
+
+        // A state change is picked up once the writer invalidates the entry
+        database.inner.update_bundle_state("b1", "sent", now).await.unwrap();
+        cache.invalidate("b1");
+        let reloaded = cache
+            .get_or_load("b1", now + chrono::Duration::milliseconds(600), || database.get_bundle("b1"))
+            .await
+            .unwrap();
+        assert_eq!(reloaded.unwrap().state, "sent");
+        assert_eq!(database.lookups(), 2);
+
+        // Past the TTL the database is consulted again
+        cache
+            .get_or_load("b1", now + chrono::Duration::seconds(2), || database.get_bundle("b1"))
+            .await
+            .unwrap();
+        assert_eq!(database.lookups(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_least_recently_used_entry_is_evicted() {
+        let cache = BundleStatusCache::new(60_000, 2);
+        let now = Utc::now();
+        let load = |id: &'static str| move || async move { Ok(Some(record(id, "sent"))) };
+
+        cache.get_or_load("a", now, load("a")).await.unwrap();
+        cache.get_or_load("b", now + chrono::Duration::seconds(1), load("b")).await.unwrap();
+        // Touch "a" so "b" becomes the least recently used
+        cache.get_or_load("a", now + chrono::Duration::seconds(2), load("a")).await.unwrap();
+        cache.get_or_load("c", now + chrono::Duration::seconds(3), load("c")).await.unwrap();
+
+        let later = now + chrono::Duration::seconds(4);
+        assert!(cache.get("a", later).is_some());
+        assert!(cache.get("b", later).is_none());
+        assert!(cache.get("c", later).is_some());
+    }
+}
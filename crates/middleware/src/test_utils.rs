@@ -0,0 +1,160 @@
+//! Shared test helpers
+
+use alloy::primitives::Address;
+use serde_json::{json, Value};
+use std::future::Future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use wiremock::{
+    matchers::{body_partial_json, method},
+    Mock, MockServer, Request, Respond, ResponseTemplate,
+};
+
+/// JSON-RPC responder that echoes the request id alongside a fixed result
+pub struct RpcResult(pub Value);
+
+impl Respond for RpcResult {
+    fn respond(&self, request: &Request) -> ResponseTemplate {
+        let body: Value = serde_json::from_slice(&request.body).unwrap_or_default();
+        ResponseTemplate::new(200).set_body_json(json!({
+            "jsonrpc": "2.0",
+            "id": body.get("id").cloned().unwrap_or(json!(0)),
+            "result": self.0
+        }))
+    }
+}
+
+/// JSON-RPC responder that echoes the request id alongside a fixed error message
+pub struct RpcError(pub &'static str);
+
+impl Respond for RpcError {
+    fn respond(&self, request: &Request) -> ResponseTemplate {
+        let body: Value = serde_json::from_slice(&request.body).unwrap_or_default();
+        ResponseTemplate::new(200).set_body_json(json!({
+            "jsonrpc": "2.0",
+            "id": body.get("id").cloned().unwrap_or(json!(0)),
+            "error": { "code": -32000, "message": self.0 }
+        }))
+    }
+}
+
+/// JSON of block 100 with the given timestamp and a 1 gwei base fee, as `eth_getBlockByNumber` returns it
+pub fn block_json(timestamp: u64) -> Value {
+    json!({
+        "hash": format!("0x{}", "11".repeat(32)),
+        "parentHash": format!("0x{}", "22".repeat(32)),
+        "sha3Uncles": format!("0x{}", "33".repeat(32)),
+        "miner": Address::ZERO,
+        "stateRoot": format!("0x{}", "44".repeat(32)),
+        "transactionsRoot": format!("0x{}", "55".repeat(32)),
+        "receiptsRoot": format!("0x{}", "66".repeat(32)),
+        "logsBloom": format!("0x{}", "00".repeat(256)),
+        "difficulty": "0x0",
+        "number": "0x64",
+        "gasLimit": "0x1c9c380",
+        "gasUsed": "0x0",
+        "timestamp": format!("{:#x}", timestamp),
+        "extraData": "0x",
+        "mixHash": format!("0x{}", "77".repeat(32)),
+        "nonce": "0x0000000000000000",
+        "baseFeePerGas": "0x3b9aca00",
+        "uncles": [],
+        "transactions": []
+    })
+}
+
+/// Log sink shared between a test subscriber and the test body
+#[derive(Clone, Default)]
+pub struct LogBuffer(pub Arc<Mutex<Vec<u8>>>);
+
+impl LogBuffer {
+    /// Everything logged so far
+    pub fn contents(&self) -> String {
+        String::from_utf8(self.0.lock().unwrap().clone()).unwrap()
+    }
+}
+
+impl std::io::Write for LogBuffer {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Mount a JSON-RPC method on the mock server that always returns `result`
+pub async fn mock_rpc_method(server: &MockServer, rpc_method: &str, result: Value) {
+    Mock::given(method("POST"))
+        .and(body_partial_json(json!({ "method": rpc_method })))
+        .respond_with(RpcResult(result))
+        .mount(server)
+        .await;
+}
+
+/// Keep-alive HTTP/1.1 server answering each JSON request body with `respond(body)`.
+///
+/// Unlike a wiremock responder, `respond` is async, so a test can hold responses back until
+/// some condition holds. Returns the server URL and the number of TCP connections accepted.
+pub async fn spawn_json_server<F, Fut>(respond: F) -> (String, Arc<AtomicUsize>)
+where
+    F: Fn(Value) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Value> + Send + 'static,
+{
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let connections = Arc::new(AtomicUsize::new(0));
+    let accepted = connections.clone();
+    let respond = Arc::new(respond);
+    tokio::spawn(async move {
+        while let Ok((socket, _)) = listener.accept().await {
+            accepted.fetch_add(1, Ordering::SeqCst);
+            tokio::spawn(serve_json_connection(socket, respond.clone()));
+        }
+    });
+    (url, connections)
+}
+
+async fn serve_json_connection<F, Fut>(mut socket: tokio::net::TcpStream, respond: Arc<F>)
+where
+    F: Fn(Value) -> Fut,
+    Fut: Future<Output = Value>,
+{
+    let mut buffer = Vec::new();
+    let mut chunk = [0u8; 4096];
+    loop {
+        let Ok(read) = socket.read(&mut chunk).await else { return };
+        if read == 0 {
+            return;
+        }
+        buffer.extend_from_slice(&chunk[..read]);
+        // Answer every complete request received so far
+        while let Some(header_end) = buffer.windows(4).position(|window| window == b"\r\n\r\n") {
+            let headers = String::from_utf8_lossy(&buffer[..header_end]).to_ascii_lowercase();
+            let content_length = headers
+                .lines()
+                .find_map(|line| line.strip_prefix("content-length:"))
+                .and_then(|value| value.trim().parse::<usize>().ok())
+                .unwrap_or(0);
+            let request_end = header_end + 4 + content_length;
+            if buffer.len() < request_end {
+                break;
+            }
+            let request: Value = serde_json::from_slice(&buffer[header_end + 4..request_end]).unwrap_or_default();
+            buffer.drain(..request_end);
+
+            let body = respond(request).await.to_string();
+            let response = format!(
+                "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            if socket.write_all(response.as_bytes()).await.is_err() {
+                return;
+            }
+        }
+    }
+}
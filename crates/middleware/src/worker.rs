@@ -0,0 +1,89 @@
+//! Bounded submission queue and the worker pool that drains it
+
+use crate::app::AppState;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{mpsc, Mutex};
+use tokio::task::JoinHandle;
+use types::BundleRequest;
+use uuid::Uuid;
+
+/// A bundle waiting to be forged and submitted
+#[derive(Debug)]
+pub struct SubmissionJob {
+    pub bundle_id: Uuid,
+    pub request: BundleRequest,
+}
+
+/// Bounded queue feeding the background submission workers
+#[derive(Debug, Clone)]
+pub struct SubmissionQueue {
+    sender: mpsc::Sender<SubmissionJob>,
+    receiver: Arc<Mutex<mpsc::Receiver<SubmissionJob>>>,
+}
+
+impl SubmissionQueue {
+    /// Create a queue holding at most `capacity` pending jobs
+    pub fn new(capacity: usize) -> Self {
+        let (sender, receiver) = mpsc::channel(capacity.max(1));
+        Self {
+            sender,
+            receiver: Arc::new(Mutex::new(receiver)),
+        }
+    }
+
+    /// Enqueue a job without waiting; hands the job back if the queue is full or closed
+    pub fn try_enqueue(&self, job: SubmissionJob) -> Result<(), SubmissionJob> {
+        self.sender.try_send(job).map_err(|e| e.into_inner())
+    }
+
+    /// Enqueue a job once `delay` has passed, waiting for room rather than dropping it when full
+    pub fn requeue_after(&self, job: SubmissionJob, delay: Duration) {
+        let sender = self.sender.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(delay).await;
+            let bundle_id = job.bundle_id;
+            if sender.send(job).await.is_err() {
+                tracing::warn!(bundle_id = %bundle_id, "Submission queue closed; dropping requeued bundle");
+            }
+        });
+    }
+
+    /// Spawn `workers` tasks that forge and submit queued bundles
+    pub fn spawn_workers(&self, state: Arc<AppState>, workers: usize) -> Vec<JoinHandle<()>> {
+        (0..workers.max(1))
+            .map(|worker_id| {
+                let receiver = self.receiver.clone();
+                let state = state.clone();
+                tokio::spawn(async move {
+                    loop {
+                        // Hold the lock only while waiting for the next job
+                        let job = receiver.lock().await.recv().await;
+                        let Some(job) = job else {
+                            break;
+                        };
+                        if is_cancelled(&state, job.bundle_id).await {
+                            tracing::info!(worker = worker_id, bundle_id = %job.bundle_id, "Skipping cancelled bundle");
+                            continue;
+                        }
+                        tracing::debug!(worker = worker_id, bundle_id = %job.bundle_id, "Processing queued bundle");
+                        let _ = crate::api::handlers::process_and_record_bundle(
+                            &state,
+                            job.bundle_id,
+                            job.request,
+                        )
+                        .await;
+                    }
+                })
+            })
+            .collect()
+    }
+}
+
+/// Whether a queued bundle was failed (e.g. cancelled by an admin) before a worker picked it up
+async fn is_cancelled(state: &AppState, bundle_id: Uuid) -> bool {
+    matches!(
+        state.database.get_bundle(&bundle_id.to_string()).await,
+        Ok(Some(record)) if record.state == "failed"
+    )
+}
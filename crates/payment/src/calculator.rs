@@ -1,7 +1,7 @@
 //! Payment calculation engine
 
 use alloy::primitives::U256;
-use types::{PaymentFormula, PaymentParams, PaymentResult, Result};
+use types::{PaymentBreakdown, PaymentFormula, PaymentParams, PaymentResult, Result};
 
 /// Payment calculator that implements various payment formulas
 #[derive(Debug, Clone)]
@@ -15,26 +15,59 @@ impl PaymentCalculator {
 
     /// Calculate payment amount based on the given parameters
     pub fn calculate_payment(&self, params: &PaymentParams) -> Result<PaymentResult> {
-        let amount_wei = match params.formula {
-            PaymentFormula::Flat => self.calculate_flat(&params)?,
-            PaymentFormula::Gas => self.calculate_gas_based(&params)?,
-            PaymentFormula::Basefee => self.calculate_basefee_based(&params)?,
+        let breakdown = self.calculate_payment_detailed(params)?;
+
+        Ok(PaymentResult::new(
+            breakdown.amount_wei,
+            breakdown.formula,
+            params.gas_used,
+            Some(params.base_fee_per_gas),
+            breakdown.was_capped,
+        ))
+    }
+
+    /// Calculate payment and report the components it was built from
+    pub fn calculate_payment_detailed(&self, params: &PaymentParams) -> Result<PaymentBreakdown> {
+        let pre_cap_amount = match params.formula {
+            PaymentFormula::Flat => self.calculate_flat(params)?,
+            PaymentFormula::Gas => self.calculate_gas_based(params)?,
+            PaymentFormula::Basefee => self.calculate_basefee_based(params)?,
+            PaymentFormula::Percentage => self.calculate_percentage_based(params)?,
+        };
+
+        let effective_gas_price = match params.formula {
+            PaymentFormula::Basefee => Some(
+                params
+                    .base_fee_per_gas
+                    .checked_add(params.max_priority_fee_per_gas)
+                    .ok_or_else(|| types::PaymentError::CalculationOverflow)?,
+            ),
+            PaymentFormula::Percentage => Some(params.base_fee_per_gas),
+            PaymentFormula::Flat | PaymentFormula::Gas => None,
         };
 
-        let was_capped = amount_wei > params.max_amount;
-        let final_amount = if was_capped {
+        // Percentage is the only formula that doesn't add k2
+        let base_component = match params.formula {
+            PaymentFormula::Percentage => U256::ZERO,
+            PaymentFormula::Flat | PaymentFormula::Gas | PaymentFormula::Basefee => params.k2,
+        };
+
+        let was_capped = pre_cap_amount > params.max_amount;
+        let amount_wei = if was_capped {
             params.max_amount
         } else {
-            amount_wei
+            pre_cap_amount
         };
 
-        Ok(PaymentResult::new(
-            final_amount,
-            params.formula.clone(),
-            params.gas_used,
-            Some(params.base_fee_per_gas),
+        Ok(PaymentBreakdown {
+            formula: params.formula.clone(),
+            gas_component: pre_cap_amount - base_component,
+            base_component,
+            effective_gas_price,
+            pre_cap_amount,
+            amount_wei,
             was_capped,
-        ))
+        })
     }
 
     /// Calculate flat payment: payment = k2
@@ -79,6 +112,20 @@ impl PaymentCalculator {
         Ok(total)
     }
 
+    /// Calculate percentage-of-gas-cost payment: payment = k1 * gas_used * base_fee
+    fn calculate_percentage_based(&self, params: &PaymentParams) -> Result<U256> {
+        let gas_cost = U256::from(params.gas_used)
+            .checked_mul(params.base_fee_per_gas)
+            .ok_or_else(|| types::PaymentError::CalculationOverflow)?;
+
+        let payment = gas_cost
+            .checked_mul(U256::from((params.k1 * 1e18) as u64))
+            .and_then(|v| v.checked_div(U256::from(1e18 as u64)))
+            .ok_or_else(|| types::PaymentError::CalculationOverflow)?;
+
+        Ok(payment)
+    }
+
     /// Validate payment parameters
     pub fn validate_params(&self, params: &PaymentParams) -> Result<()> {
         if params.gas_used == 0 {
@@ -95,6 +142,13 @@ impl PaymentCalculator {
             .into());
         }
 
+        if params.formula == PaymentFormula::Percentage && params.k1 > 1.0 {
+            tracing::warn!(
+                k1 = params.k1,
+                "Percentage payment with k1 above 1.0 pays more than the transaction's gas cost"
+            );
+        }
+
         if params.max_amount == U256::ZERO {
             return Err(types::PaymentError::InvalidParameters(
                 "Maximum amount cannot be zero".to_string(),
@@ -135,6 +189,25 @@ mod tests {
         assert!(!result.was_capped);
     }
 
+    #[test]
+    fn test_flat_payment_follows_configured_k2() {
+        let calculator = PaymentCalculator::new();
+        let params = PaymentParams {
+            gas_used: 21000,
+            base_fee_per_gas: U256::from(20_000_000_000u64),
+            max_priority_fee_per_gas: U256::from(1_000_000_000u64),
+            formula: PaymentFormula::Flat,
+            k1: 1.0,
+            k2: U256::from(300_000_000_000_000u64), // 0.0003 ETH
+            max_amount: U256::from(1_000_000_000_000_000u64),
+        };
+
+        // Not the 0.0002 ETH default: flat pays exactly the configured k2
+        let result = calculator.calculate_payment(&params).unwrap();
+        assert_eq!(result.amount_wei, U256::from(300_000_000_000_000u64));
+        assert!(!result.was_capped);
+    }
+
     #[test]
     fn test_gas_based_payment_calculation() {
         let calculator = PaymentCalculator::new();
@@ -172,6 +245,85 @@ mod tests {
         assert!(result.was_capped);
     }
 
+    #[test]
+    fn test_detailed_breakdown_components_sum_to_amount() {
+        let calculator = PaymentCalculator::new();
+        let base_fee = U256::from(20_000_000_000u64);
+        let tip = U256::from(1_000_000_000u64);
+        let k2 = U256::from(100_000_000_000_000u64);
+
+        for (formula, expected_gas_component) in [
+            (PaymentFormula::Gas, U256::from(42_000u64)),
+            (PaymentFormula::Basefee, U256::from(42_000u64) * (base_fee + tip)),
+        ] {
+            let params = PaymentParams {
+                gas_used: 21000,
+                base_fee_per_gas: base_fee,
+                max_priority_fee_per_gas: tip,
+                formula: formula.clone(),
+                k1: 2.0,
+                k2,
+                max_amount: U256::from(1_000_000_000_000_000u64),
+            };
+
+            let breakdown = calculator.calculate_payment_detailed(&params).unwrap();
+            assert_eq!(breakdown.gas_component, expected_gas_component);
+            assert_eq!(breakdown.base_component, k2);
+            assert_eq!(breakdown.pre_cap_amount, breakdown.gas_component + breakdown.base_component);
+            assert_eq!(breakdown.amount_wei, breakdown.pre_cap_amount);
+            assert!(!breakdown.was_capped);
+            assert_eq!(breakdown.amount_wei, calculator.calculate_payment(&params).unwrap().amount_wei);
+            assert_eq!(
+                breakdown.effective_gas_price,
+                (formula == PaymentFormula::Basefee).then_some(base_fee + tip)
+            );
+        }
+    }
+
+    #[test]
+    fn test_percentage_payment_calculation() {
+        let calculator = PaymentCalculator::new();
+        let base_fee = U256::from(20_000_000_000u64); // 20 gwei
+        let gas_cost = U256::from(21000u64) * base_fee;
+
+        for (k1, expected) in [(0.0, U256::ZERO), (0.1, gas_cost / U256::from(10u64))] {
+            let params = PaymentParams {
+                gas_used: 21000,
+                base_fee_per_gas: base_fee,
+                max_priority_fee_per_gas: U256::from(1_000_000_000u64),
+                formula: PaymentFormula::Percentage,
+                k1,
+                k2: U256::from(100_000_000_000_000u64), // ignored by this formula
+                max_amount: U256::from(1_000_000_000_000_000u64),
+            };
+
+            let breakdown = calculator.calculate_payment_detailed(&params).unwrap();
+            assert_eq!(breakdown.amount_wei, expected);
+            assert_eq!(breakdown.gas_component, expected);
+            assert_eq!(breakdown.base_component, U256::ZERO);
+            assert_eq!(breakdown.effective_gas_price, Some(base_fee));
+            assert!(!breakdown.was_capped);
+        }
+    }
+
+    #[test]
+    fn test_percentage_payment_is_capped() {
+        let calculator = PaymentCalculator::new();
+        let params = PaymentParams {
+            gas_used: 1_000_000,
+            base_fee_per_gas: U256::from(100_000_000_000u64), // 100 gwei
+            max_priority_fee_per_gas: U256::from(1_000_000_000u64),
+            formula: PaymentFormula::Percentage,
+            k1: 0.5, // 0.05 ETH before the cap
+            k2: U256::ZERO,
+            max_amount: U256::from(1_000_000_000_000_000u64), // 0.001 ETH
+        };
+
+        let result = calculator.calculate_payment(&params).unwrap();
+        assert_eq!(result.amount_wei, params.max_amount);
+        assert!(result.was_capped);
+    }
+
     #[test]
     fn test_invalid_parameters() {
         let calculator = PaymentCalculator::new();
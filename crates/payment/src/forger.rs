@@ -1,13 +1,26 @@
 //! Payment transaction forging
 
-use alloy::consensus::{Signed, TxEip1559, TxEnvelope};
+use alloy::consensus::{SignableTransaction, Signed, TxEip1559, TxEnvelope, TxLegacy};
 use alloy::eips::eip2718::Encodable2718;
-use alloy::network::TxSignerSync;
-use alloy::primitives::{Address, Bytes, TxKind, U256, keccak256};
+use alloy::network::TxSigner;
+use alloy::primitives::{Address, Bytes, Signature, TxKind, U256};
 use alloy::signers::local::PrivateKeySigner;
 use std::str::FromStr;
+use std::time::Duration;
 use types::{PaymentTransaction, Result};
 
+/// Maximum number of signing attempts before giving up on a transient signer error
+const MAX_SIGNING_ATTEMPTS: u32 = 3;
+
+/// Delay between signing attempts, multiplied by the attempt number
+const SIGNING_RETRY_DELAY_MS: u64 = 50;
+
+/// Largest token `decimals` whose unit (10^decimals base units) fits in a U256
+const MAX_TOKEN_DECIMALS: u8 = 77;
+
+/// Selector of ERC-20 `transfer(address,uint256)`
+const ERC20_TRANSFER_SELECTOR: [u8; 4] = [0xa9, 0x05, 0x9c, 0xbb];
+
 /// Transaction forger for creating payment transactions
 #[derive(Debug, Clone)]
 pub struct PaymentTransactionForger;
@@ -49,8 +62,42 @@ impl PaymentTransactionForger {
         gas_limit: u64,
         signer_key_hex: &str,
     ) -> Result<(String, String)> {
+        let signer = PrivateKeySigner::from_str(signer_key_hex)
+            .map_err(|e| types::AtomicBundlerError::Internal(format!("invalid signer key: {}", e)))?;
+
+        self.forge_flat_transfer_hex_with_signer(
+            to,
+            amount_wei,
+            chain_id,
+            nonce,
+            max_fee_per_gas,
+            max_priority_fee_per_gas,
+            gas_limit,
+            &signer,
+        )
+        .await
+    }
+
+    /// Forge an EIP-1559 ETH transfer signed by `signer` and return raw signed tx hex and hash.
+    ///
+    /// Transient signer failures (e.g. a remote signer timing out) are retried up to
+    /// `MAX_SIGNING_ATTEMPTS` times; other signing errors fail immediately.
+    pub async fn forge_flat_transfer_hex_with_signer<S>(
+        &self,
+        to: Address,
+        amount_wei: U256,
+        chain_id: u64,
+        nonce: u64,
+        max_fee_per_gas: u128,
+        max_priority_fee_per_gas: u128,
+        gas_limit: u64,
+        signer: &S,
+    ) -> Result<(String, String)>
+    where
+        S: TxSigner<Signature> + Send + Sync,
+    {
         // Build an EIP-1559 transaction envelope
-        let mut tx = TxEip1559 {
+        let tx = TxEip1559 {
             chain_id,
             nonce,
             max_fee_per_gas,
@@ -62,25 +109,134 @@ impl PaymentTransactionForger {
             access_list: Default::default(),
         };
 
-        // Sign
+        sign_and_encode(signer, tx).await
+    }
+
+    /// Forge and sign a legacy (type-0) ETH transfer and return raw signed tx hex and hash.
+    ///
+    /// For chains without EIP-1559; `chain_id` is included in the signature per EIP-155.
+    pub async fn forge_legacy_transfer_hex(
+        &self,
+        to: Address,
+        amount_wei: U256,
+        chain_id: u64,
+        nonce: u64,
+        gas_price: u128,
+        gas_limit: u64,
+        signer_key_hex: &str,
+    ) -> Result<(String, String)> {
+        let signer = PrivateKeySigner::from_str(signer_key_hex)
+            .map_err(|e| types::AtomicBundlerError::Internal(format!("invalid signer key: {}", e)))?;
+
+        let tx = TxLegacy {
+            chain_id: Some(chain_id),
+            nonce,
+            gas_price,
+            gas_limit,
+            to: TxKind::Call(to),
+            value: amount_wei,
+            input: Bytes::new(),
+        };
+
+        sign_and_encode(&signer, tx).await
+    }
+
+    /// Forge and sign an EIP-1559 call to `token`'s ERC-20 `transfer(to, amount)` and return
+    /// raw signed tx hex and hash.
+    ///
+    /// `amount` is in the token's base units and `decimals` is the token's `decimals()`; the
+    /// amount is sent as is (no price conversion from wei or rescaling is done) and `decimals`
+    /// only validates the token and renders the amount in whole tokens for the logs. A zero
+    /// amount, or more than 77 decimals, is rejected rather than signed.
+    pub async fn forge_permit_transfer_hex(
+        &self,
+        token: Address,
+        to: Address,
+        amount: U256,
+        decimals: u8,
+        chain_id: u64,
+        nonce: u64,
+        max_fee_per_gas: u128,
+        max_priority_fee_per_gas: u128,
+        gas_limit: u64,
+        signer_key_hex: &str,
+    ) -> Result<(String, String)> {
         let signer = PrivateKeySigner::from_str(signer_key_hex)
             .map_err(|e| types::AtomicBundlerError::Internal(format!("invalid signer key: {}", e)))?;
 
-        let signature = signer
-            .sign_transaction_sync(&mut tx)
-            .map_err(|e| types::AtomicBundlerError::Internal(format!("signing failed: {}", e)))?;
+        if amount.is_zero() {
+            return Err(types::PaymentError::InvalidParameters("token payment amount is zero".to_string()).into());
+        }
+        if decimals > MAX_TOKEN_DECIMALS {
+            return Err(types::PaymentError::InvalidParameters(format!(
+                "token decimals {} exceed {}",
+                decimals, MAX_TOKEN_DECIMALS
+            ))
+            .into());
+        }
+        tracing::debug!(
+            token = %token,
+            to = %to,
+            amount = %format_token_amount(amount, decimals),
+            "Forging ERC-20 payment"
+        );
+
+        let tx = TxEip1559 {
+            chain_id,
+            nonce,
+            max_fee_per_gas,
+            max_priority_fee_per_gas,
+            gas_limit,
+            to: TxKind::Call(token),
+            value: U256::ZERO,
+            input: erc20_transfer_calldata(to, amount),
+            access_list: Default::default(),
+        };
 
-        // Calculate the transaction hash for the signed transaction
-        let tx_hash = keccak256(alloy::rlp::encode(&tx));
-        let signed = Signed::new_unchecked(tx, signature, tx_hash);
-        let envelope: TxEnvelope = signed.into();
+        sign_and_encode(&signer, tx).await
+    }
+}
 
-        let encoded = envelope.encoded_2718();
-        let tx_hex = format!("0x{}", alloy::hex::encode(encoded));
-        let tx_hash_hex = format!("0x{}", alloy::hex::encode(tx_hash));
-        
-        Ok((tx_hex, tx_hash_hex))
+/// `amount` base units of a token with `decimals` decimals, in whole tokens (e.g. `0.5`)
+fn format_token_amount(amount: U256, decimals: u8) -> String {
+    let unit = U256::from(10u64).pow(U256::from(decimals));
+    let (whole, fraction) = amount.div_rem(unit);
+    if fraction.is_zero() {
+        return whole.to_string();
     }
+    let fraction = format!("{:0>width$}", fraction.to_string(), width = decimals as usize);
+    format!("{}.{}", whole, fraction.trim_end_matches('0'))
+}
+
+/// ABI-encoded call data for ERC-20 `transfer(to, amount)`
+fn erc20_transfer_calldata(to: Address, amount: U256) -> Bytes {
+    let mut data = Vec::with_capacity(4 + 32 + 32);
+    data.extend_from_slice(&ERC20_TRANSFER_SELECTOR);
+    data.extend_from_slice(&[0u8; 12]);
+    data.extend_from_slice(to.as_slice());
+    data.extend_from_slice(&amount.to_be_bytes::<32>());
+    data.into()
+}
+
+/// Sign `tx` and return its raw 2718 encoding and hash as hex
+async fn sign_and_encode<S, T>(signer: &S, mut tx: T) -> Result<(String, String)>
+where
+    S: TxSigner<Signature> + Send + Sync,
+    T: SignableTransaction<Signature> + Send,
+    Signed<T>: Into<TxEnvelope>,
+{
+    let signature = sign_with_retry(signer, &mut tx).await?;
+
+    // The on-chain hash covers the type byte, the signature and (for legacy) EIP-155 fields
+    let signed = tx.into_signed(signature);
+    let tx_hash = *signed.hash();
+    let envelope: TxEnvelope = signed.into();
+
+    let encoded = envelope.encoded_2718();
+    let tx_hex = format!("0x{}", alloy::hex::encode(encoded));
+    let tx_hash_hex = format!("0x{}", alloy::hex::encode(tx_hash));
+
+    Ok((tx_hex, tx_hash_hex))
 }
 
 impl Default for PaymentTransactionForger {
@@ -88,3 +244,258 @@ impl Default for PaymentTransactionForger {
         Self::new()
     }
 }
+
+/// Sign `tx`, retrying signer errors that may succeed on a later attempt
+async fn sign_with_retry<S, T>(signer: &S, tx: &mut T) -> Result<Signature>
+where
+    S: TxSigner<Signature> + Send + Sync,
+    T: SignableTransaction<Signature> + Send,
+{
+    let mut attempt = 1;
+    loop {
+        match signer.sign_transaction(tx).await {
+            Ok(signature) => return Ok(signature),
+            Err(e) if is_transient_signing_error(&e) && attempt < MAX_SIGNING_ATTEMPTS => {
+                tracing::warn!(attempt, error = %e, "Transient signing failure, retrying");
+                tokio::time::sleep(Duration::from_millis(SIGNING_RETRY_DELAY_MS * attempt as u64)).await;
+                attempt += 1;
+            }
+            Err(e) => {
+                return Err(types::AtomicBundlerError::Internal(format!("signing failed: {}", e)));
+            }
+        }
+    }
+}
+
+/// Remote signers report I/O and transport failures as `Error::Other`; everything else
+/// (chain id mismatches, ECDSA failures) is deterministic and not worth retrying.
+fn is_transient_signing_error(error: &alloy::signers::Error) -> bool {
+    matches!(error, alloy::signers::Error::Other(_))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::consensus::{Transaction, TxType};
+    use alloy::eips::eip2718::Decodable2718;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    const TEST_SIGNER_KEY: &str = "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+
+    /// Signer that fails with a transient error a fixed number of times before delegating
+    struct FlakySigner {
+        inner: PrivateKeySigner,
+        failures_remaining: AtomicU32,
+        calls: AtomicU32,
+    }
+
+    #[async_trait::async_trait]
+    impl TxSigner<Signature> for FlakySigner {
+        fn address(&self) -> Address {
+            TxSigner::<Signature>::address(&self.inner)
+        }
+
+        async fn sign_transaction(
+            &self,
+            tx: &mut dyn SignableTransaction<Signature>,
+        ) -> alloy::signers::Result<Signature> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            if self.failures_remaining.load(Ordering::SeqCst) > 0 {
+                self.failures_remaining.fetch_sub(1, Ordering::SeqCst);
+                return Err(alloy::signers::Error::other("remote signer unavailable"));
+            }
+            TxSigner::<Signature>::sign_transaction(&self.inner, tx).await
+        }
+    }
+
+    fn flaky_signer(failures: u32) -> FlakySigner {
+        FlakySigner {
+            inner: PrivateKeySigner::from_str(TEST_SIGNER_KEY).unwrap(),
+            failures_remaining: AtomicU32::new(failures),
+            calls: AtomicU32::new(0),
+        }
+    }
+
+    async fn forge_with(signer: &FlakySigner) -> Result<(String, String)> {
+        PaymentTransactionForger::new()
+            .forge_flat_transfer_hex_with_signer(
+                Address::repeat_byte(0x11),
+                U256::from(1_000u64),
+                1,
+                0,
+                2_000_000_000,
+                1_000_000_000,
+                21_000,
+                signer,
+            )
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_transient_signing_failure_is_retried() {
+        let signer = flaky_signer(1);
+        let (tx_hex, tx_hash) = forge_with(&signer).await.unwrap();
+
+        assert!(tx_hex.starts_with("0x02"));
+        assert_eq!(tx_hash.len(), 66);
+        assert_eq!(signer.calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_signing_gives_up_after_max_attempts() {
+        let signer = flaky_signer(MAX_SIGNING_ATTEMPTS);
+        assert!(forge_with(&signer).await.is_err());
+        assert_eq!(signer.calls.load(Ordering::SeqCst), MAX_SIGNING_ATTEMPTS);
+    }
+
+    #[tokio::test]
+    async fn test_malformed_key_is_not_retried() {
+        let result = PaymentTransactionForger::new()
+            .forge_flat_transfer_hex(
+                Address::repeat_byte(0x11),
+                U256::from(1_000u64),
+                1,
+                0,
+                2_000_000_000,
+                1_000_000_000,
+                21_000,
+                "0xnot-a-key",
+            )
+            .await;
+        assert!(result.unwrap_err().to_string().contains("invalid signer key"));
+    }
+
+    #[tokio::test]
+    async fn test_legacy_transfer_carries_gas_price_and_chain_id() {
+        let (tx_hex, _) = PaymentTransactionForger::new()
+            .forge_legacy_transfer_hex(
+                Address::repeat_byte(0x11),
+                U256::from(1_000u64),
+                11_155_111,
+                3,
+                5_000_000_000,
+                21_000,
+                TEST_SIGNER_KEY,
+            )
+            .await
+            .unwrap();
+
+        let raw = alloy::hex::decode(tx_hex).unwrap();
+        let envelope = TxEnvelope::decode_2718(&mut raw.as_slice()).unwrap();
+        assert_eq!(envelope.tx_type(), TxType::Legacy);
+        assert_eq!(envelope.gas_price(), Some(5_000_000_000));
+        // Recovered from the EIP-155 signature
+        assert_eq!(envelope.chain_id(), Some(11_155_111));
+
+        let tx = envelope.as_legacy().unwrap().tx();
+        assert_eq!(tx.to, TxKind::Call(Address::repeat_byte(0x11)));
+        assert_eq!(tx.value, U256::from(1_000u64));
+    }
+
+    /// Forge a transfer of `amount` base units of an 18-decimal token and decode it
+    async fn forge_token_transfer(amount: U256) -> Result<TxEip1559> {
+        forge_token_transfer_with_decimals(amount, 18).await
+    }
+
+    async fn forge_token_transfer_with_decimals(amount: U256, decimals: u8) -> Result<TxEip1559> {
+        let (tx_hex, _) = PaymentTransactionForger::new()
+            .forge_permit_transfer_hex(
+                Address::repeat_byte(0x22),
+                Address::repeat_byte(0x11),
+                amount,
+                decimals,
+                1,
+                7,
+                2_000_000_000,
+                1_000_000_000,
+                60_000,
+                TEST_SIGNER_KEY,
+            )
+            .await?;
+
+        let raw = alloy::hex::decode(tx_hex).unwrap();
+        let envelope = TxEnvelope::decode_2718(&mut raw.as_slice()).unwrap();
+        Ok(envelope.as_eip1559().unwrap().tx().clone())
+    }
+
+    #[tokio::test]
+    async fn test_permit_transfer_calls_token_transfer() {
+        let tx = forge_token_transfer(U256::from(500_000_000_000_000_000u64)).await.unwrap();
+
+        assert_eq!(tx.to, TxKind::Call(Address::repeat_byte(0x22)));
+        assert_eq!(tx.value, U256::ZERO);
+        assert_eq!(tx.nonce, 7);
+        assert_eq!(tx.gas_limit, 60_000);
+
+        let input = tx.input.as_ref();
+        assert_eq!(input.len(), 68);
+        assert_eq!(input[..4], [0xa9, 0x05, 0x9c, 0xbb]);
+        assert_eq!(input[4..16], [0u8; 12]);
+        assert_eq!(Address::from_slice(&input[16..36]), Address::repeat_byte(0x11));
+        assert_eq!(U256::from_be_slice(&input[36..68]), U256::from(500_000_000_000_000_000u64));
+    }
+
+    #[tokio::test]
+    async fn test_permit_transfer_amount_is_in_token_base_units() {
+        // 0.5 of a 6-decimal token is sent as is, without rescaling
+        let tx = forge_token_transfer_with_decimals(U256::from(500_000u64), 6).await.unwrap();
+        assert_eq!(U256::from_be_slice(&tx.input[36..68]), U256::from(500_000u64));
+    }
+
+    #[tokio::test]
+    async fn test_returned_hash_is_the_hash_of_the_raw_transaction() {
+        let forger = PaymentTransactionForger::new();
+        let to = Address::repeat_byte(0x11);
+        let forged = [
+            forger
+                .forge_flat_transfer_hex(to, U256::from(1_000u64), 1, 0, 2_000_000_000, 1_000_000_000, 21_000, TEST_SIGNER_KEY)
+                .await
+                .unwrap(),
+            forger
+                .forge_legacy_transfer_hex(to, U256::from(1_000u64), 1, 0, 2_000_000_000, 21_000, TEST_SIGNER_KEY)
+                .await
+                .unwrap(),
+            forger
+                .forge_permit_transfer_hex(
+                    Address::repeat_byte(0x22),
+                    to,
+                    U256::from(500_000u64),
+                    6,
+                    1,
+                    0,
+                    2_000_000_000,
+                    1_000_000_000,
+                    60_000,
+                    TEST_SIGNER_KEY,
+                )
+                .await
+                .unwrap(),
+        ];
+
+        for (tx_hex, tx_hash) in forged {
+            let raw = alloy::hex::decode(&tx_hex).unwrap();
+            assert_eq!(tx_hash, format!("0x{}", alloy::hex::encode(alloy::primitives::keccak256(&raw))), "{}", tx_hex);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_zero_permit_transfer_is_rejected() {
+        assert!(forge_token_transfer(U256::ZERO).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_permit_transfer_rejects_unrepresentable_decimals() {
+        assert!(forge_token_transfer_with_decimals(U256::from(1u64), MAX_TOKEN_DECIMALS).await.is_ok());
+        let err = forge_token_transfer_with_decimals(U256::from(1u64), MAX_TOKEN_DECIMALS + 1).await.unwrap_err();
+        assert!(err.to_string().contains("token decimals 78 exceed 77"), "{}", err);
+    }
+
+    #[test]
+    fn test_format_token_amount_renders_whole_tokens() {
+        assert_eq!(format_token_amount(U256::from(500_000u64), 6), "0.5");
+        assert_eq!(format_token_amount(U256::from(1_250_000u64), 6), "1.25");
+        assert_eq!(format_token_amount(U256::from(3_000_000u64), 6), "3");
+        assert_eq!(format_token_amount(U256::from(7u64), 0), "7");
+        assert_eq!(format_token_amount(U256::from(1u64), 18), "0.000000000000000001");
+    }
+}
@@ -16,35 +16,68 @@ impl PaymentPolicyEnforcer {
         Self { policy }
     }
 
-    /// Check if a payment is allowed under the current policy
+    /// Check if a payment is allowed under the current policy.
+    ///
+    /// `month_to_date_wei` is the spending so far this month, including today's.
     pub async fn check_payment_allowed(
         &self,
         payment_result: &PaymentResult,
         current_daily_spending: &DailySpending,
+        month_to_date_wei: U256,
     ) -> Result<bool> {
+        Ok(self
+            .cap_violation(payment_result.amount_wei, current_daily_spending, month_to_date_wei)
+            .is_none())
+    }
+
+    /// Describe the limit a payment of `amount_wei` would break, or None if it is allowed
+    pub fn cap_violation(
+        &self,
+        amount_wei: U256,
+        current_daily_spending: &DailySpending,
+        month_to_date_wei: U256,
+    ) -> Option<String> {
         // Check per-bundle cap
-        if payment_result.amount_wei > self.policy.per_bundle_cap_wei {
-            return Ok(false);
+        if amount_wei > self.policy.per_bundle_cap_wei {
+            return Some(format!(
+                "Payment of {} wei exceeds the per-bundle cap of {} wei",
+                amount_wei, self.policy.per_bundle_cap_wei
+            ));
         }
 
         // Check daily cap
         let new_daily_total = current_daily_spending
             .total_amount_wei
-            .checked_add(payment_result.amount_wei)
+            .checked_add(amount_wei)
             .unwrap_or(U256::MAX);
 
         if new_daily_total > self.policy.daily_cap_wei {
-            return Ok(false);
+            return Some(format!(
+                "Payment of {} wei would bring today's spending to {} wei, above the daily cap of {} wei",
+                amount_wei, new_daily_total, self.policy.daily_cap_wei
+            ));
+        }
+
+        // Check monthly cap
+        if let Some(monthly_cap_wei) = self.policy.monthly_cap_wei {
+            let new_monthly_total = month_to_date_wei.checked_add(amount_wei).unwrap_or(U256::MAX);
+            if new_monthly_total > monthly_cap_wei {
+                return Some(format!(
+                    "Payment of {} wei would bring this month's spending to {} wei, above the monthly cap of {} wei",
+                    amount_wei, new_monthly_total, monthly_cap_wei
+                ));
+            }
         }
 
         // Check emergency stop
-        if self.policy.emergency_stop_enabled
-            && payment_result.amount_wei > self.policy.emergency_stop_threshold_wei
-        {
-            return Ok(false);
+        if self.policy.emergency_stop_enabled && amount_wei > self.policy.emergency_stop_threshold_wei {
+            return Some(format!(
+                "Payment of {} wei exceeds the emergency stop threshold of {} wei",
+                amount_wei, self.policy.emergency_stop_threshold_wei
+            ));
         }
 
-        Ok(true)
+        None
     }
 
     /// Update daily spending record
@@ -101,7 +134,7 @@ mod tests {
         let daily_spending = enforcer.get_or_create_daily_spending();
 
         let allowed = enforcer
-            .check_payment_allowed(&payment_result, &daily_spending)
+            .check_payment_allowed(&payment_result, &daily_spending, U256::ZERO)
             .await
             .unwrap();
 
@@ -127,10 +160,44 @@ mod tests {
         let daily_spending = enforcer.get_or_create_daily_spending();
 
         let allowed = enforcer
-            .check_payment_allowed(&payment_result, &daily_spending)
+            .check_payment_allowed(&payment_result, &daily_spending, U256::ZERO)
             .await
             .unwrap();
 
         assert!(!allowed);
     }
+
+    #[tokio::test]
+    async fn test_payment_exceeds_monthly_cap() {
+        let policy = PaymentPolicy {
+            monthly_cap_wei: Some(U256::from(10_000_000_000_000_000u64)), // 0.01 ETH
+            ..PaymentPolicy::default()
+        };
+        let enforcer = PaymentPolicyEnforcer::new(policy);
+
+        let payment_result = PaymentResult::new(
+            U256::from(1_000_000_000_000_000u64), // 0.001 ETH
+            PaymentFormula::Flat,
+            21000,
+            None,
+            false,
+        );
+        let daily_spending = enforcer.get_or_create_daily_spending();
+
+        // Earlier days this month already spent 0.0095 ETH
+        let month_to_date = U256::from(9_500_000_000_000_000u64);
+        assert!(!enforcer
+            .check_payment_allowed(&payment_result, &daily_spending, month_to_date)
+            .await
+            .unwrap());
+        assert!(enforcer
+            .cap_violation(payment_result.amount_wei, &daily_spending, month_to_date)
+            .unwrap()
+            .contains("monthly cap"));
+
+        assert!(enforcer
+            .check_payment_allowed(&payment_result, &daily_spending, U256::ZERO)
+            .await
+            .unwrap());
+    }
 }
@@ -1,45 +1,209 @@
 //! Individual relay client implementation
 
+use crate::signing::{sign_request_body, SIGNATURE_HEADER};
+use alloy::signers::local::PrivateKeySigner;
 use reqwest::Client;
+use std::future::Future;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
 use std::time::Duration;
 use tokio::time::timeout;
 use types::{
-    BuilderRelay, RelayBundleRequest, RelayBundleResponse, RelayResult, Result,
+    BuilderRelay, BundleStats, JsonRpcId, MevBundleRequest, RelayBundleRequest, RelayBundleResponse, RelayHttpVersion, RelayResult,
+    RelaySubmission, Result,
 };
 use serde_json::Value;
 use uuid::Uuid;
 
+/// Delay before the first retry; doubles on every subsequent attempt
+const RETRY_BASE_DELAY_MS: u64 = 100;
+
+/// Replacement UUID for `bundle_id`'s submission targeting `block`.
+///
+/// Relays replace a bundle sent with an already-seen `replacementUuid`, so each target block
+/// gets its own UUID (v5 of the bundle id and block number); untargeted submissions use
+/// `bundle_id` itself.
+pub fn block_replacement_uuid(bundle_id: Uuid, block: Option<u64>) -> Uuid {
+    match block {
+        Some(block) => Uuid::new_v5(&bundle_id, &block.to_be_bytes()),
+        None => bundle_id,
+    }
+}
+
+/// Retries shared by every relay submission of one bundle.
+///
+/// Clones draw from the same pool, capping the retries summed across all relays.
+#[derive(Debug, Clone)]
+pub struct RetryBudget {
+    remaining: Arc<AtomicU32>,
+}
+
+impl RetryBudget {
+    /// Create a budget allowing `max_retries` retries in total
+    pub fn new(max_retries: u32) -> Self {
+        Self { remaining: Arc::new(AtomicU32::new(max_retries)) }
+    }
+
+    /// Consume one retry, returning false once the budget is exhausted
+    pub fn try_take(&self) -> bool {
+        self.remaining
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |remaining| remaining.checked_sub(1))
+            .is_ok()
+    }
+
+    /// Retries left in the budget
+    pub fn remaining(&self) -> u32 {
+        self.remaining.load(Ordering::SeqCst)
+    }
+}
+
 /// HTTP client for a single relay
 #[derive(Debug, Clone)]
 pub struct RelayClient {
     relay: BuilderRelay,
     http_client: Client,
+    flashbots_signer: Option<PrivateKeySigner>,
+    retry_budget: Option<RetryBudget>,
 }
 
 impl RelayClient {
     /// Create a new relay client
     pub fn new(relay: BuilderRelay) -> Self {
-        let http_client = Client::builder()
+        let pool = PoolSettings::from_relay(&relay);
+        let builder = match relay.http_version {
+            // rustls advertises h2 and http/1.1 via ALPN, so TLS relays negotiate HTTP/2 when able
+            RelayHttpVersion::Auto => Client::builder(),
+            RelayHttpVersion::Http1 => Client::builder().http1_only(),
+            RelayHttpVersion::Http2 => Client::builder().http2_prior_knowledge(),
+        };
+        let http_client = builder
             .timeout(Duration::from_secs(relay.timeout_seconds))
             .user_agent("atomic-bundler/0.1.0")
+            .pool_max_idle_per_host(pool.max_idle_per_host)
+            .pool_idle_timeout(pool.idle_timeout)
+            .tcp_keepalive(pool.tcp_keepalive)
+            // Advertise Accept-Encoding and transparently decode compressed relay responses
+            .gzip(true)
+            .deflate(true)
+            .brotli(true)
             .build()
             .expect("Failed to create HTTP client");
 
+        let flashbots_signer = load_auth_signer(&relay);
+
         Self {
             relay,
             http_client,
+            flashbots_signer,
+            retry_budget: None,
         }
     }
 
+    /// Sign every POST (`X-Flashbots-Signature`) with `signer` instead of the key named by `auth_key_env`
+    pub fn with_signer(mut self, signer: PrivateKeySigner) -> Self {
+        self.flashbots_signer = Some(signer);
+        self
+    }
+
+    /// Draw bundle submission retries from `budget` in addition to `max_retries`
+    pub fn with_retry_budget(mut self, budget: RetryBudget) -> Self {
+        self.retry_budget = Some(budget);
+        self
+    }
+
     /// Submit a bundle to the relay
     pub async fn submit_bundle(
         &self,
         transactions: Vec<String>,
         target_block: Option<u64>,
     ) -> Result<String> {
-        let request_id = self.generate_request_id();
-        // Target block is no longer required; pass None to omit it from the payload
-        let request = RelayBundleRequest::new(request_id, transactions, target_block);
+        let Some(block) = target_block else {
+            return self.submit_bundle_with_uuid(transactions, None, None).await;
+        };
+        let (_, result) = self
+            .submit_bundle_for_blocks(transactions, [block])
+            .await
+            .pop()
+            .expect("one result per target block");
+        result
+    }
+
+    /// Submit the same bundle for each of `blocks`, one `eth_sendBundle` request per block.
+    ///
+    /// Blocks are submitted in ascending order (duplicates dropped), each request with its own
+    /// id; a failure for one block does not stop the rest. Results are ordered by block number.
+    pub async fn submit_bundle_for_blocks(
+        &self,
+        transactions: Vec<String>,
+        blocks: impl IntoIterator<Item = u64>,
+    ) -> Vec<(u64, Result<String>)> {
+        let mut transactions = transactions;
+        self.submit_bundle_for_targets(&mut transactions, blocks.into_iter().map(Some), None, |_| async { None::<Vec<String>> })
+            .await
+            .into_iter()
+            .map(|(block, result)| {
+                let block = block.expect("every target has a block number");
+                (block, result.map(|submission| submission.bundle_hash))
+            })
+            .collect()
+    }
+
+    /// Like [`Self::submit_bundle_for_blocks`], but returns the relay's raw results and accepts
+    /// a `None` target (submitted without `blockNumber`).
+    ///
+    /// With `replacement_uuid`, each target is sent under its own [`block_replacement_uuid`].
+    /// The first nonce-too-low rejection calls `reforge` with the current transactions; if it
+    /// returns replacements, they are retried once for that target and used for every later one.
+    pub async fn submit_bundle_for_targets<F, Fut>(
+        &self,
+        transactions: &mut Vec<String>,
+        targets: impl IntoIterator<Item = Option<u64>>,
+        replacement_uuid: Option<Uuid>,
+        mut reforge: F,
+    ) -> Vec<(Option<u64>, Result<RelaySubmission>)>
+    where
+        F: FnMut(Vec<String>) -> Fut,
+        Fut: Future<Output = Option<Vec<String>>>,
+    {
+        let targets: std::collections::BTreeSet<Option<u64>> = targets.into_iter().collect();
+        let mut results = Vec::with_capacity(targets.len());
+        let mut reforged = false;
+        for target in targets {
+            let replacement_uuid = replacement_uuid.map(|bundle_id| block_replacement_uuid(bundle_id, target));
+            let mut result = self.submit_bundle_detailed(transactions.clone(), target, replacement_uuid).await;
+            if !reforged && result.as_ref().is_err_and(|e| e.is_nonce_too_low()) {
+                reforged = true;
+                if let Some(replacement) = reforge(transactions.clone()).await {
+                    *transactions = replacement;
+                    result = self.submit_bundle_detailed(transactions.clone(), target, replacement_uuid).await;
+                }
+            }
+            results.push((target, result));
+        }
+        results
+    }
+
+    /// Submit a bundle to the relay, attaching the replacement UUID if the relay expects one
+    pub async fn submit_bundle_with_uuid(
+        &self,
+        transactions: Vec<String>,
+        target_block: Option<u64>,
+        replacement_uuid: Option<Uuid>,
+    ) -> Result<String> {
+        self.submit_bundle_detailed(transactions, target_block, replacement_uuid)
+            .await
+            .map(|submission| submission.bundle_hash)
+    }
+
+    /// Like [`Self::submit_bundle_with_uuid`], but also returns the relay's raw result
+    pub async fn submit_bundle_detailed(
+        &self,
+        transactions: Vec<String>,
+        target_block: Option<u64>,
+        replacement_uuid: Option<Uuid>,
+    ) -> Result<RelaySubmission> {
+        let request = self.build_bundle_request(transactions, target_block, replacement_uuid);
 
         tracing::info!(
             relay = %self.relay.name,
@@ -67,12 +231,140 @@ impl RelayClient {
             }
         }
 
+        let mut attempt = 0;
+        loop {
+            match self.send_bundle_request(&request).await {
+                Ok(submission) => {
+                    tracing::info!(relay = %self.relay.name, bundle_hash = %submission.bundle_hash, "Bundle submitted");
+                    return Ok(submission);
+                }
+                Err(e) if attempt < self.relay.max_retries && is_retryable(&e) && self.take_retry() => {
+                    // Jitter spreads out retries from clients that failed at the same moment
+                    let delay = retry_backoff(attempt, RETRY_BASE_DELAY_MS, self.relay.max_backoff_ms)
+                        + Duration::from_millis(types::utils::random_jitter_ms(RETRY_BASE_DELAY_MS));
+                    tracing::warn!(
+                        relay = %self.relay.name,
+                        attempt = attempt + 1,
+                        delay_ms = delay.as_millis() as u64,
+                        error = %e,
+                        "Bundle submission failed; retrying"
+                    );
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+
+    /// Whether another retry is allowed by the shared retry budget, if any
+    fn take_retry(&self) -> bool {
+        match &self.retry_budget {
+            Some(budget) => budget.try_take(),
+            None => true,
+        }
+    }
+
+    /// Send a single eth_sendBundle request and parse the bundle hash
+    async fn send_bundle_request(
+        &self,
+        request: &RelayBundleRequest,
+    ) -> std::result::Result<RelaySubmission, types::error::RelayError> {
+        let raw_text = self.post_json(request).await?;
+
+        parse_relay_submission(
+            &self.relay.name,
+            &raw_text,
+            self.relay.response_result_path.as_deref(),
+        )
+    }
+
+    /// Submit a MEV-Share bundle via `mev_sendBundle` (single attempt, no retries)
+    pub async fn send_mev_bundle(&self, request: &MevBundleRequest) -> Result<RelaySubmission> {
+        tracing::info!(
+            relay = %self.relay.name,
+            inclusion_block = %request.params[0].inclusion.block,
+            body_len = request.params[0].body.len(),
+            "Submitting mev_sendBundle to relay"
+        );
+
+        let raw_text = self.post_json(request).await?;
+        let submission = parse_relay_submission(
+            &self.relay.name,
+            &raw_text,
+            self.relay.response_result_path.as_deref(),
+        )?;
+        tracing::info!(relay = %self.relay.name, bundle_hash = %submission.bundle_hash, "MEV-Share bundle submitted");
+        Ok(submission)
+    }
+
+    /// Submit the same bundle for several target blocks in one JSON-RPC batch call.
+    ///
+    /// Returns one result per target block, in the order given.
+    pub async fn submit_bundle_batch(
+        &self,
+        transactions: Vec<String>,
+        target_blocks: Vec<u64>,
+    ) -> Result<Vec<(u64, Result<String>)>> {
+        let requests: Vec<RelayBundleRequest> = target_blocks
+            .iter()
+            .map(|block| self.build_bundle_request(transactions.clone(), Some(*block), None))
+            .collect();
+
+        tracing::info!(
+            relay = %self.relay.name,
+            target_blocks = ?target_blocks,
+            "Submitting batched bundle to relay"
+        );
+
+        let raw_text = self.post_json(&requests).await?;
+        let responses: Vec<Value> = serde_json::from_str(&raw_text).map_err(|e| {
+            types::error::RelayError::InvalidResponse {
+                relay: self.relay.name.clone(),
+                message: format!("invalid batch response: {} | raw: {}", e, raw_text),
+            }
+        })?;
+
+        let results = requests
+            .iter()
+            .zip(target_blocks)
+            .map(|(request, block)| {
+                // Batch responses may come back in any order; match them up by id
+                let result = responses
+                    .iter()
+                    .find(|response| {
+                        response.get("id").cloned().and_then(|id| serde_json::from_value(id).ok())
+                            == Some(request.id.clone())
+                    })
+                    .ok_or_else(|| types::error::RelayError::InvalidResponse {
+                        relay: self.relay.name.clone(),
+                        message: format!("missing batch response for block {}", block),
+                    })
+                    .and_then(|response| {
+                        parse_bundle_submit_response(
+                            &self.relay.name,
+                            &response.to_string(),
+                            self.relay.response_result_path.as_deref(),
+                        )
+                    })
+                    .map_err(Into::into);
+                (block, result)
+            })
+            .collect();
+
+        Ok(results)
+    }
+
+    /// POST a JSON body to the relay and return the raw response text
+    async fn post_json<T: serde::Serialize + ?Sized>(
+        &self,
+        body: &T,
+    ) -> std::result::Result<String, types::error::RelayError> {
+        let request = self.signed_post(&self.relay.relay_url, body)?;
+
         let response = timeout(
             Duration::from_secs(self.relay.timeout_seconds),
-            self.http_client
-                .post(&self.relay.relay_url)
-                .json(&request)
-                .send(),
+            request.send(),
         )
         .await
         .map_err(|_| types::error::RelayError::ConnectionTimeout {
@@ -87,22 +379,176 @@ impl RelayClient {
             return Err(types::error::RelayError::HttpError {
                 relay: self.relay.name.clone(),
                 status: response.status().as_u16(),
+            });
+        }
+
+        let mismatches = header_mismatches(&self.relay.expected_response_headers, response.headers());
+        if !mismatches.is_empty() {
+            tracing::warn!(
+                relay = %self.relay.name,
+                mismatches = ?mismatches,
+                "Relay response headers do not match expected values"
+            );
+            if self.relay.strict_response_headers {
+                return Err(types::error::RelayError::InvalidResponse {
+                    relay: self.relay.name.clone(),
+                    message: format!("unexpected response headers: {}", mismatches.join(", ")),
+                });
             }
-            .into());
         }
 
-        let raw_text = response.text().await.map_err(|e| types::error::RelayError::InvalidResponse {
+        self.read_body(response).await
+    }
+
+    /// Build a JSON POST to `url`, signed over the exact body bytes when an auth signer is set
+    fn signed_post<T: serde::Serialize + ?Sized>(
+        &self,
+        url: &str,
+        body: &T,
+    ) -> std::result::Result<reqwest::RequestBuilder, types::error::RelayError> {
+        let body = serde_json::to_vec(body).map_err(|e| types::error::RelayError::InvalidResponse {
+            relay: self.relay.name.clone(),
+            message: format!("error encoding request: {}", e),
+        })?;
+        let mut request = self
+            .http_client
+            .post(url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json");
+        if let Some(signer) = &self.flashbots_signer {
+            let signature = sign_request_body(signer, self.relay.signature_scheme, &body).map_err(|e| {
+                types::error::RelayError::InvalidResponse {
+                    relay: self.relay.name.clone(),
+                    message: format!("error signing request: {}", e),
+                }
+            })?;
+            request = request.header(SIGNATURE_HEADER, signature);
+        }
+        Ok(request.body(body))
+    }
+
+    /// Read a response body, failing once it exceeds `max_relay_response_bytes`
+    async fn read_body(
+        &self,
+        mut response: reqwest::Response,
+    ) -> std::result::Result<String, types::error::RelayError> {
+        let max_bytes = self.relay.max_relay_response_bytes;
+        let too_large = || types::error::RelayError::InvalidResponse {
+            relay: self.relay.name.clone(),
+            message: format!("response body exceeds {} bytes", max_bytes),
+        };
+        if response.content_length().is_some_and(|len| len > max_bytes as u64) {
+            return Err(too_large());
+        }
+
+        let mut body = Vec::new();
+        while let Some(chunk) = response.chunk().await.map_err(|e| types::error::RelayError::InvalidResponse {
             relay: self.relay.name.clone(),
             message: format!("error reading response body: {}", e),
+        })? {
+            if body.len() + chunk.len() > max_bytes {
+                return Err(too_large());
+            }
+            body.extend_from_slice(&chunk);
+        }
+
+        String::from_utf8(body).map_err(|e| types::error::RelayError::InvalidResponse {
+            relay: self.relay.name.clone(),
+            message: format!("error reading response body: {}", e),
+        })
+    }
+
+    /// Forward a single signed transaction via `eth_sendPrivateTransaction` and return its hash.
+    ///
+    /// `max_block_number` bounds how long the relay keeps trying to include it.
+    pub async fn send_private_transaction(&self, tx: &str, max_block_number: Option<u64>) -> Result<String> {
+        let mut params = serde_json::json!({ "tx": tx });
+        if let Some(block) = max_block_number {
+            params["maxBlockNumber"] = serde_json::json!(format!("0x{:x}", block));
+        }
+        let request = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": self.generate_request_id(),
+            "method": "eth_sendPrivateTransaction",
+            "params": [params]
+        });
+
+        tracing::info!(relay = %self.relay.name, max_block_number = ?max_block_number, "Sending private transaction");
+        let raw_text = self.post_json(&request).await?;
+        let tx_hash = parse_bundle_submit_response(&self.relay.name, &raw_text, self.relay.response_result_path.as_deref())?;
+        Ok(tx_hash)
+    }
+
+    /// Cancel a bundle previously submitted with `replacement_uuid` via `eth_cancelBundle`
+    pub async fn cancel_bundle(&self, replacement_uuid: Uuid) -> Result<()> {
+        let request = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": self.generate_request_id(),
+            "method": "eth_cancelBundle",
+            "params": [{ "replacementUuid": replacement_uuid }]
+        });
+
+        tracing::info!(relay = %self.relay.name, replacement_uuid = %replacement_uuid, "Cancelling bundle");
+        let raw_text = self.post_json(&request).await?;
+        parse_cancel_response(&self.relay.name, &raw_text)?;
+        Ok(())
+    }
+
+    /// Ask the relay's stats endpoint (`status_url`) whether a bundle was included in a block.
+    ///
+    /// Uses `titan_getBundleStats`; returns `Ok(false)` when the relay has no stats endpoint.
+    pub async fn bundle_included(&self, bundle_hash: &str) -> Result<bool> {
+        let Some(status_url) = &self.relay.status_url else {
+            return Ok(false);
+        };
+
+        let request = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": self.generate_request_id(),
+            "method": "titan_getBundleStats",
+            "params": [{ "bundleHash": bundle_hash }]
+        });
+
+        let response = timeout(
+            Duration::from_secs(self.relay.timeout_seconds),
+            self.signed_post(status_url, &request)?.send(),
+        )
+        .await
+        .map_err(|_| types::error::RelayError::ConnectionTimeout {
+            relay: self.relay.name.clone(),
+        })?
+        .map_err(|e| types::error::RelayError::HttpError {
+            relay: self.relay.name.clone(),
+            status: e.status().map(|s| s.as_u16()).unwrap_or(0),
         })?;
 
-        match parse_bundle_submit_response(&self.relay.name, &raw_text) {
-            Ok(hash) => {
-                tracing::info!(relay = %self.relay.name, bundle_hash = %hash, "Bundle submitted");
-                Ok(hash)
+        if !response.status().is_success() {
+            return Err(types::error::RelayError::HttpError {
+                relay: self.relay.name.clone(),
+                status: response.status().as_u16(),
             }
-            Err(e) => Err(e.into()),
+            .into());
         }
+
+        let body: Value = response.json().await.map_err(|e| types::error::RelayError::InvalidResponse {
+            relay: self.relay.name.clone(),
+            message: format!("invalid bundle stats response: {}", e),
+        })?;
+        Ok(body["result"]["status"] == "IncludedInBlock")
+    }
+
+    /// Fetch a bundle's simulation and builder progress via `flashbots_getBundleStats`.
+    ///
+    /// The request is signed like bundle submissions; unknown bundles yield empty stats.
+    pub async fn get_bundle_stats(&self, bundle_hash: String, target_block: u64) -> Result<BundleStats> {
+        let request = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": self.generate_request_id(),
+            "method": "flashbots_getBundleStats",
+            "params": [{ "bundleHash": bundle_hash, "blockNumber": format!("0x{:x}", target_block) }]
+        });
+
+        let raw_text = self.post_json(&request).await?;
+        Ok(parse_bundle_stats_response(&self.relay.name, &raw_text)?)
     }
 
     /// Perform health check on the relay
@@ -119,10 +565,7 @@ impl RelayClient {
 
         let response = timeout(
             Duration::from_secs(10), // Shorter timeout for health checks
-            self.http_client
-                .post(&self.relay.relay_url)
-                .json(&request)
-                .send(),
+            self.signed_post(&self.relay.relay_url, &request)?.send(),
         )
         .await
         .map_err(|_| types::error::RelayError::ConnectionTimeout {
@@ -151,27 +594,195 @@ impl RelayClient {
         &self.relay
     }
 
+    /// Build the eth_sendBundle request for this relay
+    fn build_bundle_request(
+        &self,
+        transactions: Vec<String>,
+        target_block: Option<u64>,
+        replacement_uuid: Option<Uuid>,
+    ) -> RelayBundleRequest {
+        // Target block is no longer required; pass None to omit it from the payload
+        let request = RelayBundleRequest::new(self.generate_request_id(), transactions, target_block)
+            .with_chain_id(self.relay.chain_id)
+            .with_version(self.relay.bundle_version.clone());
+        if self.relay.send_bundle_uuid {
+            request.with_uuid(replacement_uuid)
+        } else {
+            request
+        }
+    }
+
     /// Generate a unique request ID
-    fn generate_request_id(&self) -> u64 {
+    fn generate_request_id(&self) -> JsonRpcId {
         // Use timestamp and random component for uniqueness
         let uuid = Uuid::new_v4();
         let bytes = uuid.as_bytes();
-        u64::from_be_bytes([
+        let id = u64::from_be_bytes([
             bytes[0], bytes[1], bytes[2], bytes[3], bytes[4], bytes[5], bytes[6], bytes[7],
-        ])
+        ]);
+        JsonRpcId::with_format(id, self.relay.request_id_format)
+    }
+}
+
+/// Connection pool settings applied to a relay's HTTP client
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct PoolSettings {
+    max_idle_per_host: usize,
+    idle_timeout: Option<Duration>,
+    tcp_keepalive: Option<Duration>,
+}
+
+impl PoolSettings {
+    /// Resolve the relay's pool configuration; zero durations disable the corresponding timer
+    fn from_relay(relay: &BuilderRelay) -> Self {
+        let non_zero_secs = |secs: u64| (secs > 0).then(|| Duration::from_secs(secs));
+        Self {
+            max_idle_per_host: relay.pool_max_idle_per_host,
+            idle_timeout: non_zero_secs(relay.pool_idle_timeout_seconds),
+            tcp_keepalive: non_zero_secs(relay.tcp_keepalive_seconds),
+        }
+    }
+}
+
+/// Load the request signing key from the relay's `auth_key_env`, if configured
+fn load_auth_signer(relay: &BuilderRelay) -> Option<PrivateKeySigner> {
+    let env = relay.auth_key_env.as_ref()?;
+    match std::env::var(env).map_err(|e| e.to_string()).and_then(|key| {
+        PrivateKeySigner::from_str(&key).map_err(|e| e.to_string())
+    }) {
+        Ok(signer) => Some(signer),
+        Err(e) => {
+            tracing::warn!(relay = %relay.name, env = %env, error = %e, "Relay auth key unavailable; sending unsigned requests");
+            None
+        }
+    }
+}
+
+/// Exponential backoff for the given (zero-based) retry attempt, capped at `max_backoff_ms`
+fn retry_backoff(attempt: u32, base_delay_ms: u64, max_backoff_ms: u64) -> Duration {
+    let delay_ms = base_delay_ms.saturating_mul(2u64.saturating_pow(attempt));
+    Duration::from_millis(delay_ms.min(max_backoff_ms))
+}
+
+/// Describe every expected header that is missing or has a different value
+fn header_mismatches(
+    expected: &std::collections::HashMap<String, String>,
+    headers: &reqwest::header::HeaderMap,
+) -> Vec<String> {
+    let mut mismatches: Vec<String> = expected
+        .iter()
+        .filter_map(|(name, expected_value)| match headers.get(name.as_str()) {
+            Some(actual) if actual.to_str().ok() == Some(expected_value.as_str()) => None,
+            Some(actual) => Some(format!("{}={:?} (expected {:?})", name, actual, expected_value)),
+            None => Some(format!("{} missing (expected {:?})", name, expected_value)),
+        })
+        .collect();
+    mismatches.sort();
+    mismatches
+}
+
+/// Whether a failed submission is worth retrying (transport failures, 429 and 5xx)
+fn is_retryable(error: &types::error::RelayError) -> bool {
+    match error {
+        types::error::RelayError::ConnectionTimeout { .. } => true,
+        types::error::RelayError::RateLimited { .. } => true,
+        types::error::RelayError::HttpError { status, .. } => {
+            *status == 0 || *status == 429 || *status >= 500
+        }
+        _ => false,
+    }
+}
+
+/// Parse a flashbots_getBundleStats response; a null or empty `result` means the bundle is unknown
+fn parse_bundle_stats_response(
+    relay_name: &str,
+    raw_text: &str,
+) -> std::result::Result<BundleStats, types::error::RelayError> {
+    let invalid = |message: String| types::error::RelayError::InvalidResponse {
+        relay: relay_name.to_string(),
+        message,
+    };
+    let value: Value = serde_json::from_str(raw_text)
+        .map_err(|e| invalid(format!("invalid JSON response: {} | raw: {}", e, raw_text)))?;
+
+    if let Some(err) = value.get("error").filter(|err| !err.is_null()) {
+        let code = err.get("code").and_then(|c| c.as_i64()).unwrap_or(0);
+        let message = err.get("message").and_then(|m| m.as_str()).unwrap_or("unknown error");
+        return Err(types::error::RelayError::from_rpc_error(relay_name, code, message));
+    }
+
+    match value.get("result") {
+        None | Some(Value::Null) => Ok(BundleStats::default()),
+        Some(result) => serde_json::from_value(result.clone())
+            .map_err(|e| invalid(format!("invalid bundle stats: {}", e))),
     }
 }
 
+/// Check an eth_cancelBundle response; relays return varying results, so only errors matter
+fn parse_cancel_response(relay_name: &str, raw_text: &str) -> std::result::Result<(), types::error::RelayError> {
+    let value: Value = serde_json::from_str(raw_text).map_err(|e| types::error::RelayError::InvalidResponse {
+        relay: relay_name.to_string(),
+        message: format!("invalid JSON response: {} | raw: {}", e, raw_text),
+    })?;
+
+    match value.get("error").filter(|err| !err.is_null()) {
+        Some(err) => {
+            let code = err.get("code").and_then(|c| c.as_i64()).unwrap_or(0);
+            let reason = err
+                .as_str()
+                .or_else(|| err.get("message").and_then(|m| m.as_str()))
+                .map(str::to_string)
+                .unwrap_or_else(|| err.to_string());
+            Err(types::error::RelayError::from_rpc_error(relay_name, code, &reason))
+        }
+        None => Ok(()),
+    }
+}
+
+/// Parse the bundle hash and keep the raw `result` for relay-specific extras
+fn parse_relay_submission(
+    relay_name: &str,
+    raw_text: &str,
+    result_path: Option<&str>,
+) -> std::result::Result<RelaySubmission, types::error::RelayError> {
+    let bundle_hash = parse_bundle_submit_response(relay_name, raw_text, result_path)?;
+    let raw_result = serde_json::from_str::<Value>(raw_text)
+        .map(|value| value.get("result").cloned().unwrap_or(value))
+        .unwrap_or(Value::Null);
+    Ok(RelaySubmission { bundle_hash, raw_result })
+}
+
 /// Parse builder response into bundle hash with robust fallbacks
-fn parse_bundle_submit_response(relay_name: &str, raw_text: &str) -> std::result::Result<String, types::error::RelayError> {
+///
+/// When `result_path` (a JSON pointer) is configured for the relay, it is tried first.
+fn parse_bundle_submit_response(
+    relay_name: &str,
+    raw_text: &str,
+    result_path: Option<&str>,
+) -> std::result::Result<String, types::error::RelayError> {
+    // 0) Relay-specific JSON pointer override
+    if let Some(pointer) = result_path {
+        if let Ok(value) = serde_json::from_str::<Value>(raw_text) {
+            if let Some(result) = value.pointer(pointer).and_then(|v| v.as_str()) {
+                return Ok(result.to_string());
+            }
+        }
+        tracing::debug!(
+            relay = %relay_name,
+            pointer = %pointer,
+            "Configured response_result_path did not match; falling back to default parsing"
+        );
+    }
+
     // 1) Try strict schema
     if let Ok(resp) = serde_json::from_str::<RelayBundleResponse>(raw_text) {
         return match resp.result {
             RelayResult::Success { result } => Ok(result),
-            RelayResult::Error { error } => Err(types::error::RelayError::BundleRejected {
-                relay: relay_name.to_string(),
-                reason: error.message,
-            }),
+            RelayResult::Error { error } => Err(types::error::RelayError::from_rpc_error(
+                relay_name,
+                error.code.into(),
+                &error.message,
+            )),
         };
     }
 
@@ -190,18 +801,20 @@ fn parse_bundle_submit_response(relay_name: &str, raw_text: &str) -> std::result
         return Ok(result.to_string());
     }
 
-    // error path
-    let (code, message) = if let Some(err) = value.get("error") {
-        (
-            err.get("code").and_then(|c| c.as_i64()).unwrap_or(0) as i32,
-            err.get("message").and_then(|m| m.as_str()).unwrap_or("unknown error").to_string(),
-        )
-    } else {
-        (
-            value.get("code").and_then(|c| c.as_i64()).unwrap_or(0) as i32,
-            value.get("message").and_then(|m| m.as_str()).unwrap_or("invalid response").to_string(),
-        )
-    };
+    // { "error": ... } that didn't fit the strict schema (e.g. a bare string or a missing code);
+    // relays send these with HTTP 200, so they must not be mistaken for success
+    if let Some(err) = value.get("error").filter(|err| !err.is_null()) {
+        let code = err.get("code").and_then(|c| c.as_i64()).unwrap_or(0);
+        let reason = err
+            .as_str()
+            .or_else(|| err.get("message").and_then(|m| m.as_str()))
+            .map(str::to_string)
+            .unwrap_or_else(|| err.to_string());
+        return Err(types::error::RelayError::from_rpc_error(relay_name, code, &reason));
+    }
+
+    let code = value.get("code").and_then(|c| c.as_i64()).unwrap_or(0) as i32;
+    let message = value.get("message").and_then(|m| m.as_str()).unwrap_or("invalid response");
 
     Err(types::error::RelayError::InvalidResponse {
         relay: relay_name.to_string(),
@@ -218,6 +831,19 @@ mod tests {
         Mock, MockServer, ResponseTemplate,
     };
 
+    fn test_relay(relay_url: &str) -> BuilderRelay {
+        BuilderRelay {
+            name: "test".to_string(),
+            relay_url: relay_url.to_string(),
+            payment_address: Address::ZERO,
+            enabled: true,
+            timeout_seconds: 30,
+            max_retries: 3,
+            health_check_interval_seconds: 60,
+            ..BuilderRelay::default()
+        }
+    }
+
     #[tokio::test]
     async fn test_successful_bundle_submission() {
         let mock_server = MockServer::start().await;
@@ -232,15 +858,7 @@ mod tests {
             .mount(&mock_server)
             .await;
 
-        let relay = BuilderRelay {
-            name: "test".to_string(),
-            relay_url: mock_server.uri(),
-            payment_address: Address::ZERO,
-            enabled: true,
-            timeout_seconds: 30,
-            max_retries: 3,
-            health_check_interval_seconds: 60,
-        };
+        let relay = test_relay(&mock_server.uri());
 
         let client = RelayClient::new(relay);
         let result = client
@@ -251,6 +869,31 @@ mod tests {
         assert_eq!(result.unwrap(), "0x1234567890abcdef");
     }
 
+    #[tokio::test]
+    async fn test_send_private_transaction() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .and(path("/"))
+            .and(wiremock::matchers::body_partial_json(serde_json::json!({
+                "method": "eth_sendPrivateTransaction",
+                "params": [{ "tx": "0x02f8", "maxBlockNumber": "0x64" }]
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "result": "0xabcdef"
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let client = RelayClient::new(test_relay(&mock_server.uri()));
+        let tx_hash = client.send_private_transaction("0x02f8", Some(100)).await.unwrap();
+
+        assert_eq!(tx_hash, "0xabcdef");
+    }
+
     #[tokio::test]
     async fn test_bundle_submission_error() {
         let mock_server = MockServer::start().await;
@@ -268,15 +911,7 @@ mod tests {
             .mount(&mock_server)
             .await;
 
-        let relay = BuilderRelay {
-            name: "test".to_string(),
-            relay_url: mock_server.uri(),
-            payment_address: Address::ZERO,
-            enabled: true,
-            timeout_seconds: 30,
-            max_retries: 3,
-            health_check_interval_seconds: 60,
-        };
+        let relay = test_relay(&mock_server.uri());
 
         let client = RelayClient::new(relay);
         let result = client
@@ -287,7 +922,7 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn test_health_check_success() {
+    async fn test_http_200_with_jsonrpc_error_maps_to_bundle_rejected() {
         let mock_server = MockServer::start().await;
 
         Mock::given(method("POST"))
@@ -295,21 +930,237 @@ mod tests {
             .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
                 "jsonrpc": "2.0",
                 "id": 1,
-                "result": "0x123456"
+                "error": {
+                    "code": -32000,
+                    "message": "bundle simulation failed"
+                }
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let client = RelayClient::new(test_relay(&mock_server.uri()));
+        let request = client.build_bundle_request(vec!["0x123".to_string()], Some(12345), None);
+        let result = client.send_bundle_request(&request).await;
+
+        match result {
+            Err(types::error::RelayError::BundleRejected { relay, reason }) => {
+                assert_eq!(relay, "test");
+                assert_eq!(reason, "bundle simulation failed");
+            }
+            other => panic!("expected BundleRejected, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_send_mev_bundle_posts_mev_send_bundle() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(wiremock::matchers::body_partial_json(serde_json::json!({
+                "method": "mev_sendBundle",
+                "params": [{ "version": "v0.1", "inclusion": { "block": "0x64", "maxBlock": "0x65" } }]
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "result": { "bundleHash": "0xmevhash" }
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let client = RelayClient::new(test_relay(&mock_server.uri()));
+        let request = types::MevBundleRequest::new(
+            1u64,
+            vec![types::MevBundleBodyItem::Tx { tx: "0x123".to_string(), can_revert: false }],
+            100,
+            Some(101),
+        );
+        let submission = client.send_mev_bundle(&request).await.unwrap();
+        assert_eq!(submission.bundle_hash, "0xmevhash");
+    }
+
+    #[tokio::test]
+    async fn test_get_bundle_stats_parses_flashbots_payload() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(wiremock::matchers::body_partial_json(serde_json::json!({
+                "method": "flashbots_getBundleStats",
+                "params": [{ "bundleHash": "0xabc", "blockNumber": "0x64" }]
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "result": {
+                    "isHighPriority": true,
+                    "isSimulated": true,
+                    "simulatedAt": "2024-01-01T12:00:01.250Z",
+                    "receivedAt": "2024-01-01T12:00:01Z",
+                    "consideredByBuildersAt": [
+                        { "pubkey": "0x81babe", "timestamp": "2024-01-01T12:00:02Z" }
+                    ],
+                    "sealedByBuildersAt": []
+                }
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let client = RelayClient::new(test_relay(&mock_server.uri()));
+        let stats = client.get_bundle_stats("0xabc".to_string(), 100).await.unwrap();
+        assert_eq!(stats.is_simulated, Some(true));
+        assert_eq!(stats.is_sent_to_miners, None);
+        assert_eq!(stats.simulated_at.unwrap().to_rfc3339(), "2024-01-01T12:00:01.250+00:00");
+        let considered = stats.considered_by_builders_at.unwrap();
+        assert_eq!(considered.len(), 1);
+        assert_eq!(considered[0].pubkey, "0x81babe");
+        assert_eq!(stats.sealed_by_builders_at, Some(vec![]));
+    }
+
+    #[test]
+    fn test_unknown_bundle_stats_are_empty() {
+        for raw in [
+            r#"{"jsonrpc":"2.0","id":1,"result":{}}"#,
+            r#"{"jsonrpc":"2.0","id":1,"result":null}"#,
+        ] {
+            assert_eq!(parse_bundle_stats_response("test", raw).unwrap(), BundleStats::default());
+        }
+
+        let raw = r#"{"jsonrpc":"2.0","id":1,"error":{"code":-32602,"message":"block number out of range"}}"#;
+        assert!(parse_bundle_stats_response("test", raw).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_oversized_relay_response_is_rejected() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .and(path("/"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "result": "0x1234567890abcdef",
+                "padding": "x".repeat(4096)
             })))
             .mount(&mock_server)
             .await;
 
         let relay = BuilderRelay {
-            name: "test".to_string(),
-            relay_url: mock_server.uri(),
-            status_url: None,
-            payment_address: Address::ZERO,
-            enabled: true,
-            timeout_seconds: 30,
-            max_retries: 3,
-            health_check_interval_seconds: 60,
+            max_relay_response_bytes: 1024,
+            ..test_relay(&mock_server.uri())
         };
+        let client = RelayClient::new(relay);
+        let request = client.build_bundle_request(vec!["0x123".to_string()], Some(12345), None);
+
+        match client.send_bundle_request(&request).await {
+            Err(types::error::RelayError::InvalidResponse { relay, message }) => {
+                assert_eq!(relay, "test");
+                assert_eq!(message, "response body exceeds 1024 bytes");
+            }
+            other => panic!("expected InvalidResponse, got {:?}", other),
+        }
+
+        // The same response fits under the default cap
+        let client = RelayClient::new(test_relay(&mock_server.uri()));
+        assert_eq!(
+            client.submit_bundle(vec!["0x123".to_string()], Some(12345)).await.unwrap(),
+            "0x1234567890abcdef"
+        );
+    }
+
+    #[test]
+    fn test_relay_submission_retains_extra_result_fields() {
+        let raw = r#"{"jsonrpc":"2.0","id":1,"result":{"bundleHash":"0xabc","smart":true,"simulation":{"gasUsed":42000}}}"#;
+
+        let submission = parse_relay_submission("custom", raw, None).unwrap();
+        assert_eq!(submission.bundle_hash, "0xabc");
+        assert_eq!(submission.raw_result["smart"], true);
+        assert_eq!(submission.raw_result["simulation"]["gasUsed"], 42000);
+
+        let plain = parse_relay_submission("custom", r#"{"jsonrpc":"2.0","id":1,"result":"0xdef"}"#, None).unwrap();
+        assert_eq!(plain.raw_result, serde_json::json!("0xdef"));
+    }
+
+    #[test]
+    fn test_nonstandard_error_body_maps_to_bundle_rejected() {
+        let raw = r#"{"id":1,"error":"insufficient builder payment"}"#;
+
+        let result = parse_bundle_submit_response("custom", raw, None);
+        assert!(matches!(
+            result,
+            Err(types::error::RelayError::BundleRejected { ref reason, .. }) if reason == "insufficient builder payment"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_gzip_encoded_response_is_decoded() {
+        // gzip of {"jsonrpc":"2.0","id":1,"result":"0xfeedbeef"}
+        const GZIPPED_RESPONSE: [u8; 65] = [
+            0x1f, 0x8b, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0xff, 0xab, 0x56, 0xca, 0x2a, 0xce,
+            0xcf, 0x2b, 0x2a, 0x48, 0x56, 0xb2, 0x52, 0x32, 0xd2, 0x33, 0x50, 0xd2, 0x51, 0xca, 0x4c,
+            0x51, 0xb2, 0x32, 0xd4, 0x51, 0x2a, 0x4a, 0x2d, 0x2e, 0xcd, 0x29, 0x01, 0x8a, 0x1a, 0x54,
+            0xa4, 0xa5, 0xa6, 0xa6, 0x24, 0xa5, 0xa6, 0xa6, 0x29, 0xd5, 0x02, 0x00, 0x7a, 0x20, 0x3f,
+            0xb6, 0x2e, 0x00, 0x00, 0x00,
+        ];
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .and(wiremock::matchers::header_regex("accept-encoding", "gzip"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("content-encoding", "gzip")
+                    .set_body_raw(GZIPPED_RESPONSE.to_vec(), "application/json"),
+            )
+            .mount(&mock_server)
+            .await;
+
+        let client = RelayClient::new(test_relay(&mock_server.uri()));
+        let result = client.submit_bundle(vec!["0x123".to_string()], None).await;
+
+        assert_eq!(result.unwrap(), "0xfeedbeef");
+    }
+
+    #[test]
+    fn test_well_known_relay_errors_are_classified() {
+        use types::error::RelayError;
+
+        let parse = |code: i64, message: &str| {
+            let raw = serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "error": { "code": code, "message": message }
+            })
+            .to_string();
+            parse_bundle_submit_response("test", &raw, None).unwrap_err()
+        };
+
+        assert!(matches!(parse(-32000, "nonce too low: next nonce 5, tx nonce 4"), RelayError::NonceTooLow { .. }));
+        assert!(matches!(parse(-32000, "already known"), RelayError::AlreadyKnown { .. }));
+        assert!(matches!(
+            parse(-32000, "insufficient funds for gas * price + value"),
+            RelayError::InsufficientFunds { .. }
+        ));
+        assert!(matches!(parse(-32005, "limit exceeded"), RelayError::RateLimited { .. }));
+        assert!(matches!(parse(-32000, "bundle simulation failed"), RelayError::BundleRejected { .. }));
+
+        assert!(is_retryable(&parse(-32005, "limit exceeded")));
+        assert!(!is_retryable(&parse(-32000, "nonce too low")));
+    }
+
+    #[tokio::test]
+    async fn test_health_check_success() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .and(path("/"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "result": "0x123456"
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let relay = test_relay(&mock_server.uri());
 
         let client = RelayClient::new(relay);
         let result = client.health_check().await;
@@ -317,4 +1168,380 @@ mod tests {
         assert!(result.is_ok());
         assert!(result.unwrap().as_millis() > 0);
     }
+
+    #[test]
+    fn test_custom_response_shape_parsed_via_result_path() {
+        let raw = r#"{"jsonrpc":"2.0","id":1,"result":{"data":{"bundle":{"id":"0xfeedbeef"}}}}"#;
+
+        let hash = parse_bundle_submit_response("custom", raw, Some("/result/data/bundle/id")).unwrap();
+        assert_eq!(hash, "0xfeedbeef");
+
+        // Without the pointer the default heuristics cannot find the hash
+        assert!(parse_bundle_submit_response("custom", raw, None).is_err());
+    }
+
+    #[test]
+    fn test_unmatched_result_path_falls_back_to_default_parsing() {
+        let raw = r#"{"jsonrpc":"2.0","id":1,"result":"0x1234"}"#;
+
+        let hash = parse_bundle_submit_response("custom", raw, Some("/missing")).unwrap();
+        assert_eq!(hash, "0x1234");
+    }
+
+    #[test]
+    fn test_bundle_uuid_serialized_only_when_enabled() {
+        let replacement_uuid = Uuid::new_v4();
+
+        let mut relay = test_relay("https://relay.example.com");
+        relay.send_bundle_uuid = true;
+        let request = RelayClient::new(relay).build_bundle_request(
+            vec!["0x123".to_string()],
+            None,
+            Some(replacement_uuid),
+        );
+        let json = serde_json::to_value(&request).unwrap();
+        assert_eq!(json["params"][0]["uuid"], replacement_uuid.to_string());
+
+        let request = RelayClient::new(test_relay("https://relay.example.com")).build_bundle_request(
+            vec!["0x123".to_string()],
+            None,
+            Some(replacement_uuid),
+        );
+        let json = serde_json::to_value(&request).unwrap();
+        assert!(json["params"][0].get("uuid").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_http2_prior_knowledge_against_h2_capable_server() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "result": "0xfeedbeef"
+            })))
+            .mount(&mock_server)
+            .await;
+
+        // The mock server speaks cleartext HTTP/2 alongside HTTP/1.1; a prior-knowledge client
+        // sends the HTTP/2 preface straight away, so success means HTTP/2 was used
+        let mut relay = test_relay(&mock_server.uri());
+        relay.http_version = RelayHttpVersion::Http2;
+        let result = RelayClient::new(relay).submit_bundle(vec!["0x123".to_string()], None).await;
+
+        assert_eq!(result.unwrap(), "0xfeedbeef");
+    }
+
+    #[tokio::test]
+    async fn test_requests_carry_verifiable_signature_header() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "result": "0x1234"
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let signer = PrivateKeySigner::from_str(
+            "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80",
+        )
+        .unwrap();
+        let relay = BuilderRelay {
+            signature_scheme: types::RelaySignatureScheme::RawKeccak,
+            ..test_relay(&mock_server.uri())
+        };
+        let client = RelayClient::new(relay).with_signer(signer.clone());
+        client.submit_bundle(vec!["0x1234".to_string()], Some(1)).await.unwrap();
+
+        let requests = mock_server.received_requests().await.unwrap();
+        let header = requests[0].headers.get(SIGNATURE_HEADER).unwrap().to_str().unwrap();
+        assert_eq!(
+            crate::signing::recover_request_signer(
+                types::RelaySignatureScheme::RawKeccak,
+                &requests[0].body,
+                header
+            ),
+            Some(signer.address())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_health_check_is_signed() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "result": "0x10"
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let signer = PrivateKeySigner::from_str(
+            "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80",
+        )
+        .unwrap();
+        let relay = test_relay(&mock_server.uri());
+        let scheme = relay.signature_scheme;
+        let client = RelayClient::new(relay).with_signer(signer.clone());
+        client.health_check().await.unwrap();
+
+        let requests = mock_server.received_requests().await.unwrap();
+        let header = requests[0].headers.get(SIGNATURE_HEADER).unwrap().to_str().unwrap();
+        assert_eq!(
+            crate::signing::recover_request_signer(scheme, &requests[0].body, header),
+            Some(signer.address())
+        );
+    }
+
+    #[test]
+    fn test_pool_settings_follow_relay_config() {
+        let mut relay = test_relay("https://relay.example.com");
+        relay.pool_max_idle_per_host = 64;
+        relay.pool_idle_timeout_seconds = 30;
+        relay.tcp_keepalive_seconds = 0;
+
+        assert_eq!(
+            PoolSettings::from_relay(&relay),
+            PoolSettings {
+                max_idle_per_host: 64,
+                idle_timeout: Some(Duration::from_secs(30)),
+                tcp_keepalive: None,
+            }
+        );
+    }
+
+    #[test]
+    fn test_retry_backoff_never_exceeds_cap() {
+        let max_backoff_ms = 2_000;
+
+        assert_eq!(retry_backoff(0, 100, max_backoff_ms), Duration::from_millis(100));
+        assert_eq!(retry_backoff(3, 100, max_backoff_ms), Duration::from_millis(800));
+
+        for attempt in 0..100 {
+            assert!(retry_backoff(attempt, 100, max_backoff_ms) <= Duration::from_millis(max_backoff_ms));
+        }
+        assert_eq!(retry_backoff(99, 100, max_backoff_ms), Duration::from_millis(max_backoff_ms));
+    }
+
+    #[tokio::test]
+    async fn test_transient_5xx_is_retried_until_success() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(503))
+            .up_to_n_times(2)
+            .expect(2)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "result": "0x1234"
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let client = RelayClient::new(test_relay(&mock_server.uri()));
+        assert_eq!(client.submit_bundle(vec!["0x123".to_string()], Some(1)).await.unwrap(), "0x1234");
+    }
+
+    #[tokio::test]
+    async fn test_bundle_rejection_is_not_retried() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "error": { "code": -32000, "message": "bundle simulation failed" }
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let client = RelayClient::new(test_relay(&mock_server.uri()));
+        assert!(client.submit_bundle(vec!["0x123".to_string()], Some(1)).await.is_err());
+    }
+
+    #[test]
+    fn test_mismatched_response_header_is_reported() {
+        let expected = std::collections::HashMap::from([("x-relay-id".to_string(), "flashbots".to_string())]);
+
+        let mut headers = reqwest::header::HeaderMap::new();
+        headers.insert("x-relay-id", "flashbots".parse().unwrap());
+        assert!(header_mismatches(&expected, &headers).is_empty());
+
+        headers.insert("x-relay-id", "evil".parse().unwrap());
+        assert_eq!(header_mismatches(&expected, &headers).len(), 1);
+
+        assert_eq!(header_mismatches(&expected, &reqwest::header::HeaderMap::new()).len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_strict_header_mismatch_fails_submission() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("x-relay-id", "evil")
+                    .set_body_json(serde_json::json!({ "jsonrpc": "2.0", "id": 1, "result": "0x1234" })),
+            )
+            .mount(&mock_server)
+            .await;
+
+        let mut relay = test_relay(&mock_server.uri());
+        relay.expected_response_headers.insert("x-relay-id".to_string(), "flashbots".to_string());
+
+        // Non-strict: mismatch is only logged
+        let client = RelayClient::new(relay.clone());
+        assert_eq!(client.submit_bundle(vec!["0x123".to_string()], None).await.unwrap(), "0x1234");
+
+        relay.strict_response_headers = true;
+        let client = RelayClient::new(relay);
+        assert!(client.submit_bundle(vec!["0x123".to_string()], None).await.is_err());
+    }
+
+    /// Answers a single eth_sendBundle with `0xhash<blockNumber>`
+    struct BlockHashResponder;
+
+    impl wiremock::Respond for BlockHashResponder {
+        fn respond(&self, request: &wiremock::Request) -> ResponseTemplate {
+            let req: Value = serde_json::from_slice(&request.body).unwrap();
+            let block = req["params"][0]["blockNumber"].as_str().unwrap();
+            ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": req["id"],
+                "result": format!("0xhash{}", block.trim_start_matches("0x"))
+            }))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_submit_for_blocks_sends_one_request_per_block() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(BlockHashResponder)
+            .expect(3)
+            .mount(&mock_server)
+            .await;
+
+        let client = RelayClient::new(test_relay(&mock_server.uri()));
+        let results = client
+            .submit_bundle_for_blocks(vec!["0x123".to_string()], [0x12, 0x10, 0x11])
+            .await;
+
+        let parsed: Vec<(u64, String)> = results
+            .into_iter()
+            .map(|(block, result)| (block, result.unwrap()))
+            .collect();
+        assert_eq!(
+            parsed,
+            vec![
+                (0x10, "0xhash10".to_string()),
+                (0x11, "0xhash11".to_string()),
+                (0x12, "0xhash12".to_string()),
+            ]
+        );
+
+        let requests: Vec<Value> = mock_server
+            .received_requests()
+            .await
+            .unwrap()
+            .iter()
+            .map(|r| serde_json::from_slice(&r.body).unwrap())
+            .collect();
+        let ids: std::collections::HashSet<String> = requests.iter().map(|r| r["id"].to_string()).collect();
+        assert_eq!(ids.len(), 3);
+        assert!(requests.iter().all(|r| r["method"] == "eth_sendBundle"));
+    }
+
+    #[tokio::test]
+    async fn test_each_target_block_gets_its_own_replacement_uuid() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(BlockHashResponder)
+            .expect(2)
+            .mount(&mock_server)
+            .await;
+
+        let relay = BuilderRelay { send_bundle_uuid: true, ..test_relay(&mock_server.uri()) };
+        let bundle_id = Uuid::new_v4();
+        let mut txs = vec!["0x123".to_string()];
+        let results = RelayClient::new(relay)
+            .submit_bundle_for_targets(&mut txs, [Some(0x10), Some(0x11)], Some(bundle_id), |_| async { None::<Vec<String>> })
+            .await;
+        assert!(results.iter().all(|(_, result)| result.is_ok()));
+
+        let mut uuids: Vec<String> = mock_server
+            .received_requests()
+            .await
+            .unwrap()
+            .iter()
+            .map(|r| serde_json::from_slice::<Value>(&r.body).unwrap()["params"][0]["uuid"].as_str().unwrap().to_string())
+            .collect();
+        uuids.sort();
+        let mut expected = vec![
+            block_replacement_uuid(bundle_id, Some(0x10)).to_string(),
+            block_replacement_uuid(bundle_id, Some(0x11)).to_string(),
+        ];
+        expected.sort();
+        assert_eq!(uuids, expected);
+        assert_ne!(uuids[0], uuids[1]);
+        assert_eq!(block_replacement_uuid(bundle_id, None), bundle_id);
+    }
+
+    /// Answers a JSON-RPC batch with `0xhash<blockNumber>` per request, in reverse order
+    struct BatchResponder;
+
+    impl wiremock::Respond for BatchResponder {
+        fn respond(&self, request: &wiremock::Request) -> ResponseTemplate {
+            let batch: Vec<Value> = serde_json::from_slice(&request.body).unwrap();
+            let responses: Vec<Value> = batch
+                .iter()
+                .rev()
+                .map(|req| {
+                    let block = req["params"][0]["blockNumber"].as_str().unwrap();
+                    serde_json::json!({
+                        "jsonrpc": "2.0",
+                        "id": req["id"],
+                        "result": format!("0xhash{}", block.trim_start_matches("0x"))
+                    })
+                })
+                .collect();
+            ResponseTemplate::new(200).set_body_json(responses)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_batched_submission_parses_each_block_result() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(BatchResponder)
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let client = RelayClient::new(test_relay(&mock_server.uri()));
+        let results = client
+            .submit_bundle_batch(vec!["0x123".to_string()], vec![0x10, 0x11, 0x12])
+            .await
+            .unwrap();
+
+        let parsed: Vec<(u64, String)> = results
+            .into_iter()
+            .map(|(block, result)| (block, result.unwrap()))
+            .collect();
+        assert_eq!(
+            parsed,
+            vec![
+                (0x10, "0xhash10".to_string()),
+                (0x11, "0xhash11".to_string()),
+                (0x12, "0xhash12".to_string()),
+            ]
+        );
+    }
 }
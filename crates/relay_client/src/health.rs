@@ -27,14 +27,67 @@ impl RelayHealthMonitor {
         &self.relays
     }
 
-    /// Update health status for a relay
-    pub fn update_health(&mut self, relay_name: &str, _health: RelayHealth, response_time: Option<Duration>) {
-        if let Some(check) = self.relays.iter_mut().find(|r| r.name == relay_name) {
-            if let Some(duration) = response_time {
-                check.mark_healthy(duration.as_millis() as u64);
-            } else {
-                check.mark_unhealthy("No response".to_string());
-            }
+    /// Record a health check: its response time, or the error if it failed.
+    ///
+    /// Returns the previous and new status when the relay's status changed.
+    pub fn update_health(
+        &mut self,
+        relay_name: &str,
+        outcome: std::result::Result<Duration, String>,
+    ) -> Option<(RelayHealth, RelayHealth)> {
+        let check = self.relays.iter_mut().find(|r| r.name == relay_name)?;
+        let previous = check.status.clone();
+        match outcome {
+            Ok(duration) => check.mark_healthy(duration.as_millis() as u64),
+            Err(error) => check.mark_failed(error),
         }
+        (check.status != previous).then(|| (previous, check.status.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_update_health_reports_status_changes() {
+        let relay = BuilderRelay { name: "flashbots".to_string(), ..Default::default() };
+        let mut monitor = RelayHealthMonitor::new(vec![relay]);
+
+        let healthy = Ok(Duration::from_millis(5));
+        assert_eq!(
+            monitor.update_health("flashbots", healthy.clone()),
+            Some((RelayHealth::Unknown, RelayHealth::Healthy))
+        );
+        assert_eq!(monitor.update_health("flashbots", healthy), None);
+        assert_eq!(
+            monitor.update_health("flashbots", Err("timeout".to_string())),
+            Some((RelayHealth::Healthy, RelayHealth::Degraded))
+        );
+        assert_eq!(monitor.update_health("unknown-relay", Err("timeout".to_string())), None);
+    }
+
+    #[test]
+    fn test_failures_degrade_then_recover() {
+        let relay = BuilderRelay { name: "flashbots".to_string(), ..Default::default() };
+        let mut monitor = RelayHealthMonitor::new(vec![relay]);
+        let status = |monitor: &RelayHealthMonitor| monitor.get_all_health()[0].status.clone();
+
+        monitor.update_health("flashbots", Ok(Duration::from_millis(5)));
+        assert_eq!(status(&monitor), RelayHealth::Healthy);
+
+        monitor.update_health("flashbots", Err("timeout".to_string()));
+        assert_eq!(status(&monitor), RelayHealth::Degraded);
+        monitor.update_health("flashbots", Err("timeout".to_string()));
+        assert_eq!(status(&monitor), RelayHealth::Degraded);
+        monitor.update_health("flashbots", Err("timeout".to_string()));
+        assert_eq!(status(&monitor), RelayHealth::Unhealthy);
+        assert_eq!(monitor.get_all_health()[0].consecutive_failures, 3);
+        assert_eq!(monitor.get_all_health()[0].error_message.as_deref(), Some("timeout"));
+
+        monitor.update_health("flashbots", Ok(Duration::from_millis(7)));
+        assert_eq!(status(&monitor), RelayHealth::Healthy);
+        assert_eq!(monitor.get_all_health()[0].consecutive_failures, 0);
+        assert_eq!(monitor.get_all_health()[0].response_time_ms, Some(7));
     }
 }
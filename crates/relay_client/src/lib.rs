@@ -6,6 +6,7 @@
 pub mod client;
 pub mod health;
 pub mod manager;
+pub mod signing;
 
 pub use client::*;
 pub use health::*;
@@ -1,8 +1,8 @@
 //! Relay manager for coordinating multiple relays
 
 use crate::{RelayClient, RelayHealthMonitor};
-use std::collections::HashMap;
-use types::{BuilderRelay, Result};
+use std::collections::{HashMap, HashSet};
+use types::{BuilderRelay, ConfigError, Result};
 
 /// Manager for multiple relay clients
 #[derive(Debug)]
@@ -12,8 +12,19 @@ pub struct RelayManager {
 }
 
 impl RelayManager {
-    /// Create a new relay manager
-    pub fn new(relays: Vec<BuilderRelay>) -> Self {
+    /// Create a new relay manager, rejecting relays that share a name
+    pub fn new(relays: Vec<BuilderRelay>) -> Result<Self> {
+        let mut names = HashSet::new();
+        for relay in &relays {
+            if !names.insert(relay.name.as_str()) {
+                return Err(ConfigError::ValidationError {
+                    field: "builders".to_string(),
+                    message: format!("Duplicate builder name: {}", relay.name),
+                }
+                .into());
+            }
+        }
+
         let mut clients = HashMap::new();
         
         for relay in &relays {
@@ -24,10 +35,10 @@ impl RelayManager {
 
         let health_monitor = RelayHealthMonitor::new(relays);
 
-        Self {
+        Ok(Self {
             clients,
             health_monitor,
-        }
+        })
     }
 
     /// Submit bundle to all enabled relays
@@ -61,3 +72,19 @@ impl RelayManager {
         &self.health_monitor
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_duplicate_relay_names_are_rejected() {
+        let relay = |name: &str| BuilderRelay { name: name.to_string(), ..Default::default() };
+
+        let manager = RelayManager::new(vec![relay("flashbots"), relay("titan")]).unwrap();
+        assert_eq!(manager.relay_names().len(), 2);
+
+        let err = RelayManager::new(vec![relay("flashbots"), relay("titan"), relay("flashbots")]).unwrap_err();
+        assert!(err.to_string().contains("Duplicate builder name: flashbots"));
+    }
+}
@@ -5,10 +5,10 @@ use alloy::rpc::types::Transaction;
 use async_trait::async_trait;
 use types::Result;
 use alloy::consensus::TxEnvelope;
-use alloy::rlp::Decodable;
+use alloy::eips::eip2718::Decodable2718;
 use alloy::providers::{Provider, ProviderBuilder};
 use alloy::rpc::types::{TransactionInput, TransactionRequest};
-use alloy::primitives::{Bytes, TxKind, U256};
+use alloy::primitives::{Address, Bytes, TxKind, U256};
 use alloy::consensus::Transaction as ConsensusTransaction;
 
 /// Stub simulation engine for development
@@ -71,16 +71,73 @@ impl Default for StubSimulationEngine {
     }
 }
 
+/// Decode a raw signed transaction hex (EIP-2718 encoded) into a transaction envelope.
+pub fn decode_raw_transaction(raw_tx_hex: &str) -> Result<TxEnvelope> {
+    let raw = raw_tx_hex.trim().trim_start_matches("0x");
+    let bytes = alloy::hex::decode(raw)
+        .map_err(|e| types::AtomicBundlerError::Internal(format!("invalid transaction hex: {}", e)))?;
+
+    TxEnvelope::decode_2718(&mut bytes.as_slice())
+        .map_err(|e| types::AtomicBundlerError::Internal(format!("failed to decode transaction: {}", e)))
+}
+
+/// Recover the sender address of a signed transaction envelope.
+pub fn recover_sender(envelope: &TxEnvelope) -> Result<Address> {
+    envelope
+        .signature()
+        .recover_address_from_prehash(&envelope.signature_hash())
+        .map_err(|e| types::AtomicBundlerError::Internal(format!("failed to recover sender: {}", e)))
+}
+
 /// Estimate gas for a raw signed transaction hex by decoding it and calling eth_estimateGas.
 pub async fn estimate_gas_from_raw(rpc_url: &str, raw_tx_hex: &str) -> Result<u64> {
-    let raw = raw_tx_hex.trim_start_matches("0x");
-    let mut bytes = alloy::hex::decode(raw)
-        .map_err(|e| types::AtomicBundlerError::Internal(format!("invalid tx1 hex: {}", e)))?;
+    let envelope = decode_raw_transaction(raw_tx_hex)?;
+
+    let req = transaction_request(&envelope);
+
+    let provider = ProviderBuilder::new()
+        .on_http(rpc_url.parse().map_err(|_| types::AtomicBundlerError::Internal("Invalid RPC URL".to_string()))?);
+
+    let gas = provider
+        .estimate_gas(&req)
+        .await
+        .map_err(|e| types::AtomicBundlerError::Internal(format!("eth_estimateGas failed: {}", e)))?;
+
+    Ok(gas.try_into().unwrap_or(21_000u64))
+}
+
+/// Execute a raw signed transaction with `eth_simulateV1` on top of the latest block
+/// and return the gas it actually used
+pub async fn simulate_gas_used_from_raw(rpc_url: &str, raw_tx_hex: &str) -> Result<u64> {
+    let envelope = decode_raw_transaction(raw_tx_hex)?;
+    let mut req = transaction_request(&envelope);
+    // Execution depends on the sender, so recover it regardless of the `k256` feature
+    req.from = Some(recover_sender(&envelope)?);
+
+    let provider = ProviderBuilder::new()
+        .on_http(rpc_url.parse().map_err(|_| types::AtomicBundlerError::Internal("Invalid RPC URL".to_string()))?);
 
-    let envelope = TxEnvelope::decode(&mut bytes.as_slice())
-        .map_err(|e| types::AtomicBundlerError::Internal(format!("failed to decode tx1: {}", e)))?;
+    let payload = serde_json::json!({ "blockStateCalls": [{ "calls": [req] }] });
+    let blocks: serde_json::Value = provider
+        .raw_request("eth_simulateV1".into(), (payload, "latest"))
+        .await
+        .map_err(|e| types::AtomicBundlerError::Internal(format!("eth_simulateV1 failed: {}", e)))?;
+
+    let call = &blocks[0]["calls"][0];
+    if call["status"] == "0x0" {
+        return Err(types::AtomicBundlerError::Internal(format!(
+            "simulated transaction reverted: {}",
+            call["error"]["message"].as_str().unwrap_or("unknown error")
+        )));
+    }
+    call["gasUsed"]
+        .as_str()
+        .and_then(|gas| u64::from_str_radix(gas.trim_start_matches("0x"), 16).ok())
+        .ok_or_else(|| types::AtomicBundlerError::Internal("eth_simulateV1 returned no gasUsed".to_string()))
+}
 
-    // Build TransactionRequest from as many fields as possible
+/// Build a call request carrying as many of a signed transaction's fields as possible
+fn transaction_request(envelope: &TxEnvelope) -> TransactionRequest {
     let mut req = TransactionRequest::default();
 
     match envelope.to() {
@@ -152,13 +209,5 @@ pub async fn estimate_gas_from_raw(rpc_url: &str, raw_tx_hex: &str) -> Result<u6
     // Trim conflicting keys based on preferred type
     req.trim_conflicting_keys();
 
-    let provider = ProviderBuilder::new()
-        .on_http(rpc_url.parse().map_err(|_| types::AtomicBundlerError::Internal("Invalid RPC URL".to_string()))?);
-
-    let gas = provider
-        .estimate_gas(&req)
-        .await
-        .map_err(|e| types::AtomicBundlerError::Internal(format!("eth_estimateGas failed: {}", e)))?;
-
-    Ok(gas.try_into().unwrap_or(21_000u64))
+    req
 }
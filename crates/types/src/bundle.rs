@@ -6,8 +6,8 @@ use alloy::{
 };
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::time::Duration;
 use uuid::Uuid;
-use crate::payment::PaymentFormula;
 
 /// Unique identifier for a bundle
 pub type BundleId = Uuid;
@@ -67,6 +67,9 @@ pub struct BundleRequest {
     /// Optional single target block number for inclusion
     #[serde(default)]
     pub target_block: Option<u64>,
+    /// Optional pre-signed payment transaction; when present it is used instead of forging tx2
+    #[serde(default)]
+    pub tx2: Option<Bytes>,
 }
 
 /// Payment configuration for a bundle
@@ -74,8 +77,8 @@ pub struct BundleRequest {
 pub struct PaymentRequest {
     /// Payment mode (direct, permit, escrow)
     pub mode: String,
-    /// Payment formula (flat, gas, basefee)
-    pub formula: PaymentFormula,
+    /// Payment formula (flat, gas, basefee); unknown values are resolved against `payment.formula`
+    pub formula: String,
     /// Maximum payment amount in wei
     #[serde(rename = "maxAmountWei")]
     pub max_amount_wei: String,
@@ -186,6 +189,16 @@ impl Bundle {
         Utc::now() > self.expires_at
     }
 
+    /// Remaining lifetime of the bundle, zero once it has expired
+    pub fn time_to_expiry(&self) -> Duration {
+        self.time_to_expiry_at(Utc::now())
+    }
+
+    /// Remaining lifetime of the bundle as of `now`, zero once it has expired
+    pub fn time_to_expiry_at(&self, now: DateTime<Utc>) -> Duration {
+        crate::utils::time_to_expiry(self.expires_at, now)
+    }
+
     /// Update the bundle state
     pub fn update_state(&mut self, new_state: BundleState) {
         self.state = new_state;
@@ -213,3 +226,24 @@ impl Default for BundleState {
         BundleState::Queued
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bundle_expiring_at(expires_at: DateTime<Utc>) -> Bundle {
+        Bundle::new(Transaction::default(), U256::ZERO, vec![], expires_at)
+    }
+
+    #[test]
+    fn test_time_to_expiry() {
+        let now = DateTime::parse_from_rfc3339("2024-01-01T12:00:00Z").unwrap().with_timezone(&Utc);
+        let bundle = bundle_expiring_at(now + chrono::Duration::seconds(90));
+
+        assert_eq!(bundle.time_to_expiry_at(now), Duration::from_secs(90));
+        assert_eq!(
+            bundle.time_to_expiry_at(now + chrono::Duration::seconds(120)),
+            Duration::ZERO
+        );
+    }
+}
@@ -62,6 +62,19 @@ pub enum AtomicBundlerError {
     ExternalService { service: String, message: String },
 }
 
+/// Message prefix of relay errors converted from [`RelayError::NonceTooLow`]
+const NONCE_TOO_LOW_PREFIX: &str = "Nonce too low";
+
+impl AtomicBundlerError {
+    /// Whether this is a relay's nonce-too-low rejection
+    pub fn is_nonce_too_low(&self) -> bool {
+        matches!(
+            self,
+            AtomicBundlerError::RelayCommunication { message, .. } if message.starts_with(NONCE_TOO_LOW_PREFIX)
+        )
+    }
+}
+
 /// Result type alias for atomic bundler operations
 pub type Result<T> = std::result::Result<T, AtomicBundlerError>;
 
@@ -147,6 +160,40 @@ pub enum RelayError {
     /// Rate limited by relay
     #[error("Rate limited by relay: {relay}")]
     RateLimited { relay: String },
+
+    /// A bundle transaction's nonce has already been used
+    #[error("Nonce too low reported by relay {relay}: {message}")]
+    NonceTooLow { relay: String, message: String },
+
+    /// The relay has already seen this bundle or transaction
+    #[error("Bundle already known to relay: {relay}")]
+    AlreadyKnown { relay: String },
+
+    /// A bundle transaction's sender cannot cover value plus gas
+    #[error("Insufficient funds reported by relay {relay}: {message}")]
+    InsufficientFunds { relay: String, message: String },
+}
+
+impl RelayError {
+    /// Classify a JSON-RPC error returned by a relay.
+    ///
+    /// Relays mostly use the generic -32000 code, so well-known geth/builder messages are
+    /// matched as well; anything unrecognised becomes `BundleRejected`.
+    pub fn from_rpc_error(relay: &str, code: i64, message: &str) -> Self {
+        let relay = relay.to_string();
+        let lower = message.to_lowercase();
+        if code == -32005 || lower.contains("rate limit") || lower.contains("too many requests") {
+            RelayError::RateLimited { relay }
+        } else if lower.contains("nonce too low") {
+            RelayError::NonceTooLow { relay, message: message.to_string() }
+        } else if lower.contains("already known") || lower.contains("known transaction") {
+            RelayError::AlreadyKnown { relay }
+        } else if lower.contains("insufficient funds") {
+            RelayError::InsufficientFunds { relay, message: message.to_string() }
+        } else {
+            RelayError::BundleRejected { relay, reason: message.to_string() }
+        }
+    }
 }
 
 /// Database specific errors
@@ -243,6 +290,18 @@ impl From<RelayError> for AtomicBundlerError {
                 relay,
                 message: "Rate limited".to_string(),
             },
+            RelayError::NonceTooLow { relay, message } => AtomicBundlerError::RelayCommunication {
+                relay,
+                message: format!("{}: {}", NONCE_TOO_LOW_PREFIX, message),
+            },
+            RelayError::AlreadyKnown { relay } => AtomicBundlerError::RelayCommunication {
+                relay,
+                message: "Bundle already known".to_string(),
+            },
+            RelayError::InsufficientFunds { relay, message } => AtomicBundlerError::RelayCommunication {
+                relay,
+                message: format!("Insufficient funds: {}", message),
+            },
         }
     }
 }
@@ -13,4 +13,4 @@ pub mod utils;
 pub use bundle::*;
 pub use error::{AtomicBundlerError, Result, TransactionError, PaymentError, DatabaseError, ConfigError};
 pub use payment::*;
-pub use relay::{BuilderRelay, RelayBundleRequest, RelayBundleResponse, RelayHealth, RelayHealthCheck, RelayError, RelayResult};
+pub use relay::{BuilderRelay, BuilderTimestamp, BundleStats, JsonRpcId, MevBundleBodyItem, MevBundleInclusion, MevBundleParams, MevBundlePrivacy, MevBundleRefund, MevBundleRefundConfig, MevBundleRequest, MevBundleValidity, MevShareHints, RelayBundleRequest, RelayBundleResponse, RelayHealth, RelayHealthCheck, RelayError, RelayHttpVersion, RelayResult, RelaySignatureScheme, RelaySubmission, RequestIdFormat, UNHEALTHY_AFTER_FAILURES};
@@ -3,6 +3,8 @@
 use alloy::primitives::{Address, U256};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use crate::error::PaymentError;
 
 /// Payment formula types
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -14,6 +16,8 @@ pub enum PaymentFormula {
     Gas,
     /// Base fee-based payment: payment = k1 * gas_used * (base_fee + tip) + k2
     Basefee,
+    /// Percentage of the gas cost: payment = k1 * gas_used * base_fee, with k1 a fraction
+    Percentage,
 }
 
 /// Payment mode types
@@ -22,12 +26,34 @@ pub enum PaymentFormula {
 pub enum PaymentMode {
     /// Direct ETH transfer to builder
     Direct,
-    /// ERC-20 permit-based payment (future)
+    /// ERC-20 token transfer, forged by `PaymentTransactionForger::forge_permit_transfer_hex`
     Permit,
     /// Escrow-based payment (future)
     Escrow,
 }
 
+/// How a request's payment is shared among the builders it is sent to
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum PaymentSplit {
+    /// Every builder is offered the full payment
+    #[default]
+    Full,
+    /// The payment is divided equally among the enabled builders
+    Proportional,
+}
+
+/// Transaction type tx2 is forged as
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Tx2Type {
+    /// EIP-1559 transaction with max and priority fees
+    #[default]
+    Eip1559,
+    /// Legacy (type-0) transaction with a single gas price, for chains without EIP-1559
+    Legacy,
+}
+
 /// Payment configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PaymentConfig {
@@ -35,14 +61,84 @@ pub struct PaymentConfig {
     pub formula: PaymentFormula,
     /// Multiplier coefficient (used for gas and basefee formulas)
     pub k1: f64,
-    /// Base amount in wei
+    /// Base amount in wei (raw wei or with a unit, e.g. `"0.0002 eth"`)
+    #[serde(deserialize_with = "crate::utils::deserialize_wei_amount")]
     pub k2: U256,
     /// Maximum payment amount in wei
+    #[serde(deserialize_with = "crate::utils::deserialize_wei_amount")]
     pub max_amount_wei: U256,
     /// Per-bundle payment cap in wei
+    #[serde(deserialize_with = "crate::utils::deserialize_wei_amount")]
     pub per_bundle_cap_wei: U256,
     /// Daily spending cap in wei
+    #[serde(deserialize_with = "crate::utils::deserialize_wei_amount")]
     pub daily_cap_wei: U256,
+    /// Skip the pre-submission signer balance check (for sponsored/relayer setups)
+    #[serde(default)]
+    pub skip_balance_check: bool,
+    /// Multiplier applied to the network's suggested priority fee for tx2 (0 = no tip)
+    #[serde(default)]
+    pub tx2_priority_fee_multiplier: f64,
+    /// Gas limit for tx2 when the payment address is a contract (EOAs use 21000)
+    #[serde(default = "default_tx2_contract_gas_limit")]
+    pub tx2_contract_gas_limit: u64,
+    /// Multiplier applied to the `eth_feeHistory` suggested max fee for tx2 (0 = derive from base fee)
+    #[serde(default)]
+    pub tx2_max_fee_multiplier: f64,
+    /// Lowest max fee per gas tx2 is forged with, in gwei (0 = no floor)
+    #[serde(default)]
+    pub tx2_min_max_fee_gwei: f64,
+    /// Transaction type of tx2; legacy transactions pay the max fee as their gas price
+    #[serde(default)]
+    pub tx2_type: Tx2Type,
+    /// Additional tx2 signers: reference name -> environment variable holding the private key
+    #[serde(default)]
+    pub signers: HashMap<String, String>,
+    /// Recent blocks sampled for the low-payment warning (0 = disabled)
+    #[serde(default)]
+    pub low_payment_warning_blocks: u64,
+    /// Warn when the payment is below this fraction of the recent priority fees for the bundle's gas
+    #[serde(default = "default_low_payment_warning_ratio")]
+    pub low_payment_warning_ratio: f64,
+    /// Prefetch signer nonces at startup so the first submission skips the RPC round trip
+    #[serde(default = "default_warm_signer_nonces")]
+    pub warm_signer_nonces: bool,
+    /// Whether each builder is offered the full payment or an equal share of it
+    #[serde(default)]
+    pub split: PaymentSplit,
+    /// Reject requests with an unknown payment formula instead of falling back to `formula`
+    #[serde(default)]
+    pub strict_formula: bool,
+    /// Pay on tx1's gas used in an `eth_simulateV1` run rather than `eth_estimateGas`
+    #[serde(default)]
+    pub use_simulated_gas: bool,
+    /// Queue bundles whose signer balance is insufficient and retry them for up to this many
+    /// seconds after submission, instead of rejecting them (optional)
+    #[serde(default)]
+    pub balance_grace_seconds: Option<u64>,
+    /// Delay between retries of a bundle waiting for signer balance, in milliseconds
+    #[serde(default = "default_balance_retry_interval_ms")]
+    pub balance_retry_interval_ms: u64,
+    /// Forward tx1 alone via `eth_sendPrivateTransaction` when a request carries no payment
+    /// (no tx2 and a zero `maxAmountWei`) instead of forging a bundle
+    #[serde(default)]
+    pub private_tx_when_unpaid: bool,
+}
+
+fn default_tx2_contract_gas_limit() -> u64 {
+    50_000
+}
+
+fn default_balance_retry_interval_ms() -> u64 {
+    1_000
+}
+
+fn default_low_payment_warning_ratio() -> f64 {
+    0.5
+}
+
+fn default_warm_signer_nonces() -> bool {
+    true
 }
 
 /// Payment calculation parameters
@@ -63,6 +159,21 @@ pub struct PaymentParams {
     pub max_amount: U256,
 }
 
+/// Builder for [`PaymentParams`]
+///
+/// `gas_used`, `base_fee_per_gas`, `formula` and `max_amount` are required; the tip
+/// defaults to zero, `k1` to 1.0 and `k2` to zero.
+#[derive(Debug, Clone)]
+pub struct PaymentParamsBuilder {
+    gas_used: Option<u64>,
+    base_fee_per_gas: Option<U256>,
+    max_priority_fee_per_gas: U256,
+    formula: Option<PaymentFormula>,
+    k1: f64,
+    k2: U256,
+    max_amount: Option<U256>,
+}
+
 /// Payment calculation result
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PaymentResult {
@@ -80,6 +191,25 @@ pub struct PaymentResult {
     pub calculated_at: DateTime<Utc>,
 }
 
+/// Components of a payment calculation
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PaymentBreakdown {
+    /// Payment formula used
+    pub formula: PaymentFormula,
+    /// Gas-dependent part of the payment (`k1 * gas_used [* effective_gas_price]`)
+    pub gas_component: U256,
+    /// Constant part of the payment (`k2`)
+    pub base_component: U256,
+    /// Base fee plus tip per gas (basefee formula only)
+    pub effective_gas_price: Option<U256>,
+    /// Payment before `max_amount` is applied
+    pub pre_cap_amount: U256,
+    /// Payment after `max_amount` is applied
+    pub amount_wei: U256,
+    /// Whether the payment was capped
+    pub was_capped: bool,
+}
+
 /// Payment policy for spending limits
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PaymentPolicy {
@@ -132,6 +262,7 @@ impl PaymentFormula {
             "flat" => Ok(PaymentFormula::Flat),
             "gas" => Ok(PaymentFormula::Gas),
             "basefee" => Ok(PaymentFormula::Basefee),
+            "percentage" => Ok(PaymentFormula::Percentage),
             _ => Err(format!("Unknown payment formula: {}", s)),
         }
     }
@@ -142,6 +273,7 @@ impl PaymentFormula {
             PaymentFormula::Flat => "flat",
             PaymentFormula::Gas => "gas",
             PaymentFormula::Basefee => "basefee",
+            PaymentFormula::Percentage => "percentage",
         }
     }
 }
@@ -167,6 +299,84 @@ impl PaymentMode {
     }
 }
 
+impl PaymentParams {
+    /// Start building payment parameters
+    pub fn builder() -> PaymentParamsBuilder {
+        PaymentParamsBuilder {
+            gas_used: None,
+            base_fee_per_gas: None,
+            max_priority_fee_per_gas: U256::ZERO,
+            formula: None,
+            k1: 1.0,
+            k2: U256::ZERO,
+            max_amount: None,
+        }
+    }
+}
+
+impl PaymentParamsBuilder {
+    /// Gas used by the transaction (required)
+    pub fn gas_used(mut self, gas_used: u64) -> Self {
+        self.gas_used = Some(gas_used);
+        self
+    }
+
+    /// Base fee per gas in wei (required)
+    pub fn base_fee_per_gas(mut self, base_fee_per_gas: U256) -> Self {
+        self.base_fee_per_gas = Some(base_fee_per_gas);
+        self
+    }
+
+    /// Max priority fee per gas in wei (defaults to zero)
+    pub fn max_priority_fee_per_gas(mut self, max_priority_fee_per_gas: U256) -> Self {
+        self.max_priority_fee_per_gas = max_priority_fee_per_gas;
+        self
+    }
+
+    /// Payment formula (required)
+    pub fn formula(mut self, formula: PaymentFormula) -> Self {
+        self.formula = Some(formula);
+        self
+    }
+
+    /// Multiplier coefficient (defaults to 1.0)
+    pub fn k1(mut self, k1: f64) -> Self {
+        self.k1 = k1;
+        self
+    }
+
+    /// Base amount in wei (defaults to zero)
+    pub fn k2(mut self, k2: U256) -> Self {
+        self.k2 = k2;
+        self
+    }
+
+    /// Maximum allowed payment in wei (required)
+    pub fn max_amount(mut self, max_amount: U256) -> Self {
+        self.max_amount = Some(max_amount);
+        self
+    }
+
+    /// Take `k1`, `k2` and `max_amount` from the payment configuration
+    pub fn config(self, config: &PaymentConfig) -> Self {
+        self.k1(config.k1).k2(config.k2).max_amount(config.max_amount_wei)
+    }
+
+    /// Build the parameters, failing if a required field is missing
+    pub fn build(self) -> Result<PaymentParams, PaymentError> {
+        let missing = |field: &str| PaymentError::InvalidParameters(format!("{} is required", field));
+        Ok(PaymentParams {
+            gas_used: self.gas_used.ok_or_else(|| missing("gas_used"))?,
+            base_fee_per_gas: self.base_fee_per_gas.ok_or_else(|| missing("base_fee_per_gas"))?,
+            max_priority_fee_per_gas: self.max_priority_fee_per_gas,
+            formula: self.formula.ok_or_else(|| missing("formula"))?,
+            k1: self.k1,
+            k2: self.k2,
+            max_amount: self.max_amount.ok_or_else(|| missing("max_amount"))?,
+        })
+    }
+}
+
 impl PaymentResult {
     /// Create a new payment result
     pub fn new(
@@ -208,6 +418,22 @@ impl Default for PaymentConfig {
             max_amount_wei: U256::from(500_000_000_000_000u64), // 0.0005 ETH
             per_bundle_cap_wei: U256::from(2_000_000_000_000_000u64), // 0.002 ETH
             daily_cap_wei: U256::from(500_000_000_000_000_000u64), // 0.5 ETH
+            skip_balance_check: false,
+            tx2_priority_fee_multiplier: 0.0,
+            tx2_contract_gas_limit: default_tx2_contract_gas_limit(),
+            tx2_max_fee_multiplier: 0.0,
+            tx2_min_max_fee_gwei: 0.0,
+            tx2_type: Tx2Type::Eip1559,
+            signers: HashMap::new(),
+            low_payment_warning_blocks: 0,
+            low_payment_warning_ratio: default_low_payment_warning_ratio(),
+            warm_signer_nonces: default_warm_signer_nonces(),
+            split: PaymentSplit::Full,
+            strict_formula: false,
+            use_simulated_gas: false,
+            balance_grace_seconds: None,
+            balance_retry_interval_ms: default_balance_retry_interval_ms(),
+            private_tx_when_unpaid: false,
         }
     }
 }
@@ -223,3 +449,52 @@ impl Default for PaymentPolicy {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_payment_params_builder_matches_struct_literal() {
+        let config = PaymentConfig::default();
+        let params = PaymentParams::builder()
+            .gas_used(100_000)
+            .base_fee_per_gas(U256::from(20_000_000_000u64))
+            .max_priority_fee_per_gas(U256::from(1_000_000_000u64))
+            .formula(PaymentFormula::Gas)
+            .config(&config)
+            .build()
+            .unwrap();
+
+        let expected = PaymentParams {
+            gas_used: 100_000,
+            base_fee_per_gas: U256::from(20_000_000_000u64),
+            max_priority_fee_per_gas: U256::from(1_000_000_000u64),
+            formula: PaymentFormula::Gas,
+            k1: config.k1,
+            k2: config.k2,
+            max_amount: config.max_amount_wei,
+        };
+
+        assert_eq!(format!("{:?}", params), format!("{:?}", expected));
+    }
+
+    #[test]
+    fn test_payment_params_builder_requires_fields() {
+        let err = PaymentParams::builder()
+            .base_fee_per_gas(U256::from(1u64))
+            .formula(PaymentFormula::Flat)
+            .max_amount(U256::from(1u64))
+            .build()
+            .unwrap_err();
+        assert!(err.to_string().contains("gas_used"));
+
+        let err = PaymentParams::builder()
+            .gas_used(21_000)
+            .base_fee_per_gas(U256::from(1u64))
+            .formula(PaymentFormula::Flat)
+            .build()
+            .unwrap_err();
+        assert!(err.to_string().contains("max_amount"));
+    }
+}
@@ -3,6 +3,8 @@
 use alloy::primitives::{Address, TxHash};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use uuid::Uuid;
 
 /// Builder relay configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -22,8 +24,134 @@ pub struct BuilderRelay {
     pub timeout_seconds: u64,
     /// Maximum retries for failed requests
     pub max_retries: u32,
+    /// Upper bound on the delay between retries in milliseconds
+    #[serde(default = "default_max_backoff_ms")]
+    pub max_backoff_ms: u64,
     /// Health check interval in seconds
     pub health_check_interval_seconds: u64,
+    /// JSON pointer to the bundle hash in non-standard relay responses (optional)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response_result_path: Option<String>,
+    /// Include the bundle's replacement UUID as `uuid` in eth_sendBundle params
+    #[serde(default)]
+    pub send_bundle_uuid: bool,
+    /// Headers (name -> value) the relay is expected to send back on submission
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub expected_response_headers: HashMap<String, String>,
+    /// Fail the submission (instead of warning) when expected headers don't match
+    #[serde(default)]
+    pub strict_response_headers: bool,
+    /// Encoding of the JSON-RPC request id sent to this relay
+    #[serde(default)]
+    pub request_id_format: RequestIdFormat,
+    /// Maximum idle HTTP connections kept open to the relay
+    #[serde(default = "default_pool_max_idle_per_host")]
+    pub pool_max_idle_per_host: usize,
+    /// Seconds an idle pooled connection is kept before closing (0 = never expire)
+    #[serde(default = "default_pool_idle_timeout_seconds")]
+    pub pool_idle_timeout_seconds: u64,
+    /// TCP keepalive interval in seconds for relay connections (0 = disabled)
+    #[serde(default = "default_tcp_keepalive_seconds")]
+    pub tcp_keepalive_seconds: u64,
+    /// HTTP protocol version used to talk to the relay
+    #[serde(default)]
+    pub http_version: RelayHttpVersion,
+    /// Environment variable holding the key that signs relay requests (unsigned if unset)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auth_key_env: Option<String>,
+    /// How the request body is signed for the relay's signature header
+    #[serde(default)]
+    pub signature_scheme: RelaySignatureScheme,
+    /// Chain id sent as `chainId` in eth_sendBundle params (omitted if unset)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chain_id: Option<u64>,
+    /// Largest relay response body read before failing with `InvalidResponse`
+    #[serde(default = "default_max_relay_response_bytes")]
+    pub max_relay_response_bytes: usize,
+    /// Bundle API version sent as `version` in eth_sendBundle params (omitted if unset)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bundle_version: Option<String>,
+}
+
+fn default_max_relay_response_bytes() -> usize {
+    1024 * 1024
+}
+
+fn default_max_backoff_ms() -> u64 {
+    2_000
+}
+
+fn default_pool_max_idle_per_host() -> usize {
+    32
+}
+
+fn default_pool_idle_timeout_seconds() -> u64 {
+    90
+}
+
+fn default_tcp_keepalive_seconds() -> u64 {
+    60
+}
+
+/// How JSON-RPC request ids are encoded for a relay
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RequestIdFormat {
+    /// `"id": 42`
+    #[default]
+    Numeric,
+    /// `"id": "42"`, for relays that reject numeric ids
+    String,
+}
+
+/// HTTP protocol selection for relay connections
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RelayHttpVersion {
+    /// Negotiate HTTP/2 via TLS ALPN, falling back to HTTP/1.1
+    #[default]
+    Auto,
+    /// Always use HTTP/1.1
+    Http1,
+    /// Use HTTP/2 with prior knowledge (no negotiation, works over plain HTTP)
+    Http2,
+}
+
+/// Signature scheme for the relay request authentication header
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RelaySignatureScheme {
+    /// EIP-191 personal-sign of the hex-encoded keccak256 of the body (Flashbots)
+    #[default]
+    Eip191,
+    /// Sign the keccak256 of the body directly
+    RawKeccak,
+}
+
+/// JSON-RPC request id, either numeric or string
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum JsonRpcId {
+    /// Numeric id
+    Number(u64),
+    /// String id
+    String(String),
+}
+
+impl JsonRpcId {
+    /// Encode `id` according to `format`
+    pub fn with_format(id: u64, format: RequestIdFormat) -> Self {
+        match format {
+            RequestIdFormat::Numeric => JsonRpcId::Number(id),
+            RequestIdFormat::String => JsonRpcId::String(id.to_string()),
+        }
+    }
+}
+
+impl From<u64> for JsonRpcId {
+    fn from(id: u64) -> Self {
+        JsonRpcId::Number(id)
+    }
 }
 
 /// Bundle submission request to relay
@@ -32,13 +160,127 @@ pub struct RelayBundleRequest {
     /// JSON-RPC version
     pub jsonrpc: String,
     /// Request ID
-    pub id: u64,
+    pub id: JsonRpcId,
     /// Method name (eth_sendBundle)
     pub method: String,
     /// Request parameters
     pub params: Vec<RelayBundleParams>,
 }
 
+/// MEV-Share bundle submission request (`mev_sendBundle`, v0.1)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MevBundleRequest {
+    /// JSON-RPC version
+    pub jsonrpc: String,
+    /// Request ID
+    pub id: JsonRpcId,
+    /// Method name (mev_sendBundle)
+    pub method: String,
+    /// Request parameters
+    pub params: Vec<MevBundleParams>,
+}
+
+/// Parameters for mev_sendBundle request
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MevBundleParams {
+    /// Protocol version (`v0.1`)
+    pub version: String,
+    /// Blocks the bundle may be included in
+    pub inclusion: MevBundleInclusion,
+    /// Ordered bundle contents
+    pub body: Vec<MevBundleBodyItem>,
+    /// Refund requirements (optional)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub validity: Option<MevBundleValidity>,
+    /// Privacy settings (optional)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub privacy: Option<MevBundlePrivacy>,
+}
+
+/// Inclusion block range of a MEV-Share bundle
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MevBundleInclusion {
+    /// First block the bundle is valid for (hex)
+    pub block: String,
+    /// Last block the bundle is valid for (hex, optional)
+    #[serde(rename = "maxBlock", default, skip_serializing_if = "Option::is_none")]
+    pub max_block: Option<String>,
+}
+
+/// Item of a MEV-Share bundle body
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum MevBundleBodyItem {
+    /// Signed transaction
+    Tx {
+        /// Signed transaction hex
+        tx: String,
+        /// Whether the bundle stays valid if this transaction reverts
+        #[serde(rename = "canRevert")]
+        can_revert: bool,
+    },
+    /// Transaction already shared through MEV-Share, referenced by hash
+    Hash {
+        /// Transaction hash
+        hash: String,
+    },
+}
+
+/// Refund requirements of a MEV-Share bundle
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct MevBundleValidity {
+    /// Percent of the bundle's profit refunded to the signer of a body item
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub refund: Vec<MevBundleRefund>,
+    /// How the refund is split among addresses
+    #[serde(rename = "refundConfig", default, skip_serializing_if = "Vec::is_empty")]
+    pub refund_config: Vec<MevBundleRefundConfig>,
+}
+
+/// Refund owed to the signer of body item `body_idx`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MevBundleRefund {
+    /// Index of the body item
+    #[serde(rename = "bodyIdx")]
+    pub body_idx: u64,
+    /// Refund percent
+    pub percent: u64,
+}
+
+/// Share of the refund paid to `address`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MevBundleRefundConfig {
+    /// Refund recipient
+    pub address: Address,
+    /// Percent of the refund
+    pub percent: u64,
+}
+
+/// Privacy settings of a MEV-Share bundle
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct MevBundlePrivacy {
+    /// Bundle data shared with searchers
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub hints: Vec<MevShareHints>,
+    /// Builders allowed to receive the bundle (optional)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub builders: Option<Vec<String>>,
+}
+
+/// Transaction data revealed to searchers by MEV-Share
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MevShareHints {
+    /// Transaction calldata
+    Calldata,
+    /// Event logs
+    Logs,
+    /// Address of the called contract
+    ContractAddress,
+    /// Four-byte function selector
+    FunctionSelector,
+}
+
 /// Parameters for eth_sendBundle request
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RelayBundleParams {
@@ -56,6 +298,15 @@ pub struct RelayBundleParams {
     /// Reverting transaction hashes (optional)
     #[serde(rename = "revertingTxHashes", skip_serializing_if = "Option::is_none")]
     pub reverting_tx_hashes: Option<Vec<TxHash>>,
+    /// Replacement UUID used by some relays for deduplication (optional)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uuid: Option<String>,
+    /// Chain id (hex) required by some relays (optional)
+    #[serde(rename = "chainId", default, skip_serializing_if = "Option::is_none")]
+    pub chain_id: Option<String>,
+    /// Bundle API version required by newer Flashbots APIs (optional)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
 }
 
 /// Response from relay bundle submission
@@ -64,7 +315,7 @@ pub struct RelayBundleResponse {
     /// JSON-RPC version
     pub jsonrpc: String,
     /// Request ID
-    pub id: u64,
+    pub id: JsonRpcId,
     /// Result (bundle hash) or error
     #[serde(flatten)]
     pub result: RelayResult,
@@ -80,6 +331,52 @@ pub enum RelayResult {
     Error { error: RelayError },
 }
 
+/// A bundle accepted by a relay: the parsed bundle hash plus the raw JSON-RPC `result`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RelaySubmission {
+    /// Bundle hash extracted from the response
+    pub bundle_hash: String,
+    /// The response's `result` as sent, including relay-specific extras (e.g. `smart`
+    /// flags or simulation results); the whole response body if it had no `result`
+    pub raw_result: serde_json::Value,
+}
+
+/// Bundle progress reported by `flashbots_getBundleStats`; every field is `None` for unknown bundles
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleStats {
+    /// Whether the bundle was simulated
+    #[serde(default)]
+    pub is_simulated: Option<bool>,
+    /// Whether the bundle was forwarded to block producers
+    #[serde(default)]
+    pub is_sent_to_miners: Option<bool>,
+    /// Whether the bundle was treated as high priority
+    #[serde(default)]
+    pub is_high_priority: Option<bool>,
+    /// When the relay received the bundle
+    #[serde(default)]
+    pub received_at: Option<DateTime<Utc>>,
+    /// When the bundle was simulated
+    #[serde(default)]
+    pub simulated_at: Option<DateTime<Utc>>,
+    /// Builders that considered the bundle, and when
+    #[serde(default)]
+    pub considered_by_builders_at: Option<Vec<BuilderTimestamp>>,
+    /// Builders that sealed a block with the bundle, and when
+    #[serde(default)]
+    pub sealed_by_builders_at: Option<Vec<BuilderTimestamp>>,
+}
+
+/// When a builder (identified by its BLS public key) acted on a bundle
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BuilderTimestamp {
+    /// Builder public key
+    pub pubkey: String,
+    /// Time of the action
+    pub timestamp: DateTime<Utc>,
+}
+
 /// Relay error details
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RelayError {
@@ -105,6 +402,9 @@ pub enum RelayHealth {
     Unknown,
 }
 
+/// Consecutive failed health checks after which a degraded relay is considered unhealthy
+pub const UNHEALTHY_AFTER_FAILURES: u32 = 3;
+
 /// Relay health check result
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RelayHealthCheck {
@@ -186,10 +486,10 @@ pub struct RelayMetrics {
 
 impl RelayBundleRequest {
     /// Create a new bundle request; if block_number is None, omit it
-    pub fn new(id: u64, txs: Vec<String>, block_number: Option<u64>) -> Self {
+    pub fn new(id: impl Into<JsonRpcId>, txs: Vec<String>, block_number: Option<u64>) -> Self {
         Self {
             jsonrpc: "2.0".to_string(),
-            id,
+            id: id.into(),
             method: "eth_sendBundle".to_string(),
             params: vec![RelayBundleParams {
                 txs,
@@ -197,9 +497,78 @@ impl RelayBundleRequest {
                 min_timestamp: None,
                 max_timestamp: None,
                 reverting_tx_hashes: None,
+                uuid: None,
+                chain_id: None,
+                version: None,
+            }],
+        }
+    }
+
+    /// Attach a replacement UUID to the bundle params
+    pub fn with_uuid(mut self, uuid: Option<Uuid>) -> Self {
+        for params in &mut self.params {
+            params.uuid = uuid.map(|u| u.to_string());
+        }
+        self
+    }
+
+    /// Attach the chain id to the bundle params
+    pub fn with_chain_id(mut self, chain_id: Option<u64>) -> Self {
+        for params in &mut self.params {
+            params.chain_id = chain_id.map(|id| format!("0x{:x}", id));
+        }
+        self
+    }
+
+    /// Attach the bundle API version to the bundle params
+    pub fn with_version(mut self, version: Option<String>) -> Self {
+        for params in &mut self.params {
+            params.version = version.clone();
+        }
+        self
+    }
+}
+
+impl MevBundleRequest {
+    /// Create a v0.1 bundle request valid from `block` through `max_block` (if given)
+    pub fn new(
+        id: impl Into<JsonRpcId>,
+        body: Vec<MevBundleBodyItem>,
+        block: u64,
+        max_block: Option<u64>,
+    ) -> Self {
+        Self {
+            jsonrpc: "2.0".to_string(),
+            id: id.into(),
+            method: "mev_sendBundle".to_string(),
+            params: vec![MevBundleParams {
+                version: "v0.1".to_string(),
+                inclusion: MevBundleInclusion {
+                    block: format!("0x{:x}", block),
+                    max_block: max_block.map(|n| format!("0x{:x}", n)),
+                },
+                body,
+                validity: None,
+                privacy: None,
             }],
         }
     }
+
+    /// Attach refund requirements to the bundle params
+    pub fn with_validity(mut self, validity: MevBundleValidity) -> Self {
+        for params in &mut self.params {
+            params.validity = Some(validity.clone());
+        }
+        self
+    }
+
+    /// Attach privacy settings to the bundle params
+    pub fn with_privacy(mut self, privacy: MevBundlePrivacy) -> Self {
+        for params in &mut self.params {
+            params.privacy = Some(privacy.clone());
+        }
+        self
+    }
 }
 
 impl RelayHealthCheck {
@@ -224,13 +593,18 @@ impl RelayHealthCheck {
         self.consecutive_failures = 0;
     }
 
-    /// Mark as unhealthy with error message
-    pub fn mark_unhealthy(&mut self, error_message: String) {
-        self.status = RelayHealth::Unhealthy;
+    /// Record a failed check: degraded after one failure, unhealthy after
+    /// [`UNHEALTHY_AFTER_FAILURES`] consecutive ones
+    pub fn mark_failed(&mut self, error_message: String) {
+        self.consecutive_failures += 1;
+        self.status = if self.consecutive_failures >= UNHEALTHY_AFTER_FAILURES {
+            RelayHealth::Unhealthy
+        } else {
+            RelayHealth::Degraded
+        };
         self.response_time_ms = None;
         self.last_check = Utc::now();
         self.error_message = Some(error_message);
-        self.consecutive_failures += 1;
     }
 }
 
@@ -244,7 +618,22 @@ impl Default for BuilderRelay {
             enabled: true,
             timeout_seconds: 30,
             max_retries: 3,
+            max_backoff_ms: default_max_backoff_ms(),
             health_check_interval_seconds: 60,
+            response_result_path: None,
+            send_bundle_uuid: false,
+            expected_response_headers: HashMap::new(),
+            strict_response_headers: false,
+            request_id_format: RequestIdFormat::default(),
+            pool_max_idle_per_host: default_pool_max_idle_per_host(),
+            pool_idle_timeout_seconds: default_pool_idle_timeout_seconds(),
+            tcp_keepalive_seconds: default_tcp_keepalive_seconds(),
+            http_version: RelayHttpVersion::default(),
+            auth_key_env: None,
+            signature_scheme: RelaySignatureScheme::default(),
+            chain_id: None,
+            max_relay_response_bytes: default_max_relay_response_bytes(),
+            bundle_version: None,
         }
     }
 }
@@ -260,3 +649,92 @@ impl Default for SubmissionStatus {
         SubmissionStatus::Pending
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_id_encodings() {
+        let numeric = RelayBundleRequest::new(JsonRpcId::with_format(42, RequestIdFormat::Numeric), vec![], None);
+        let string = RelayBundleRequest::new(JsonRpcId::with_format(42, RequestIdFormat::String), vec![], None);
+
+        assert_eq!(serde_json::to_value(&numeric).unwrap()["id"], serde_json::json!(42));
+        assert_eq!(serde_json::to_value(&string).unwrap()["id"], serde_json::json!("42"));
+
+        let parsed: JsonRpcId = serde_json::from_str("\"42\"").unwrap();
+        assert_eq!(parsed, JsonRpcId::String("42".to_string()));
+    }
+
+    #[test]
+    fn test_mev_bundle_request_uses_spec_field_names() {
+        let request = MevBundleRequest::new(
+            7u64,
+            vec![
+                MevBundleBodyItem::Hash { hash: "0xaa".to_string() },
+                MevBundleBodyItem::Tx { tx: "0x02f8".to_string(), can_revert: false },
+            ],
+            100,
+            Some(102),
+        )
+        .with_validity(MevBundleValidity {
+            refund: vec![MevBundleRefund { body_idx: 0, percent: 90 }],
+            refund_config: vec![],
+        })
+        .with_privacy(MevBundlePrivacy {
+            hints: vec![MevShareHints::Calldata, MevShareHints::ContractAddress],
+            builders: None,
+        });
+
+        let value = serde_json::to_value(&request).unwrap();
+        assert_eq!(
+            value,
+            serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": 7,
+                "method": "mev_sendBundle",
+                "params": [{
+                    "version": "v0.1",
+                    "inclusion": { "block": "0x64", "maxBlock": "0x66" },
+                    "body": [
+                        { "hash": "0xaa" },
+                        { "tx": "0x02f8", "canRevert": false }
+                    ],
+                    "validity": { "refund": [{ "bodyIdx": 0, "percent": 90 }] },
+                    "privacy": { "hints": ["calldata", "contract_address"] }
+                }]
+            })
+        );
+
+        let round_tripped: MevBundleRequest = serde_json::from_value(value).unwrap();
+        assert_eq!(round_tripped, request);
+
+        let minimal = serde_json::to_value(MevBundleRequest::new(1u64, vec![], 100, None)).unwrap();
+        assert_eq!(minimal["params"][0]["inclusion"], serde_json::json!({ "block": "0x64" }));
+        assert!(minimal["params"][0].get("validity").is_none());
+        assert!(minimal["params"][0].get("privacy").is_none());
+    }
+
+    #[test]
+    fn test_chain_id_serialized_as_hex_only_when_set() {
+        let request = RelayBundleRequest::new(1u64, vec!["0x01".to_string()], Some(100));
+        let params = &serde_json::to_value(&request).unwrap()["params"][0];
+        assert!(params.get("chainId").is_none());
+
+        let request = request.with_chain_id(Some(11155111));
+        let params = &serde_json::to_value(&request).unwrap()["params"][0];
+        assert_eq!(params["chainId"], serde_json::json!("0xaa36a7"));
+        assert_eq!(params["blockNumber"], serde_json::json!("0x64"));
+    }
+
+    #[test]
+    fn test_version_serialized_only_when_set() {
+        let request = RelayBundleRequest::new(1u64, vec!["0x01".to_string()], Some(100));
+        let params = &serde_json::to_value(&request).unwrap()["params"][0];
+        assert!(params.get("version").is_none());
+
+        let request = request.with_version(Some("v0.1".to_string()));
+        let params = &serde_json::to_value(&request).unwrap()["params"][0];
+        assert_eq!(params["version"], serde_json::json!("v0.1"));
+    }
+}
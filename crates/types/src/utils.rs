@@ -13,6 +13,56 @@ pub fn string_to_u256(s: &str) -> Result<U256, String> {
     s.parse().map_err(|e| format!("Failed to parse U256: {}", e))
 }
 
+/// Parse a wei amount, either raw (`"2000000000000000"`, `"0x..."`) or with a unit suffix
+/// (`"0.002 eth"`, `"200 gwei"`, `"21000 wei"`).
+///
+/// Decimal amounts are converted exactly; fractions finer than one wei are rejected.
+pub fn parse_wei_amount(s: &str) -> Result<U256, String> {
+    let s = s.trim();
+    let Some((amount, unit)) = s.split_once(char::is_whitespace) else {
+        return string_to_u256(s);
+    };
+
+    let decimals: usize = match unit.trim().to_ascii_lowercase().as_str() {
+        "wei" => 0,
+        "gwei" => 9,
+        "eth" | "ether" => 18,
+        other => return Err(format!("Unknown unit {:?} in amount {:?}", other, s)),
+    };
+
+    let (integer, fraction) = amount.split_once('.').unwrap_or((amount, ""));
+    if fraction.len() > decimals {
+        return Err(format!("Amount {:?} is more precise than 1 wei", s));
+    }
+    if integer.is_empty() && fraction.is_empty()
+        || !integer.chars().chain(fraction.chars()).all(|c| c.is_ascii_digit())
+    {
+        return Err(format!("Invalid amount {:?}", s));
+    }
+
+    // Shift the decimal point right by the unit's decimals, e.g. 0.002 eth -> 2 followed by 15 zeros
+    let digits = format!("{}{}{}", integer, fraction, "0".repeat(decimals - fraction.len()));
+    U256::from_str_radix(&digits, 10).map_err(|e| format!("Invalid amount {:?}: {}", s, e))
+}
+
+/// Deserialize a wei amount accepted by [`parse_wei_amount`], or a plain integer
+pub fn deserialize_wei_amount<'de, D>(deserializer: D) -> Result<U256, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(serde::Deserialize)]
+    #[serde(untagged)]
+    enum WeiAmount {
+        Integer(u64),
+        Text(String),
+    }
+
+    match <WeiAmount as serde::Deserialize>::deserialize(deserializer)? {
+        WeiAmount::Integer(wei) => Ok(U256::from(wei)),
+        WeiAmount::Text(text) => parse_wei_amount(&text).map_err(serde::de::Error::custom),
+    }
+}
+
 /// Convert wei to ETH (as f64)
 pub fn wei_to_eth(wei: U256) -> f64 {
     if wei == U256::ZERO {
@@ -55,6 +105,11 @@ pub fn generate_correlation_id() -> String {
     uuid::Uuid::new_v4().to_string()
 }
 
+/// Time left from `now` until `expires_at`, zero once it has passed
+pub fn time_to_expiry(expires_at: DateTime<Utc>, now: DateTime<Utc>) -> std::time::Duration {
+    (expires_at - now).to_std().unwrap_or(std::time::Duration::ZERO)
+}
+
 /// Calculate time difference in milliseconds
 pub fn time_diff_ms(start: DateTime<Utc>, end: DateTime<Utc>) -> i64 {
     end.timestamp_millis() - start.timestamp_millis()
@@ -145,6 +200,38 @@ mod tests {
         assert_eq!(eth_to_wei(1.0), one_eth);
     }
 
+    #[test]
+    fn test_parse_wei_amount_units() {
+        assert_eq!(parse_wei_amount("2000000000000000").unwrap(), U256::from(2_000_000_000_000_000u64));
+        assert_eq!(parse_wei_amount("0.002 eth").unwrap(), U256::from(2_000_000_000_000_000u64));
+        assert_eq!(parse_wei_amount("1 ETH").unwrap(), U256::from(10u64.pow(18)));
+        assert_eq!(parse_wei_amount("0.5 ether").unwrap(), U256::from(5 * 10u64.pow(17)));
+        assert_eq!(parse_wei_amount("200 gwei").unwrap(), U256::from(200_000_000_000u64));
+        assert_eq!(parse_wei_amount("1.5 gwei").unwrap(), U256::from(1_500_000_000u64));
+        assert_eq!(parse_wei_amount("21000 wei").unwrap(), U256::from(21_000u64));
+        assert_eq!(parse_wei_amount(" .25 eth ").unwrap(), U256::from(25 * 10u64.pow(16)));
+
+        assert!(parse_wei_amount("0.5 wei").is_err());
+        assert!(parse_wei_amount("1 finney").is_err());
+        assert!(parse_wei_amount("-1 eth").is_err());
+        assert!(parse_wei_amount("lots").is_err());
+    }
+
+    #[test]
+    fn test_deserialize_wei_amount() {
+        #[derive(serde::Deserialize)]
+        struct Amounts {
+            #[serde(deserialize_with = "deserialize_wei_amount")]
+            text: U256,
+            #[serde(deserialize_with = "deserialize_wei_amount")]
+            integer: U256,
+        }
+
+        let amounts: Amounts = serde_json::from_str(r#"{"text": "0.0005 eth", "integer": 21000}"#).unwrap();
+        assert_eq!(amounts.text, U256::from(500_000_000_000_000u64));
+        assert_eq!(amounts.integer, U256::from(21_000u64));
+    }
+
     #[test]
     fn test_address_validation() {
         assert!(is_valid_address("0x1234567890123456789012345678901234567890"));